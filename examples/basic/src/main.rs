@@ -1,6 +1,5 @@
-use error_iter::ErrorIter as _;
 use onlyargs::{CliError, OnlyArgs};
-use std::{ffi::OsString, process::ExitCode};
+use std::ffi::OsString;
 
 #[derive(Debug)]
 struct Args {
@@ -22,7 +21,7 @@ impl OnlyArgs for Args {
                     verbose = true;
                 }
                 Some("--") => break,
-                _ => return Err(CliError::Unknown(arg)),
+                _ => return Err(CliError::Unknown { argument: arg }),
             }
         }
 
@@ -30,30 +29,12 @@ impl OnlyArgs for Args {
     }
 }
 
-fn run() -> Result<(), CliError> {
-    let args: Args = onlyargs::parse()?;
+fn main() {
+    let args: Args = onlyargs::parse_or_exit();
 
     println!("Arguments parsed successfully!");
 
     if args.verbose {
         println!("Verbose output is enabled");
     }
-
-    Ok(())
-}
-
-fn main() -> ExitCode {
-    match run() {
-        Ok(_) => ExitCode::SUCCESS,
-        Err(err) => {
-            eprintln!("{}", Args::HELP);
-
-            eprintln!("Error: {err}");
-            for source in err.sources().skip(1) {
-                eprintln!("  Caused by: {source}");
-            }
-
-            ExitCode::FAILURE
-        }
-    }
 }
@@ -75,7 +75,7 @@ impl OnlyArgs for Args {
                 Some(_) => {
                     numbers.push(arg.parse_int("<POSITIONAL>")?);
                 }
-                None => return Err(onlyargs::CliError::Unknown(arg)),
+                None => return Err(onlyargs::CliError::Unknown { argument: arg }),
             }
         }
 
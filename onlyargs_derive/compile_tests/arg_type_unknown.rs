@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[arg_type(duration)]
+    timeout: String,
+}
+
+fn main() {}
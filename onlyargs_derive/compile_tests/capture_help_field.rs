@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+#[capture_help]
+struct Args {
+    verbose: bool,
+}
+
+fn main() {}
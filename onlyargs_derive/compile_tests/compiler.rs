@@ -53,6 +53,10 @@ fn compile_tests() {
     t.pass("compile_tests/manual_short_name.rs");
     t.pass("compile_tests/ignore_short_name.rs");
 
+    t.pass("compile_tests/env.rs");
+    t.pass("compile_tests/env_prefix.rs");
+    t.pass("compile_tests/dotenv.rs");
+
     // Various expected errors.
     t.compile_fail("compile_tests/required_bool.rs");
     t.compile_fail("compile_tests/required_option.rs");
@@ -60,7 +64,24 @@ fn compile_tests() {
     t.compile_fail("compile_tests/default_multivalue.rs");
     t.compile_fail("compile_tests/default_option.rs");
     t.compile_fail("compile_tests/default_positional.rs");
+    t.compile_fail("compile_tests/default_cstring.rs");
+    t.compile_fail("compile_tests/default_url.rs");
+    t.compile_fail("compile_tests/default_uuid.rs");
+    t.compile_fail("compile_tests/default_version.rs");
     t.compile_fail("compile_tests/positional_option.rs");
     t.compile_fail("compile_tests/positional_single_bool.rs");
     t.compile_fail("compile_tests/positional_single_string.rs");
+    t.compile_fail("compile_tests/env_bool.rs");
+    t.compile_fail("compile_tests/env_multivalue.rs");
+    t.compile_fail("compile_tests/config_path_type.rs");
+    t.compile_fail("compile_tests/origins_type.rs");
+    t.compile_fail("compile_tests/possible_values_int.rs");
+    t.compile_fail("compile_tests/help_template_placeholder.rs");
+    t.compile_fail("compile_tests/homepage_missing.rs");
+    t.compile_fail("compile_tests/capture_help_field.rs");
+    t.compile_fail("compile_tests/misplaced_attribute.rs");
+    t.compile_fail("compile_tests/arg_type_unknown.rs");
+    t.compile_fail("compile_tests/deny_missing_docs.rs");
+    t.compile_fail("compile_tests/tuple_order.rs");
+    t.compile_fail("compile_tests/flag_enum_short.rs");
 }
@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[config_path]
+    config: String,
+}
+
+fn main() {}
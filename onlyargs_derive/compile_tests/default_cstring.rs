@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[default("eth\0")]
+    interface: std::ffi::CString,
+}
+
+fn main() {}
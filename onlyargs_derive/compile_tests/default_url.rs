@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[default("example.com")]
+    endpoint: url::Url,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[default("67e55044-10b1-426f")]
+    id: uuid::Uuid,
+}
+
+fn main() {}
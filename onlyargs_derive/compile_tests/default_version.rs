@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[default("1.0")]
+    min_version: semver::Version,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+#[deny_missing_docs]
+struct Args {
+    /// Enable verbose output.
+    verbose: bool,
+    #[help("Set the width.")]
+    width: Option<u32>,
+    #[hide]
+    debug: bool,
+    output: Option<std::path::PathBuf>,
+}
+
+fn main() {}
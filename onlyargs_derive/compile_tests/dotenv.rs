@@ -0,0 +1,10 @@
+#![deny(unused_variables)]
+
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+#[dotenv]
+#[config_file("app.toml")]
+struct Args {
+    verbose: bool,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[env("USERNAME")]
+    username: String,
+
+    #[env("WIDTH")]
+    #[default(42)]
+    width: i32,
+
+    #[env("OUTPUT")]
+    output: Option<std::path::PathBuf>,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[env("VERBOSE")]
+    verbose: bool,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[env("NAMES")]
+    names: Vec<String>,
+}
+
+fn main() {}
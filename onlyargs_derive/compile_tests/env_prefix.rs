@@ -0,0 +1,13 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+#[env_prefix("MYAPP_")]
+struct Args {
+    log_level: String,
+    output: Option<std::path::PathBuf>,
+    names: Vec<String>,
+    verbose: bool,
+
+    #[positional]
+    rest: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+#[derive(Debug, Default)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[flag_enum(Json)]
+    #[short('j')]
+    format: Format,
+}
+
+fn main() {}
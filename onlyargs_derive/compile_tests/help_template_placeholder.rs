@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+#[help_template("{name}\n{arguments}")]
+struct Args {
+    verbose: bool,
+}
+
+fn main() {}
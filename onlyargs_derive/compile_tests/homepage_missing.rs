@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+#[homepage]
+struct Args {
+    verbose: bool,
+}
+
+fn main() {}
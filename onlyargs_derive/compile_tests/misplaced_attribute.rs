@@ -0,0 +1,8 @@
+/// Misplaced attribute.
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+#[short('x')]
+struct Args {
+    width: Option<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[origins]
+    origins: Vec<String>,
+}
+
+fn main() {}
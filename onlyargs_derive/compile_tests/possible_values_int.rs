@@ -0,0 +1,7 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args {
+    #[possible_values("1", "2")]
+    level: i32,
+}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[derive(Debug, onlyargs_derive::OnlyArgs)]
+struct Args(Option<String>, std::path::PathBuf);
+
+fn main() {}
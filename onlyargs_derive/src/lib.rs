@@ -41,7 +41,8 @@
 //!
 //! # DSL reference
 //!
-//! Only structs with named fields are supported. Doc comments are used for the generated help text.
+//! Structs with named fields are supported, as well as [tuple structs](#tuple-structs) with only
+//! positional arguments. Doc comments are used for the generated help text.
 //! Argument names are generated automatically from field names with only a few rules:
 //!
 //! - Long argument names start with `--`, ASCII alphabetic characters are made lowercase, and all
@@ -51,20 +52,534 @@
 //!   - This behavior can be suppressed with the `#[long]` attribute (see below).
 //!   - Alternatively, the `#[short('…')]` attribute can be used to set a specific short name.
 //!
-//! # Footer
+//! # Usage
 //!
-//! The `#[footer = "..."]` attribute on the argument struct will add lines to the bottom of the
-//! help message. It can be used multiple times.
+//! The usage line lists required options and positional arguments by name, e.g.
+//! `myapp --username <STRING> [flags] [options] <files>...`. The `#[usage("...")]` attribute on
+//! the argument struct replaces it with a custom line. It can be used multiple times, and
+//! `{bin_name}` is replaced with the name of the binary.
+//!
+//! The usage line is also available as `OnlyArgs::USAGE`. `OnlyArgs::format_error()` and
+//! `OnlyArgs::exit_with_error()` show it along with argument parsing errors.
+//!
+//! # Help order
+//!
+//! Arguments are listed in the help text in declaration order. The `#[sort_help]` attribute on the
+//! argument struct sorts them alphabetically instead. Either way, arguments with the
+//! `#[display_order(n)]` field attribute are listed first.
+//!
+//! # Examples
+//!
+//! The `#[example("...")]` attribute on the argument struct adds a line to the "Examples:" section
+//! of the help message, following the argument lists. It can be used multiple times, and
+//! `{bin_name}` is replaced with the name of the binary.
+//!
+//! # Tuple structs
+//!
+//! Each field of a tuple struct is a positional argument, filled in order. Fields of type `T` are
+//! required, `Option<T>` fields are optional and must follow the required fields, and a `Vec<T>`
+//! field takes the remaining values and must be the last field. Values beginning with `-` can
+//! follow `--`.
+//!
+//! The arguments are named by their value name, like `<PATH>`, in the usage line and the
+//! "Arguments:" help section. Fields of the same type need distinct names from
+//! `#[value_name(...)]`. Only doc comments and the `#[help(...)]`, `#[value_name(...)]`,
+//! `#[arg_type(...)]`, and `#[complete(...)]` attributes can be used on the fields.
+//!
+//! ```
+//! # use onlyargs::OnlyArgs as _;
+//! use onlyargs_derive::OnlyArgs;
+//! use std::path::PathBuf;
+//!
+//! /// Copy a file.
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args(
+//!     /// File to copy.
+//!     #[value_name("SRC")]
+//!     PathBuf,
+//!     /// Destination path.
+//!     #[value_name("DEST")]
+//!     PathBuf,
+//! );
+//!
+//! assert!(Args::USAGE.ends_with(" <SRC> <DEST>"));
+//!
+//! let args = Args::parse(vec!["a.txt".into(), "b.txt".into()])?;
+//!
+//! assert_eq!(args.1, PathBuf::from("b.txt"));
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! # Missing docs
+//!
+//! Fields without a doc comment have an empty description in the help text. The
+//! `#[deny_missing_docs]` attribute on the argument struct makes them a compile error instead, so
+//! the help stays complete as arguments are added. Fields with `#[help(...)]` or `#[hide]` are
+//! fine without a doc comment.
+//!
+//! # Required options
+//!
+//! Required options are marked with `[required]` in the help text. The `#[group_required]`
+//! attribute on the argument struct lists them in a "Required:" section before the flags instead,
+//! so the minimum invocation is easy to spot. Options with `#[help_heading(...)]` stay in their own
+//! section.
+//!
+//! # Help template
+//!
+//! The `#[help_template("...")]` attribute on the argument struct rearranges the help text. The
+//! template can use these placeholders:
+//!
+//! - `{name}`, `{version}`, `{description}`: Package metadata from `Cargo.toml`, or the
+//!   `#[name(...)]` and `#[version(...)]` attributes.
+//! - `{authors}`, `{homepage}`, `{repository}`: Package metadata from `Cargo.toml`, or the
+//!   attributes of the same name.
+//! - `{links}`: The lines added by `#[authors]`, `#[homepage]`, and `#[repository]`.
+//! - `{bin_name}`: The name of the binary, replaced at runtime with `onlyargs::bin_name()`.
+//! - `{header}`, `{doc}`, `{footer}`: The header, struct doc comment, and footer. Each is
+//!   surrounded by line breaks, or empty when it is not given.
+//! - `{usage}`: The usage line.
+//! - `{required}`: The section for required options with `#[group_required]`, including its
+//!   title.
+//! - `{flags}`, `{options}`: The argument lists, one argument per line.
+//! - `{headings}`: The sections for `#[help_heading(...)]` arguments, including their titles.
+//! - `{positional}`: The positional argument section, including its title.
+//! - `{examples}`: The examples section, including its title.
+//!
+//! ```
+//! use onlyargs::OnlyArgs as _;
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! #[help_template("{name} {version}\nUSAGE: {usage}\n\nOPTIONS:\n{flags}{options}")]
+//! struct Args {
+//!     /// Enable verbose output.
+//!     verbose: bool,
+//! }
+//!
+//! assert!(Args::HELP.contains("\nOPTIONS:\n  -h --help     Show this help message.\n"));
+//! ```
+//!
+//! # Name and version
+//!
+//! The application name and version in the help and version messages are read from the
+//! `CARGO_PKG_NAME` and `CARGO_PKG_VERSION` environment variables of the crate deriving
+//! `OnlyArgs`. The `#[name("mytool")]` and `#[version("2.1.0-beta")]` attributes on the argument
+//! struct override them, e.g. for binaries that are renamed or built from a workspace crate with a
+//! different name.
+//!
+//! The `#[long_version(...)]` attribute adds lines to the version message, following the name and
+//! version. Its arguments are passed to [`concat!()`], so they can be string literals and macros
+//! like [`env!()`]. Build metadata like the git commit or target triple can be provided by a build
+//! script with `cargo:rustc-env`:
+//!
+//! ```
+//! use onlyargs::OnlyArgs as _;
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! #[name("mytool")]
+//! #[version("1.0.0")]
+//! #[long_version("license: ", env!("CARGO_PKG_LICENSE"))]
+//! struct Args {}
+//!
+//! assert_eq!(Args::VERSION, "mytool v1.0.0\nlicense: MIT\n");
+//! assert_eq!(Args::SHORT_VERSION, "mytool v1.0.0\n");
+//! ```
+//!
+//! `--version` prints the full version message, and `-V` only prints the name and version.
+//!
+//! # Authors and links
+//!
+//! The `#[authors]`, `#[homepage]`, and `#[repository]` attributes on the argument struct add lines
+//! with the package authors and links below the description in the help message. The values are
+//! read from `Cargo.toml`, or can be given explicitly, e.g. `#[homepage("https://example.com")]`.
+//!
+//! # Header and footer
+//!
+//! The `#[header = "..."]` attribute on the argument struct will add lines between the version
+//! banner and the rest of the help message, which is useful for warnings or links. The
+//! `#[footer = "..."]` attribute will add lines to the bottom of the help message. Both can be used
+//! multiple times.
+//!
+//! Longer text can be kept in a file with `#[header(include = "path")]` or
+//! `#[footer(include = "path")]`. The path is relative to the crate root (the directory containing
+//! `Cargo.toml`). Like `include_str!()`, the file is embedded at compile time and the crate is
+//! rebuilt when it changes.
+//!
+//! # Environment prefix
+//!
+//! The `#[env_prefix("MYAPP_")]` attribute on the argument struct gives every option an
+//! environment variable fallback (as if `#[env(...)]` was used on each field). The variable name is
+//! the prefix followed by the field name in uppercase. For example, the `log_level` field is read
+//! from `MYAPP_LOG_LEVEL`. An explicit `#[env(...)]` on a field takes precedence over the prefix.
+//! Flags, multivalue, and positional arguments are not affected.
+//!
+//! # `.env` file
+//!
+//! The `#[dotenv]` attribute on the argument struct loads a `.env` file from the current working
+//! directory before environment variable fallbacks are read. A different path can be given with
+//! `#[dotenv("path/to/.env")]`. Variables in the process environment take precedence over the
+//! file, and the file is allowed to be missing. This requires enabling the `dotenv` feature on the
+//! `onlyargs` crate. See the [`onlyargs::dotenv`] module for the supported syntax.
+//!
+//! [`onlyargs::dotenv`]: https://docs.rs/onlyargs/latest/onlyargs/dotenv/index.html
+//!
+//! # Config file
+//!
+//! The `#[config_file("app.toml")]` attribute on the argument struct loads a configuration file
+//! that fills in any options that were not provided on the command line. This requires enabling
+//! the `toml` or `json` feature on the `onlyargs` crate. Files with a `.json` extension are parsed
+//! as JSON, and all others are parsed as TOML. See the [`onlyargs::config`] module for the
+//! supported formats.
+//!
+//! - Keys in the file mirror the field names, e.g. `log_level = "debug"`.
+//! - Arrays are used for multivalue arguments, e.g. `names = ["Alice", "Bob"]`.
+//! - The path is relative to the current working directory, and the file is allowed to be missing.
+//!
+//! The `#[config_path]` attribute on a `PathBuf` or `Option<PathBuf>` field lets users choose the
+//! config file on the command line, e.g. `--config app.toml`. The command line is scanned for this
+//! argument after `#[preprocess(...)]` and before the full parse, skipping the values of other
+//! options. The named file fills in options underneath the command line
+//! and environment variables. It takes precedence over `#[config_file(...)]`. A file named on the
+//! command line or by an environment variable must exist, but a `#[default(...)]` path is allowed
+//! to be missing.
+//!
+//! [`onlyargs::config`]: https://docs.rs/onlyargs/latest/onlyargs/config/index.html
+//!
+//! # Layered sources
+//!
+//! Options that are not provided on the command line are looked up in the layers returned by
+//! `OnlyArgs::layers()`. Values are used in this order of precedence: command line, environment
+//! variable, `.env` file, config file, `#[default(T)]`.
+//!
+//! Applications can add their own layers and parse with `OnlyArgs::parse_with_layers()`. See the
+//! [`onlyargs::source`] module for details.
+//!
+//! [`onlyargs::source`]: https://docs.rs/onlyargs/latest/onlyargs/source/index.html
+//!
+//! # Value provenance
+//!
+//! A field of type `onlyargs::source::Origins` marked with `#[origins]` records where each
+//! argument's value came from: the command line, the environment, a config file, or the default.
+//! This field is not an argument itself.
+//!
+//! ```
+//! use onlyargs::source::{Origin, Origins};
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args {
+//!     verbose: bool,
+//!
+//!     #[origins]
+//!     origins: Origins,
+//! }
+//!
+//! let args: Args = onlyargs::parse()?;
+//!
+//! if args.origins.source_of("verbose") == Some(Origin::Cli) {
+//!     eprintln!("Verbose output was enabled on the command line.");
+//! }
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! The derive macro also generates a `show_config(&self) -> String` method, which formats a table
+//! of every argument's effective value and, when the struct has an `#[origins]` field, where the
+//! value came from. Printing it helps debug which layer set a value.
+//!
+//! # Error collection
+//!
+//! The parser returns the first error it finds by default. The `#[collect_errors]` attribute on the
+//! argument struct makes it keep going instead, and return every error at once as
+//! `CliError::Multiple`. Users can then fix all of their mistakes in one go.
+//!
+//! ```
+//! use onlyargs::{CliError, OnlyArgs as _};
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! #[collect_errors]
+//! struct Args {
+//!     width: Option<i32>,
+//!     depth: Option<i32>,
+//! }
+//!
+//! let err = Args::parse(vec![
+//!     "--width".into(),
+//!     "wide".into(),
+//!     "--depth".into(),
+//!     "deep".into(),
+//! ])
+//! .unwrap_err();
+//!
+//! assert!(matches!(err, CliError::Multiple { errors } if errors.len() == 2));
+//! ```
+//!
+//! # Preprocessing
+//!
+//! The `#[preprocess(path)]` attribute on the argument struct rewrites the command line arguments
+//! with the function at `path` before they are parsed. The function has the signature
+//! `fn(Vec<OsString>) -> Result<Vec<OsString>, CliError>`. This is useful for inserting extra
+//! arguments or replacing renamed arguments:
+//!
+//! ```
+//! use onlyargs::{CliError, OnlyArgs as _};
+//! use onlyargs_derive::OnlyArgs;
+//! use std::ffi::OsString;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! #[preprocess(rename_args)]
+//! struct Args {
+//!     verbose: bool,
+//! }
+//!
+//! fn rename_args(args: Vec<OsString>) -> Result<Vec<OsString>, CliError> {
+//!     // `--debug` was renamed to `--verbose`.
+//!     Ok(args
+//!         .into_iter()
+//!         .map(|arg| if arg == "--debug" { "--verbose".into() } else { arg })
+//!         .collect())
+//! }
+//!
+//! assert!(Args::parse(vec!["--debug".into()])?.verbose);
+//! # Ok::<_, CliError>(())
+//! ```
+//!
+//! # Validation
+//!
+//! The `#[validate(path)]` attribute on the argument struct checks the parsed arguments with the
+//! function at `path`, which has the signature `fn(&Self) -> Result<(), CliError>`. It is called
+//! after all arguments are parsed, so it can check arguments against each other.
+//!
+//! ```
+//! use onlyargs::{CliError, OnlyArgs as _};
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! #[validate(Args::check_range)]
+//! struct Args {
+//!     #[default(0)]
+//!     start: i32,
+//!     #[default(100)]
+//!     end: i32,
+//! }
+//!
+//! impl Args {
+//!     fn check_range(&self) -> Result<(), CliError> {
+//!         if self.start > self.end {
+//!             return Err(CliError::custom("--start", "must not be after `--end`"));
+//!         }
+//!
+//!         Ok(())
+//!     }
+//! }
+//!
+//! assert!(Args::parse(vec!["--start".into(), "200".into()]).is_err());
+//! ```
+//!
+//! # Partial parsing
+//!
+//! `OnlyArgs::parse_partial()` stops at the first unknown argument, and returns it along with the
+//! remaining arguments. Arguments after `--` are returned too. This does not apply to structs with
+//! a `#[positional]` field, which takes every argument that is not recognized.
+//!
+//! # Shell completions
+//!
+//! `OnlyArgs::SPEC` describes every argument that is not hidden, with its names, value type,
+//! default value, description, and whether it is required. Completion scripts and documentation are
+//! generated from it, and it is also useful for test assertions.
+//! `OnlyArgs::completions()` generates a zsh, fish, or Nushell completion script.
+//! Options that take a `PathBuf` complete file names. See [`onlyargs::completion`].
+//!
+//! The `#[complete(...)]` attribute on an option or positional argument changes what is completed
+//! for its value:
+//!
+//! - `#[complete(file)]`: File names.
+//! - `#[complete(dir)]`: Directory names.
+//! - `#[complete(hostname)]`: Host names.
+//! - `#[complete(fn = path)]`: The candidates returned by the function at `path`, which has the
+//!   signature `fn(&str) -> Vec<String>`. It is called with the word being completed. The completion
+//!   script asks the binary for candidates, which `onlyargs::parse()` answers before parsing.
+//!
+//! ```
+//! # use onlyargs_derive::OnlyArgs;
+//! fn branches(word: &str) -> Vec<String> {
+//!     ["main", "next"]
+//!         .iter()
+//!         .filter(|name| name.starts_with(word))
+//!         .map(ToString::to_string)
+//!         .collect()
+//! }
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args {
+//!     /// The branch to check out.
+//!     #[complete(fn = branches)]
+//!     branch: Option<String>,
+//! }
+//! ```
+//!
+//! The `#[generate]` attribute on the argument struct adds a hidden `--generate` argument, so
+//! packagers can produce artifacts from the shipped binary. `onlyargs::parse()` handles it before
+//! parsing, prints the artifact, and exits. It must be the first argument:
+//!
+//! - `--generate completions <SHELL>`: A zsh, fish, or Nushell completion script.
+//! - `--generate man`: A man page.
+//! - `--generate markdown`: A Markdown reference.
+//! - `--generate json`: A JSON spec for external tools, also available from `OnlyArgs::spec_json()`.
+//!
+//! See [`onlyargs::generate`].
+//!
+//! With the `clap` feature on the `onlyargs` crate, `OnlyArgs::clap_command()` constructs an
+//! equivalent `clap::Command`, for tools from the clap ecosystem like `clap_complete` and
+//! `clap_mangen`. See [`onlyargs::clap`].
+//!
+//! [`onlyargs::clap`]: https://docs.rs/onlyargs/latest/onlyargs/clap/index.html
+//! [`onlyargs::completion`]: https://docs.rs/onlyargs/latest/onlyargs/completion/index.html
+//! [`onlyargs::generate`]: https://docs.rs/onlyargs/latest/onlyargs/generate/index.html
+//!
+//! # Reconstructing the command line
+//!
+//! The derive macro also generates a `to_args(&self) -> Vec<OsString>` method, which produces a
+//! command line that parses back into the same values. This is useful for re-executing the
+//! application or spawning workers with modified options. Options use their long names, and
+//! positional arguments follow `--`.
+//!
+//! ```
+//! # use onlyargs::OnlyArgs as _;
+//! # use onlyargs_derive::OnlyArgs;
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args {
+//!     verbose: bool,
+//!     width: Option<u32>,
+//! }
+//!
+//! let mut args = Args::parse(vec!["-v".into()])?;
+//! args.width = Some(3);
+//!
+//! assert_eq!(args.to_args(), ["--verbose", "--width", "3"]);
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! # Default implementation
+//!
+//! The `#[impl_default]` attribute on the argument struct generates `impl Default` with the values
+//! that parsing an empty command line would produce: the `#[default(...)]` value, `false` for
+//! flags, `None` for optional options, and empty lists for multivalue options. This constructs
+//! baseline settings without going through the command line. Every required option needs a
+//! `#[default(...)]`.
+//!
+//! ```
+//! # use onlyargs_derive::OnlyArgs;
+//! #[derive(Debug, OnlyArgs)]
+//! #[impl_default]
+//! struct Args {
+//!     verbose: bool,
+//!     #[default(80)]
+//!     width: u32,
+//!     name: Option<String>,
+//! }
+//!
+//! let args = Args::default();
+//!
+//! assert_eq!(args.width, 80);
+//! ```
 //!
 //! # Provided arguments
 //!
 //! `--help|-h` and `--version|-V` arguments are automatically generated. When the parser encounters
 //! either, it will print the help or version message and exit the application with exit code 0.
+//! The messages are printed to `stdout`, or to `stderr` with the `#[help_stderr]` attribute on the
+//! argument struct.
+//!
+//! The `#[help_exit_code(n)]` attribute on the argument struct changes the exit code used after
+//! printing the help or version message. Similarly, `#[error_exit_code(n)]` changes the exit code
+//! used by `OnlyArgs::exit_with_error()` for argument parsing errors, which defaults to `2`. Use
+//! `64` to follow the `EX_USAGE` convention from `sysexits.h`.
+//! The help message is wrapped to the width of the terminal. See [`onlyargs::help`].
+//!
+//! The `#[help_width(n)]` attribute on the argument struct wraps the help message to a fixed width
+//! of `n` columns instead. This is useful when the terminal width is not detected correctly, e.g.
+//! in CI logs or when generating documentation. Lines that fit within the width are never changed.
+//!
+//! The `#[plain_help]` attribute on the argument struct always prints the help message in a plain
+//! format, with one `name: description` pair per line and no column alignment. This works better
+//! with screen readers and `grep`. Users can also request the plain format by setting the
+//! `ONLYARGS_PLAIN_HELP` environment variable. See [`onlyargs::help::plain`].
+//!
+//! The `#[pager]` attribute on the argument struct shows help messages that are taller than the
+//! terminal in a pager, like `less`. This requires enabling the `pager` feature on the `onlyargs`
+//! crate. See [`onlyargs::help::page`].
+//!
+//! `OnlyArgs::try_parse()` returns these arguments as a `ParseResult` instead of exiting.
+//!
+//! The `#[capture_help]` and `#[capture_version]` attributes on the argument struct parse these
+//! arguments into `help: bool` and `version: bool` fields instead of printing the messages and
+//! exiting. The fields are declared like any other flag, so the application decides when and where
+//! to print the messages:
+//!
+//! ```
+//! use onlyargs::OnlyArgs as _;
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! #[capture_help]
+//! struct Args {
+//!     /// Show this help message.
+//!     help: bool,
+//! }
+//!
+//! let args = Args::parse(vec!["--help".into()])?;
+//!
+//! if args.help {
+//!     println!("{}", Args::HELP);
+//! }
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! Required arguments and `#[validate(path)]` are not checked when a captured flag is set. Missing
+//! required values are filled with `Default::default()`, so their types must implement `Default`.
+//!
+//! `-h` prints a condensed help message with only the first line of each argument's description,
+//! and `--help` prints the full descriptions.
+//! Likewise, `-V` prints a condensed version message without the `#[long_version(...)]` lines.
+//!
+//! [`onlyargs::help`]: https://docs.rs/onlyargs/latest/onlyargs/help/index.html
+//! [`onlyargs::help::page`]: https://docs.rs/onlyargs/latest/onlyargs/help/fn.page.html
+//! [`onlyargs::help::plain`]: https://docs.rs/onlyargs/latest/onlyargs/help/fn.plain.html
 //!
 //! # Field attributes
 //!
 //! Parsing options are configurable with the following attributes:
 //!
+//! - `#[help("Description")]`: Use this description in the help text instead of the doc comment.
+//!   The doc comment is still used for API docs. It can be used multiple times for multiple lines.
+//! - `#[verbatim_doc_comment]`: Use the doc comment in the help text exactly as written, without
+//!   trimming trailing whitespace. Block doc comments keep their line breaks. This is useful for
+//!   preformatted text like tables. It can also be used on the argument struct. Combine it with
+//!   `#[help_width(n)]` to keep wide text from being wrapped to the terminal width.
+//! - `#[help_heading("Network options")]`: List the argument under a custom heading in the help
+//!   text, instead of "Flags" or "Options". Flags and options can share a heading, and headings
+//!   are listed in the order they first appear.
+//! - `#[alias("name")]`: Also accept `--name` for this argument. It can be used multiple times.
+//! - `#[possible_values("a", "b", ...)]`: Only accept the listed values. Can be used on `String`,
+//!   `Option<String>`, and `Vec<String>`.
+//! - `#[display_order(n)]`: List the argument before other arguments in its help section.
+//!   Arguments with lower numbers are listed first.
+//! - `#[value_name("FILE")]`: Show `FILE` as the value placeholder in the help text instead of the
+//!   type name, e.g. `--output FILE` instead of `--output PATH`. This also names values that the
+//!   application parses from a `String` field, e.g. `--timeout DURATION` for a value parsed with
+//!   [`ArgExt::parse_duration`](onlyargs::traits::ArgExt::parse_duration).
+//! - `#[deprecated = "Use --new-name instead."]`: Warn when the argument is used on the command
+//!   line. It still parses, and the help text notes the deprecation. The warning is printed to
+//!   `stderr`, or returned by `OnlyArgs::parse_with_warnings()`. This is Rust's own attribute, so
+//!   reading the field in the application is also linted as deprecated.
+//! - `#[arg_type(integer)]`: Parse the field as this kind of value, for types the derive cannot
+//!   detect by name. See [Type detection](#type-detection).
+//! - `#[flag_enum(Json, Yaml)]`: Set an enum field with one flag per listed variant, like `--json`
+//!   and `--yaml`. See [Flag enums](#flag-enums).
+//! - `#[hide]`: Omit the argument from the help text. It can still be parsed. This is useful for
+//!   internal, debug, or experimental arguments.
 //! - `#[long]`: Only generate long argument names like `--help`. Short args like `-h` are generated
 //!   by default, and this attribute suppresses that behavior.
 //! - `#[short('N')]`: Generate a short argument name with the given character. In this example, it
@@ -74,9 +589,22 @@
 //!   - Accepts string literals for `PathBuf`.
 //!   - Accepts numeric literals for numeric types.
 //!   - Accepts `true` and `false` idents and `"true"` and `"false"` string literals for `boolean`.
+//!   - Accepts string literals for `CString`, `Url`, `Uuid`, and `Version`, which are checked at
+//!     compile time.
 //! - `#[required]`: Can be used on `Vec<T>` to require at least one value. This ensures the vector
 //!   is never empty.
 //! - `#[positional]`: Makes a `Vec<T>` the dumping ground for positional arguments.
+//! - `#[env("VAR_NAME")]`: Read the value from the `VAR_NAME` environment variable when the
+//!   argument is not provided on the command line. The environment variable takes precedence over
+//!   `#[default(T)]`, and it satisfies required arguments.
+//!   - Can be used on primitive types and `Option<T>`.
+//!   - The help text will include the environment variable name.
+//! - `#[expand_tilde]`: Replace a leading `~` in the path with the home directory, like
+//!   `~/app.toml`. Shells do not expand `~` in quoted values. The home directory is `HOME`, or
+//!   `USERPROFILE` on Windows. Can be used on path types, and also applies to `#[default(T)]`.
+//!
+//! Environment variables, aliases, possible values, deprecations, defaults, and required arguments
+//! are noted in the help text, e.g. `[env: MYAPP_PORT]` or `[possible values: auto, always, never]`.
 //!
 //! # Supported types
 //!
@@ -95,14 +623,27 @@
 //! | `OsString`       | A string option with platform-specific encoding. |
 //! | `PathBuf`        | A file system path option.                       |
 //! | `String`         | UTF-8 encoded string option.                     |
+//! | `CString`        | UTF-8 string option without NUL bytes, for FFI.  |
+//!
+//! `Box<str>`, `Rc<str>`, and `Arc<str>` are parsed like `String`, and `Box<Path>` is parsed like
+//! `PathBuf`. The parsed value is converted with `Into`, so no intermediate `String` is kept.
+//!
+//! With the matching features enabled on `onlyargs`, these types are primitive types too:
+//!
+//! | Type              | Feature  | Description                    |
+//! |-------------------|----------|--------------------------------|
+//! | `url::Url`        | `url`    | An absolute URL option.        |
+//! | `uuid::Uuid`      | `uuid`   | A UUID option.                 |
+//! | `semver::Version` | `semver` | A semantic version option.     |
 //!
 //! Additionally, some wrapper and composite types are also available, where the type `T` must be
 //! one of the primitive types listed above (except `bool`).
 //!
-//! | Type        | Description                                                |
-//! |-------------|------------------------------------------------------------|
-//! | `Option<T>` | An optional argument.                                      |
-//! | `Vec<T>`    | Multivalue and positional arguments (see `#[positional]`). |
+//! | Type             | Description                                                |
+//! |------------------|------------------------------------------------------------|
+//! | `Option<T>`      | An optional argument.                                      |
+//! | `Vec<T>`         | Multivalue and positional arguments (see `#[positional]`). |
+//! | `Occurrences<T>` | Like `Vec<T>`, with `count()` and `last()` accessors.      |
 //!
 //! In argument parsing parlance, "flags" are simple boolean values; the argument does not require
 //! a value. For example, the argument `--help`.
@@ -112,15 +653,145 @@
 //!
 //! Multivalue arguments can be passed on the command line by using the same argument multiple
 //! times.
+//!
+//! ## Type detection
+//!
+//! Proc macros only see the type as it is written, so types are detected by name. Primitive types
+//! are recognized bare, with the `std::` paths they are usually imported from (like
+//! `std::path::PathBuf` or `path::PathBuf`), and with the paths of their crates (like `url::Url`).
+//! `Option<...>` and `Vec<...>` must be written without a path.
+//!
+//! Type aliases, re-exports, and other paths are not recognized. The `#[arg_type(...)]` field
+//! attribute names the kind of value instead, and the wrapper is still detected by name. The kinds
+//! are `float`, `integer`, `os_string`, `path`, `string`, `url`, `uuid`, and `version`.
+//!
+//! ```
+//! # use onlyargs::OnlyArgs as _;
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! type Port = u16;
+//!
+//! mod paths {
+//!     pub use std::path::PathBuf as IncludeDir;
+//! }
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args {
+//!     #[arg_type(integer)]
+//!     port: Option<Port>,
+//!
+//!     #[arg_type(path)]
+//!     include: Vec<paths::IncludeDir>,
+//! }
+//!
+//! let args = Args::parse(vec!["--port".into(), "8080".into()])?;
+//!
+//! assert_eq!(args.port, Some(8080));
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! ## Verbosity
+//!
+//! A field of type [`onlyargs::types::Verbosity`] adds repeatable `-v/--verbose` and `-q/--quiet`
+//! flags. Short flags can be grouped, like `-vv`. With the `log` feature on `onlyargs`, the level
+//! maps to a `log::LevelFilter`.
+//!
+//! The derive macro also generates an `init_logging(&self)` method, which installs a logger at the
+//! parsed level with the `env_logger` or `tracing-subscriber` features on `onlyargs`. See the
+//! [`onlyargs::logging`] module.
+//!
+//! ```
+//! # use onlyargs::OnlyArgs as _;
+//! use onlyargs::types::Verbosity;
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args {
+//!     verbosity: Verbosity,
+//! }
+//!
+//! let args = Args::parse(vec!["-vv".into(), "--quiet".into()])?;
+//!
+//! assert_eq!(args.verbosity.level(), 1);
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! [`onlyargs::types::Verbosity`]: https://docs.rs/onlyargs/latest/onlyargs/types/struct.Verbosity.html
+//! [`onlyargs::logging`]: https://docs.rs/onlyargs/latest/onlyargs/logging/index.html
+//!
+//! ## Color choice
+//!
+//! A field of type [`onlyargs::types::ColorChoice`] adds an option like `--color <WHEN>`, which
+//! accepts `auto`, `always`, or `never`. It defaults to `auto` and has no short name unless
+//! `#[short(...)]` is given. `should_colorize()` decides whether to color a stream, honoring
+//! `NO_COLOR` and whether the stream is a terminal.
+//!
+//! ```
+//! # use onlyargs::OnlyArgs as _;
+//! use onlyargs::help::Output;
+//! use onlyargs::types::ColorChoice;
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args {
+//!     color: ColorChoice,
+//! }
+//!
+//! let args = Args::parse(vec!["--color".into(), "never".into()])?;
+//!
+//! assert!(!args.color.should_colorize(Output::Stdout));
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! [`onlyargs::types::ColorChoice`]: https://docs.rs/onlyargs/latest/onlyargs/types/enum.ColorChoice.html
+//!
+//! ## Flag enums
+//!
+//! A field of a fieldless enum type with `#[flag_enum(...)]` adds a flag for each listed variant.
+//! The flag name is the variant name in kebab case, so `PrettyJson` is `--pretty-json`. Giving two
+//! different flags for the same field is a `CliError::Conflict`. Without any of the flags, an
+//! `Option<T>` field is `None` and other fields use `T::default()`.
+//!
+//! The derive cannot see the enum's variants, so each flag must be listed in the attribute.
+//! Variants that are not listed cannot be selected on the command line.
+//!
+//! ```
+//! # use onlyargs::OnlyArgs as _;
+//! use onlyargs_derive::OnlyArgs;
+//!
+//! #[derive(Debug, Default, PartialEq)]
+//! enum Format {
+//!     #[default]
+//!     Text,
+//!     Json,
+//!     Yaml,
+//! }
+//!
+//! #[derive(Debug, OnlyArgs)]
+//! struct Args {
+//!     /// Output format.
+//!     #[flag_enum(Json, Yaml)]
+//!     format: Format,
+//! }
+//!
+//! let args = Args::parse(vec!["--yaml".into()])?;
+//! assert_eq!(args.format, Format::Yaml);
+//!
+//! assert!(Args::parse(vec!["--json".into(), "--yaml".into()]).is_err());
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
 
 #![forbid(unsafe_code)]
 #![deny(clippy::all)]
 #![deny(clippy::pedantic)]
 #![allow(clippy::let_underscore_untyped)]
 
-use crate::parser::{ArgFlag, ArgOption, ArgProperty, ArgType, ArgView, ArgumentStruct};
+use crate::parser::{
+    split_template, to_snake_case, ArgFlag, ArgOption, ArgProperty, ArgType, ArgView,
+    ArgumentStruct, Complete, TemplatePart,
+};
 use myn::utils::spanned_error;
-use proc_macro::{Ident, Span, TokenStream};
+use proc_macro::{Ident, Literal, Span, TokenStream};
 use std::{collections::HashMap, fmt::Write as _, str::FromStr as _};
 
 mod parser;
@@ -129,27 +800,100 @@ mod parser;
 #[allow(clippy::too_many_lines)]
 #[proc_macro_derive(
     OnlyArgs,
-    attributes(footer, default, long, positional, required, short)
+    attributes(
+        alias,
+        arg_type,
+        authors,
+        capture_help,
+        capture_version,
+        collect_errors,
+        complete,
+        config_file,
+        config_path,
+        footer,
+        default,
+        deny_missing_docs,
+        display_order,
+        dotenv,
+        env,
+        env_prefix,
+        error_exit_code,
+        example,
+        expand_tilde,
+        flag_enum,
+        generate,
+        group_required,
+        header,
+        help,
+        help_exit_code,
+        help_heading,
+        help_stderr,
+        help_template,
+        help_width,
+        hide,
+        homepage,
+        impl_default,
+        long,
+        long_version,
+        name,
+        origins,
+        pager,
+        plain_help,
+        positional,
+        possible_values,
+        preprocess,
+        repository,
+        required,
+        short,
+        sort_help,
+        usage,
+        validate,
+        value_name,
+        verbatim_doc_comment,
+        version
+    )
 )]
 pub fn derive_parser(input: TokenStream) -> TokenStream {
-    let ast = match ArgumentStruct::parse(input) {
+    let mut ast = match ArgumentStruct::parse(input) {
         Ok(ast) => ast,
         Err(err) => return err,
     };
 
-    let mut flags = vec![
-        ArgFlag::new_priv(
+    let mut flags = vec![];
+    if !ast.capture_help {
+        flags.push(ArgFlag::new_priv(
             Ident::new("help", Span::call_site()),
             Some('h'),
             vec!["Show this help message.".to_string()],
-        ),
-        ArgFlag::new_priv(
+        ));
+    }
+    if !ast.capture_version {
+        flags.push(ArgFlag::new_priv(
             Ident::new("version", Span::call_site()),
             Some('V'),
             vec!["Show the application version.".to_string()],
-        ),
-    ];
+        ));
+    }
+    // `Verbosity` fields stand for repeatable `--verbose` and `--quiet` flags.
+    if let Some(verbosity) = ast.verbosity.as_ref() {
+        for (name, short, doc) in [
+            ("verbose", 'v', "Increase the verbosity. Can be repeated."),
+            ("quiet", 'q', "Decrease the verbosity. Can be repeated."),
+        ] {
+            let mut flag = ArgFlag::new_priv(
+                Ident::new(name, verbosity.span()),
+                Some(short),
+                vec![doc.to_string()],
+            );
+            flag.counted = true;
+            flags.push(flag);
+        }
+    }
     flags.extend(ast.flags);
+    // `#[flag_enum(...)]` fields stand for one flag per variant.
+    for flag_enum in &mut ast.flag_enums {
+        flags.append(&mut flag_enum.flags);
+    }
 
     // De-dupe short args.
     let mut dupes = HashMap::new();
@@ -164,30 +908,83 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
         }
     }
 
-    // Produce help text for all arguments.
-    let max_width = get_max_width(flags.iter().map(ArgFlag::as_view));
-    let flags_help = flags
-        .iter()
-        .map(|arg| to_help(arg.as_view(), max_width))
-        .collect::<String>();
+    // Produce help text for all arguments. The short help only includes the first line of each
+    // description.
+    let (flags_help, flags_short_help) = to_help_section(
+        flags
+            .iter()
+            .filter(|&flag| !flag.hidden && flag.heading.is_none())
+            .map(ArgFlag::as_view)
+            .collect(),
+        ast.sort_help,
+    );
+    let (options_help, options_short_help) = to_help_section(
+        ast.options
+            .iter()
+            .filter(|&opt| !opt.hidden && opt.heading.is_none())
+            .filter(|&opt| !ast.group_required || !opt.is_required())
+            .map(ArgOption::as_view)
+            .collect(),
+        ast.sort_help,
+    );
 
-    let max_width = get_max_width(ast.options.iter().map(ArgOption::as_view));
-    let options_help = ast
+    // Required options are listed in their own section with `#[group_required]`.
+    let required = ast
         .options
         .iter()
-        .map(|arg| to_help(arg.as_view(), max_width))
-        .collect::<String>();
+        .filter(|&opt| !opt.hidden && opt.heading.is_none())
+        .filter(|&opt| ast.group_required && opt.is_required())
+        .map(ArgOption::as_view)
+        .collect::<Vec<_>>();
+    let (required_help, required_short_help) = if required.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let (help, short_help) = to_help_section(required, ast.sort_help);
 
-    let positional_header = ast
-        .positional
-        .as_ref()
-        .map(|opt| format!(" [{}...]", opt.name))
-        .unwrap_or_default();
-    let positional_help = ast
-        .positional
-        .as_ref()
-        .map(|opt| format!("\n{}:\n  {}\n", opt.name, opt.doc.join("\n  ")))
-        .unwrap_or_default();
+        (
+            format!("\nRequired:\n{help}"),
+            format!("\nRequired:\n{short_help}"),
+        )
+    };
+
+    // Arguments with `#[help_heading(...)]` are listed in their own sections.
+    let (headings_help, headings_short_help) = ast.headings.iter().fold(
+        (String::new(), String::new()),
+        |(mut help, mut short_help), heading| {
+            let views = flags
+                .iter()
+                .filter(|&flag| !flag.hidden && flag.heading.as_ref() == Some(heading))
+                .map(ArgFlag::as_view)
+                .chain(
+                    ast.options
+                        .iter()
+                        .filter(|&opt| !opt.hidden && opt.heading.as_ref() == Some(heading))
+                        .map(ArgOption::as_view),
+                )
+                .collect::<Vec<_>>();
+            if !views.is_empty() {
+                let (section, short_section) = to_help_section(views, ast.sort_help);
+                write!(help, "\n{heading}:\n{section}").unwrap();
+                write!(short_help, "\n{heading}:\n{short_section}").unwrap();
+            }
+
+            (help, short_help)
+        },
+    );
+
+    let visible_positional = ast.positional.as_ref().filter(|&opt| !opt.hidden);
+    let positional_help = visible_positional.map_or_else(
+        || to_ordered_help(&ast.ordered, false),
+        |opt| format!("\n{}:\n  {}\n", opt.name, opt.doc.join("\n  ")),
+    );
+    let positional_short_help = visible_positional.map_or_else(
+        || to_ordered_help(&ast.ordered, true),
+        |opt| {
+            let doc = opt.doc.first().map(String::as_str).unwrap_or_default();
+
+            format!("\n{}:\n  {doc}\n", opt.name)
+        },
+    );
 
     // Produce variables for argument parser state.
     let flags_vars =
@@ -208,19 +1005,16 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
         .options
         .iter()
         .map(|opt| {
+            // Defaults are applied after the layered sources have been tried.
             let name = &opt.name;
-            if let Some(default) = opt.default.as_ref() {
-                format!("let mut {name} = {default}{};", opt.ty_help.converter())
-            } else {
-                match opt.property {
-                    ArgProperty::Optional | ArgProperty::Required => {
-                        format!("let mut {name} = None;")
-                    }
-                    ArgProperty::MultiValue { .. } => {
-                        format!("let mut {name} = vec![];")
-                    }
-                    ArgProperty::Positional { .. } => unreachable!(),
+            match opt.property {
+                ArgProperty::Optional | ArgProperty::Required => {
+                    format!("let mut {name} = None;")
                 }
+                ArgProperty::MultiValue { .. } => {
+                    format!("let mut {name} = vec![];")
+                }
+                ArgProperty::Positional { .. } => unreachable!(),
             }
         })
         .collect::<String>();
@@ -232,6 +1026,40 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
             format!("let mut {name} = vec![];")
         })
         .unwrap_or_default();
+    // Tuple struct fields are filled in order, tracked by `position_`.
+    let ordered_vars = if ast.ordered.is_empty() {
+        String::new()
+    } else {
+        ast.ordered.iter().fold(
+            "let mut position_ = 0_usize;".to_string(),
+            |mut vars, opt| {
+                let value = match opt.property {
+                    ArgProperty::MultiValue { .. } => "vec![]",
+                    _ => "None",
+                };
+                write!(vars, "let mut {} = {value};", opt.name).unwrap();
+                vars
+            },
+        )
+    };
+    // A `#[positional]` field or a trailing `Vec` field takes every remaining value.
+    let takes_rest = ast.positional.is_some()
+        || matches!(
+            ast.ordered.last().map(|opt| opt.property),
+            Some(ArgProperty::MultiValue { .. })
+        );
+    let flag_enums_vars = ast
+        .flag_enums
+        .iter()
+        .fold(String::new(), |mut vars, flag_enum| {
+            write!(vars, "let mut {} = None;", flag_enum.name).unwrap();
+            vars
+        });
+    let verbosity_var = if ast.verbosity.is_some() {
+        "let mut verbose_ = 0_u8; let mut quiet_ = 0_u8;"
+    } else {
+        ""
+    };
 
     // Produce matchers for parser.
     let flags_matchers =
@@ -244,11 +1072,28 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
                     .short
                     .map(|ch| format!(r#"| Some("-{ch}")"#))
                     .unwrap_or_default();
+                let aliases = flag
+                    .aliases
+                    .iter()
+                    .fold(String::new(), |mut aliases, alias| {
+                        write!(aliases, r#"| Some("--{alias}")"#).unwrap();
+                        aliases
+                    });
+
+                let track = if ast.origins.is_some() {
+                    format!(
+                        "origins_.set({:?}, ::onlyargs::source::Origin::Cli);",
+                        name.to_string()
+                    )
+                } else {
+                    String::new()
+                };
 
                 write!(
                     matchers,
-                    r#"Some("--{arg}") {short} => {name} = true,"#,
-                    arg = to_arg_name(name)
+                    r#"Some("--{arg}") {short} {aliases} => {{ {name} = true; {track} {warn} }},"#,
+                    arg = to_arg_name(name),
+                    warn = deprecation_warning(name, flag.deprecated.as_deref()),
                 )
                 .unwrap();
                 matchers
@@ -257,63 +1102,134 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
         let name = &opt.name;
         let short = opt
             .short
-            .map(|ch| format!(r#"| Some(arg_name_ @ "-{ch}")"#))
+            .map(|ch| format!(r#"| Some("-{ch}")"#))
             .unwrap_or_default();
-        let assignment = if opt.default.is_some() {
-            match opt.ty_help {
-                ArgType::Float => format!("{name} = args.next().parse_float(arg_name_)?"),
-                ArgType::Integer => format!("{name} = args.next().parse_int(arg_name_)?"),
-                ArgType::OsString => format!("{name} = args.next().parse_osstr(arg_name_)?"),
-                ArgType::Path => format!("{name} = args.next().parse_path(arg_name_)?"),
-                ArgType::String => format!("{name} = args.next().parse_str(arg_name_)?"),
-            }
-        } else {
-            match opt.property {
-                ArgProperty::Optional | ArgProperty::Required => match opt.ty_help {
-                    ArgType::Float => format!("{name} = Some(args.next().parse_float(arg_name_)?)"),
-                    ArgType::Integer => format!("{name} = Some(args.next().parse_int(arg_name_)?)"),
-                    ArgType::OsString => {
-                        format!("{name} = Some(args.next().parse_osstr(arg_name_)?)")
-                    }
-                    ArgType::Path => format!("{name} = Some(args.next().parse_path(arg_name_)?)"),
-                    ArgType::String => format!("{name} = Some(args.next().parse_str(arg_name_)?)"),
-                },
-                ArgProperty::MultiValue { .. } => match opt.ty_help {
-                    ArgType::Float => format!("{name}.push(args.next().parse_float(arg_name_)?)"),
-                    ArgType::Integer => format!("{name}.push(args.next().parse_int(arg_name_)?)"),
-                    ArgType::OsString => {
-                        format!("{name}.push(args.next().parse_osstr(arg_name_)?)")
-                    }
-                    ArgType::Path => format!("{name}.push(args.next().parse_path(arg_name_)?)"),
-                    ArgType::String => format!("{name}.push(args.next().parse_str(arg_name_)?)"),
-                },
-                ArgProperty::Positional { .. } => unreachable!(),
-            }
+        let aliases = opt
+            .aliases
+            .iter()
+            .fold(String::new(), |mut aliases, alias| {
+                write!(aliases, r#"| Some("--{alias}")"#).unwrap();
+                aliases
+            });
+        // Missing values are reported at the option.
+        let value = format!(
+            "{{
+                let (index_, value_) = args
+                    .next()
+                    .map_or((index_, None), |(index_, arg_)| (index_, Some(arg_)));
+                let token_ = value_.as_ref().unwrap_or(&arg).clone();
+
+                try_!(
+                    {parse}.map_err(|err_| err_.at(index_, token_)),
+                    continue
+                )
+            }}",
+            parse = to_parser_call(opt, "value_", &format!("{:?}", to_label(opt))),
+        );
+        let assignment = match opt.property {
+            ArgProperty::Optional | ArgProperty::Required => format!("{name} = Some({value})"),
+            ArgProperty::MultiValue { .. } => format!("{name}.push({value})"),
+            ArgProperty::Positional { .. } => unreachable!(),
         };
 
         write!(
             matchers,
-            r#"Some(arg_name_ @ "--{arg}") {short} => {assignment},"#,
-            arg = to_arg_name(name)
+            r#"Some("--{arg}") {short} {aliases} => {{ {warn} {assignment}; }},"#,
+            arg = to_arg_name(name),
+            warn = deprecation_warning(name, opt.deprecated.as_deref()),
         )
         .unwrap();
         matchers
     });
+    // Repeated short flags can be grouped, like `-vv`.
+    let verbosity_matchers = if ast.verbosity.is_some() {
+        r#"
+            Some("--verbose") => verbose_ = verbose_.saturating_add(1),
+            Some("--quiet") => quiet_ = quiet_.saturating_add(1),
+            Some(arg_) if ::onlyargs::types::Verbosity::count_short(arg_, 'v') > 0 => {
+                let count_ = ::onlyargs::types::Verbosity::count_short(arg_, 'v');
+                verbose_ = verbose_.saturating_add(count_);
+            }
+            Some(arg_) if ::onlyargs::types::Verbosity::count_short(arg_, 'q') > 0 => {
+                let count_ = ::onlyargs::types::Verbosity::count_short(arg_, 'q');
+                quiet_ = quiet_.saturating_add(count_);
+            }
+        "#
+    } else {
+        ""
+    };
+    // The first flag of a `#[flag_enum(...)]` field sets the variant, and any other flag for the
+    // same field is a conflict. Repeating the same flag is allowed.
+    let flag_enums_matchers =
+        ast.flag_enums
+            .iter()
+            .fold(String::new(), |mut matchers, flag_enum| {
+                let name = &flag_enum.name;
+                let track = if ast.origins.is_some() {
+                    format!(
+                        "origins_.set({:?}, ::onlyargs::source::Origin::Cli);",
+                        name.to_string()
+                    )
+                } else {
+                    String::new()
+                };
+
+                for variant in &flag_enum.variants {
+                    write!(
+                        matchers,
+                        r#"Some("--{arg}") => {{
+                        if let Some((other_, _)) = {name} {{
+                            if other_ != "--{arg}" {{
+                                fail_!(::onlyargs::CliError::Conflict {{
+                                    argument: "--{arg}".to_string(),
+                                    other: ::std::string::ToString::to_string(other_),
+                                }}
+                                .at(index_, &arg));
+                            }}
+                        }}
+                        {name} = Some(("--{arg}", {ty}::{variant}));
+                        {track}
+                    }},"#,
+                        arg = to_variant_arg_name(variant),
+                        ty = flag_enum.ty,
+                    )
+                    .unwrap();
+                }
+                matchers
+            });
+    // Known argument names for "did you mean" suggestions. Hidden arguments are not suggested.
+    let known = flags
+        .iter()
+        .filter(|&flag| !flag.hidden)
+        .map(|flag| (&flag.name, &flag.aliases))
+        .chain(
+            ast.options
+                .iter()
+                .filter(|&opt| !opt.hidden)
+                .map(|opt| (&opt.name, &opt.aliases)),
+        )
+        .fold(String::new(), |mut known, (name, aliases)| {
+            write!(known, r#""--{}","#, to_arg_name(name)).unwrap();
+            for alias in aliases {
+                write!(known, r#""--{alias}","#).unwrap();
+            }
+            known
+        });
     let positional_matcher = match ast.positional.as_ref() {
         Some(opt) => {
             let name = &opt.name;
-            let value = match opt.ty_help {
-                ArgType::Float => r#"arg.parse_float("<POSITIONAL>")?"#,
-                ArgType::Integer => r#"arg.parse_int("<POSITIONAL>")?"#,
-                ArgType::OsString => r#"arg.parse_osstr("<POSITIONAL>")?"#,
-                ArgType::Path => r#"arg.parse_path("<POSITIONAL>")?"#,
-                ArgType::String => r#"arg.parse_str("<POSITIONAL>")?"#,
-            };
+            let value = format!(
+                "{{
+                    let token_ = arg.clone();
+                    try_!({parse}.map_err(|err_| err_.at(index_, token_)), continue)
+                }}",
+                parse = to_parser_call(opt, "arg", r#""<POSITIONAL>""#),
+            );
 
             format!(
                 r#"
                     Some("--") => {{
-                        for arg in args {{
+                        for (index_, arg) in args {{
                             {name}.push({value});
                         }}
                         break;
@@ -322,11 +1238,365 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
                 "#
             )
         }
-        None => r#"
-            Some("--") => break,
-            _ => return Err(::onlyargs::CliError::Unknown(arg)),
-        "#
-        .to_string(),
+        None if !ast.ordered.is_empty() => {
+            let arms =
+                ast.ordered
+                    .iter()
+                    .enumerate()
+                    .fold(String::new(), |mut arms, (index, opt)| {
+                        let name = &opt.name;
+                        let value = format!(
+                            "{{
+                                let token_ = arg.clone();
+                                try_!({parse}.map_err(|err_| err_.at(index_, token_)), continue)
+                            }}",
+                            parse = to_parser_call(
+                                opt,
+                                "arg",
+                                &format!("{:?}", to_ordered_label(opt)),
+                            ),
+                        );
+                        let arm = match opt.property {
+                            ArgProperty::MultiValue { .. } => format!("_ => {name}.push({value}),"),
+                            _ => format!("{index} => {name} = Some({value}),"),
+                        };
+                        arms.push_str(&arm);
+                        arms
+                    });
+            // Values beyond the last field are unknown, unless parsing is partial.
+            let (overflow, stop) = if takes_rest {
+                (String::new(), String::new())
+            } else {
+                (
+                    format!(
+                        "_ if partial_ => remainder_.push(arg),
+                        _ => fail_!(::onlyargs::CliError::unknown(arg.clone(), &[{known}]).at(index_, arg)),"
+                    ),
+                    format!(
+                        "_ if partial_ && position_ >= {count} => {{
+                            remainder_.push(arg);
+                            remainder_.extend(args.map(|(_, arg_)| arg_));
+                            break;
+                        }}",
+                        count = ast.ordered.len(),
+                    ),
+                )
+            };
+            let push = format!(
+                "{{
+                    position_ += 1;
+                    match position_ - 1 {{
+                        {arms}
+                        {overflow}
+                    }}
+                }}"
+            );
+
+            format!(
+                r#"
+                    Some("--") => {{
+                        for (index_, arg) in args {{
+                            {push}
+                        }}
+                        break;
+                    }}
+                    {stop}
+                    _ => {push}
+                "#
+            )
+        }
+        None => format!(
+            r#"
+                Some("--") => {{
+                    if partial_ {{
+                        remainder_.extend(args.map(|(_, arg_)| arg_));
+                    }}
+                    break;
+                }}
+                _ if partial_ => {{
+                    remainder_.push(arg);
+                    remainder_.extend(args.map(|(_, arg_)| arg_));
+                    break;
+                }}
+                _ => fail_!(::onlyargs::CliError::unknown(arg.clone(), &[{known}]).at(index_, arg)),
+            "#
+        ),
+    };
+
+    // Produce the default layered sources.
+    let dotenv_layer = ast
+        .dotenv
+        .as_ref()
+        .map(|path| format!(".push(::onlyargs::dotenv::DotEnv::load({path:?})?)"))
+        .unwrap_or_default();
+    let config_layer = ast
+        .config_file
+        .as_ref()
+        .map(|path| format!(".push(::onlyargs::config::Config::load({path:?})?)"))
+        .unwrap_or_default();
+
+    // Produce the pre-parse that locates a config file named by the `#[config_path]` field.
+    let config_path_layers = match ast.options.iter().find(|opt| opt.config_path) {
+        Some(opt) => {
+            let short = opt
+                .short
+                .map(|ch| format!(r#"| Some(arg_name_ @ "-{ch}")"#))
+                .unwrap_or_default();
+            let aliases = opt
+                .aliases
+                .iter()
+                .fold(String::new(), |mut aliases, alias| {
+                    write!(aliases, r#"| Some(arg_name_ @ "--{alias}")"#).unwrap();
+                    aliases
+                });
+            // Values of other options are skipped, so they are never mistaken for the config path.
+            let values = ast
+                .options
+                .iter()
+                .filter(|other| !other.config_path)
+                .flat_map(|other| {
+                    std::iter::once(format!(r#"Some("--{}")"#, to_arg_name(&other.name)))
+                        .chain(other.short.map(|ch| format!(r#"Some("-{ch}")"#)))
+                        .chain(
+                            other
+                                .aliases
+                                .iter()
+                                .map(|alias| format!(r#"Some("--{alias}")"#)),
+                        )
+                })
+                .collect::<Vec<_>>();
+            let values = if values.is_empty() {
+                String::new()
+            } else {
+                format!("{} => {{ args_.next(); }}", values.join(" | "))
+            };
+            // The scan sees the same arguments as the parser.
+            let args = if ast.preprocess.is_some() {
+                "<Self as ::onlyargs::OnlyArgs>::preprocess(args.to_vec())?.into_iter()"
+            } else {
+                "args.iter().cloned()"
+            };
+            let key = format!(
+                "::onlyargs::source::Key {{ name: {key:?}, env: {env:?} }}",
+                key = opt.name.to_string(),
+                env = opt.env.as_deref(),
+            );
+            // A default path is allowed to be missing, like `#[config_file(...)]`.
+            let default = opt.default.as_ref().map_or_else(
+                || "None".to_string(),
+                |default| {
+                    format!(
+                        "Some(::onlyargs::config::Config::load(::std::path::PathBuf::from({default}))?)"
+                    )
+                },
+            );
+            // The config path layer takes precedence over the `#[config_file(...)]` layer.
+            let layer = if ast.config_file.is_some() {
+                "{ let index_ = layers_.len() - 1; layers_.insert(index_, config_) }"
+            } else {
+                "layers_.push(config_)"
+            };
+
+            format!(
+                r#"
+                    let mut layers_ = Self::layers()?;
+                    let mut config_path_ = None;
+                    let mut args_ = {args};
+                    while let Some(arg) = args_.next() {{
+                        match arg.to_str() {{
+                            Some(arg_name_ @ "--{arg}") {short} {aliases} => {{
+                                config_path_ = Some(args_.next().parse_path(arg_name_)?);
+                            }}
+                            {values}
+                            Some("--") => break,
+                            _ => (),
+                        }}
+                    }}
+                    if config_path_.is_none() {{
+                        config_path_ = layers_.parse(
+                            &{key},
+                            |value_, name_| value_.parse_path(name_),
+                        )?;
+                    }}
+                    let config_ = match config_path_ {{
+                        Some(path_) => Some(::onlyargs::config::Config::open(path_)?),
+                        None => {default},
+                    }};
+                    if let Some(config_) = config_ {{
+                        layers_ = {layer};
+                    }}
+                "#,
+                arg = to_arg_name(&opt.name),
+            )
+        }
+        None => "let layers_ = Self::layers()?;".to_string(),
+    };
+
+    // Produce fallbacks for options that were not provided on the command line.
+    let options_fallbacks = if ast.options.is_empty() {
+        "let _ = layers_;".to_string()
+    } else {
+        ast.options
+            .iter()
+            .map(|opt| {
+                let name = &opt.name;
+                let key = format!(
+                    "::onlyargs::source::Key {{ name: {key:?}, env: {env:?} }}",
+                    key = name.to_string(),
+                    env = opt.env.as_deref(),
+                );
+                let parser = to_parser_call(opt, "value_", "name_");
+                let (is_missing, parse) = if let ArgProperty::MultiValue { .. } = opt.property {
+                    ("is_empty", "parse_all")
+                } else {
+                    ("is_none", "parse")
+                };
+                let (track_cli, track_layer) = if ast.origins.is_some() {
+                    let field = name.to_string();
+                    (
+                        format!(
+                            r"
+                                if !{name}.{is_missing}() {{
+                                    origins_.set({field:?}, ::onlyargs::source::Origin::Cli);
+                                }}
+                            ",
+                        ),
+                        format!(
+                            r"
+                                if let Some(origin_) = layers_.origin(&{key}) {{
+                                    origins_.set({field:?}, origin_);
+                                }}
+                            ",
+                        ),
+                    )
+                } else {
+                    (String::new(), String::new())
+                };
+
+                // Invalid choices name the environment variable or config key that they came from.
+                let mut fallback = if opt.choices.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        r"
+                            let argument_ = if {name}.{is_missing}() {{
+                                use ::onlyargs::source::Source as _;
+                                layers_.describe(&{key})
+                            }} else {{
+                                {label:?}.to_string()
+                            }};
+                        ",
+                        label = to_label(opt),
+                    )
+                };
+                write!(
+                    fallback,
+                    r"
+                        {track_cli}
+                        if {name}.{is_missing}() {{
+                            {name} = try_!(
+                                layers_.{parse}(
+                                    &{key},
+                                    |value_, name_| {parser},
+                                ),
+                                Default::default()
+                            );
+                            {track_layer}
+                        }}
+                    ",
+                )
+                .unwrap();
+                if !opt.choices.is_empty() {
+                    let check = format!(
+                        r"
+                            let value_: &str = ::std::convert::AsRef::as_ref(value_);
+                            if !{choices:?}.contains(&value_) {{
+                                fail_!(::onlyargs::CliError::InvalidChoice {{
+                                    argument: argument_.clone(),
+                                    value: value_.into(),
+                                    choices: ::std::vec!{choices:?},
+                                }});
+                            }}
+                        ",
+                        choices = opt.choices,
+                    );
+                    if let ArgProperty::MultiValue { .. } = opt.property {
+                        write!(fallback, "for value_ in &{name} {{ {check} }}").unwrap();
+                    } else {
+                        write!(fallback, "if let Some(value_) = &{name} {{ {check} }}").unwrap();
+                    }
+                }
+                if let Some(default) = opt.default.as_ref() {
+                    write!(
+                        fallback,
+                        "let {name} = match {name} {{ Some(value_) => value_, None => {} }};",
+                        to_default_value(opt, default),
+                    )
+                    .unwrap();
+                }
+
+                fallback
+            })
+            .collect::<String>()
+    };
+
+    // Produce provenance tracking for the `#[origins]` field.
+    let (origins_var, origins_positional, origins_ident) = match ast.origins.as_ref() {
+        Some(origins) => {
+            let fields = flags
+                .iter()
+                .filter(|&flag| flag.output)
+                .map(|flag| &flag.name)
+                .chain(ast.options.iter().map(|opt| &opt.name))
+                .chain(ast.positional.iter().map(|opt| &opt.name))
+                .chain(ast.flag_enums.iter().map(|flag_enum| &flag_enum.name))
+                .fold(String::new(), |mut fields, name| {
+                    write!(
+                        fields,
+                        "origins_.set({:?}, ::onlyargs::source::Origin::Default);",
+                        name.to_string(),
+                    )
+                    .unwrap();
+                    fields
+                });
+            let positional = ast
+                .positional
+                .as_ref()
+                .map(|opt| {
+                    format!(
+                        r"
+                            if !{name}.is_empty() {{
+                                origins_.set({field:?}, ::onlyargs::source::Origin::Cli);
+                            }}
+                        ",
+                        name = opt.name,
+                        field = opt.name.to_string(),
+                    )
+                })
+                .unwrap_or_default();
+
+            (
+                format!("let mut origins_ = ::onlyargs::source::Origins::default(); {fields}"),
+                positional,
+                format!("{origins}: origins_,"),
+            )
+        }
+        None => (String::new(), String::new(), String::new()),
+    };
+
+    // Captured help and version flags skip the required and validation checks. Missing required
+    // values are filled with their defaults.
+    let captured = [("help", ast.capture_help), ("version", ast.capture_version)]
+        .into_iter()
+        .filter_map(|(name, capture)| capture.then_some(name))
+        .collect::<Vec<_>>()
+        .join(" || ");
+    let unless_captured = |required: String, default: String| {
+        if captured.is_empty() {
+            required
+        } else {
+            format!("if {captured} {{ {default} }} else {{ {required} }}")
+        }
     };
 
     // Produce identifiers for args constructor.
@@ -345,122 +1615,1046 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
                     | ArgProperty::Positional { required: false }
                     | ArgProperty::MultiValue { required: false }
             );
-            if opt.default.is_some() || optional {
-                format!("{name},")
+            let value = if opt.default.is_some() || optional {
+                name.to_string()
             } else {
-                format!(
-                    r#"{name}: {name}.required("--{arg}")?,"#,
-                    arg = to_arg_name(name)
+                let default = match opt.property {
+                    ArgProperty::Required => format!("{name}.unwrap_or_default()"),
+                    _ => name.to_string(),
+                };
+                unless_captured(
+                    format!("{name}.required({label:?})?", label = to_label(opt)),
+                    default,
                 )
-            }
+            };
+
+            to_field_init(opt, &value)
         })
         .collect::<String>();
     let positional_ident = ast
         .positional
+        .as_ref()
         .map(|opt| {
-            if matches!(opt.property, ArgProperty::Positional { required: true }) {
-                format!(
-                    r#"{}: {}.required("{arg}")?,"#,
-                    opt.name,
-                    opt.name,
-                    arg = to_arg_name(&opt.name),
+            let value = if matches!(opt.property, ArgProperty::Positional { required: true }) {
+                unless_captured(
+                    format!(
+                        r#"{}.required("{arg}")?"#,
+                        opt.name,
+                        arg = to_arg_name(&opt.name),
+                    ),
+                    opt.name.to_string(),
                 )
             } else {
-                format!("{},", opt.name)
-            }
+                opt.name.to_string()
+            };
+
+            to_field_init(opt, &value)
         })
         .unwrap_or_default();
 
+    let ordered_idents =
+        ast.ordered
+            .iter()
+            .enumerate()
+            .fold(String::new(), |mut idents, (index, opt)| {
+                let name = &opt.name;
+                let value = match opt.property {
+                    ArgProperty::Required => {
+                        format!("{name}.required({label:?})?", label = to_ordered_label(opt))
+                    }
+                    _ if opt.occurrences => format!("::onlyargs::Occurrences::from({name})"),
+                    _ => name.to_string(),
+                };
+                write!(idents, "{index}: {value},").unwrap();
+                idents
+            });
+
+    let flag_enums_idents = ast
+        .flag_enums
+        .iter()
+        .fold(String::new(), |mut idents, flag_enum| {
+            let name = &flag_enum.name;
+            if flag_enum.optional {
+                write!(idents, "{name}: {name}.map(|(_, value_)| value_),").unwrap();
+            } else {
+                write!(
+                    idents,
+                    "{name}: {name}.map_or_else(
+                        ::std::default::Default::default,
+                        |(_, value_)| value_,
+                    ),"
+                )
+                .unwrap();
+            }
+            idents
+        });
+
+    let verbosity_ident = ast
+        .verbosity
+        .as_ref()
+        .map(|name| format!("{name}: ::onlyargs::types::Verbosity::new(verbose_, quiet_),"))
+        .unwrap_or_default();
+
     let name = ast.name;
     let doc_comment = if ast.doc.is_empty() {
         String::new()
     } else {
         format!("\n{}\n", ast.doc.join("\n"))
     };
+    let header = if ast.header.is_empty() {
+        String::new()
+    } else {
+        format!("\n{}\n", ast.header.join("\n"))
+    };
     let footer = if ast.footer.is_empty() {
         String::new()
     } else {
         format!("\n{}\n", ast.footer.join("\n"))
     };
-    let bin_name = std::env::var_os("CARGO_BIN_NAME").and_then(|name| name.into_string().ok());
-    let help_impl = if bin_name.is_none() {
-        r#"fn help() -> ! {
-            let bin_name = ::std::env::args_os()
-                .next()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .into_owned();
-            ::std::eprintln!("{}", Self::HELP.replace("{bin_name}", &bin_name));
-            ::std::process::exit(0);
-        }"#
+    let help_width_impl = ast
+        .help_width
+        .map(|width| format!("fn help_width() -> usize {{ {width} }}"))
+        .unwrap_or_default();
+    let plain_help_impl = if ast.plain_help {
+        "fn plain_help() -> bool { true }"
+    } else {
+        ""
+    };
+    let help_output_impl = if ast.help_stderr {
+        "fn help_output() -> ::onlyargs::help::Output { ::onlyargs::help::Output::Stderr }"
+    } else {
+        ""
+    };
+    let help_matchers = if ast.capture_help {
+        ""
+    } else {
+        r#"Some("--help") => return Ok((::onlyargs::ParseResult::Help, warnings_, remainder_)),
+        Some("-h") => return Ok((::onlyargs::ParseResult::ShortHelp, warnings_, remainder_)),"#
+    };
+    let version_matchers = if ast.capture_version {
+        ""
+    } else {
+        r#"Some("--version") => return Ok((::onlyargs::ParseResult::Version, warnings_, remainder_)),
+        Some("-V") => return Ok((::onlyargs::ParseResult::ShortVersion, warnings_, remainder_)),"#
+    };
+    // Only deprecated arguments produce warnings.
+    let warnings_var = if flags.iter().any(|flag| flag.deprecated.is_some())
+        || ast.options.iter().any(|opt| opt.deprecated.is_some())
+    {
+        "let mut warnings_ = ::std::vec::Vec::new();"
+    } else {
+        "let warnings_ = ::std::vec::Vec::new();"
+    };
+    // Positional arguments take every argument that is not recognized, so nothing remains.
+    let remainder_var = if takes_rest {
+        "let remainder_ = ::std::vec::Vec::new(); let _ = partial_;"
+    } else {
+        "let mut remainder_ = ::std::vec::Vec::new();"
+    };
+    // Errors return early by default. With `#[collect_errors]`, they are collected and parsing
+    // continues with the next argument.
+    let (error_macros, errors_check) = if ast.collect_errors {
+        let required_checks = ast
+            .options
+            .iter()
+            .filter(|&opt| opt.default.is_none())
+            .map(|opt| (opt, to_label(opt)))
+            .chain(
+                ast.positional
+                    .iter()
+                    .map(|opt| (opt, to_arg_name(&opt.name))),
+            )
+            .chain(ast.ordered.iter().map(|opt| (opt, to_ordered_label(opt))))
+            .fold(String::new(), |mut checks, (opt, arg)| {
+                let is_missing = match opt.property {
+                    ArgProperty::Required => "is_none",
+                    ArgProperty::MultiValue { required: true }
+                    | ArgProperty::Positional { required: true } => "is_empty",
+                    _ => return checks,
+                };
+                write!(
+                    checks,
+                    r"
+                        if {name}.{is_missing}() {{
+                            errors_.push(::onlyargs::CliError::MissingRequired {{
+                                argument: {arg:?}.to_string(),
+                            }});
+                        }}
+                    ",
+                    name = opt.name,
+                )
+                .unwrap();
+                checks
+            });
+        let required_checks = if captured.is_empty() || required_checks.is_empty() {
+            required_checks
+        } else {
+            format!("if !({captured}) {{ {required_checks} }}")
+        };
+
+        (
+            r"
+                let mut errors_ = vec![];
+
+                #[allow(unused_macros)]
+                macro_rules! try_ {
+                    ($result:expr, $bail:expr) => {
+                        match $result {
+                            Ok(value_) => value_,
+                            Err(err_) => {
+                                errors_.push(err_);
+                                $bail
+                            }
+                        }
+                    };
+                }
+
+                #[allow(unused_macros)]
+                macro_rules! fail_ {
+                    ($err:expr) => {
+                        errors_.push($err)
+                    };
+                }
+            ",
+            format!(
+                r"
+                    {required_checks}
+                    if !errors_.is_empty() {{
+                        return Err(::onlyargs::CliError::Multiple {{ errors: errors_ }});
+                    }}
+                "
+            ),
+        )
+    } else {
+        (
+            r"
+                #[allow(unused_macros)]
+                macro_rules! try_ {
+                    ($result:expr, $bail:expr) => {
+                        $result?
+                    };
+                }
+
+                #[allow(unused_macros)]
+                macro_rules! fail_ {
+                    ($err:expr) => {
+                        return Err($err)
+                    };
+                }
+            ",
+            String::new(),
+        )
+    };
+    let exit_codes_impl = [
+        ("help_exit_code", ast.help_exit_code),
+        ("error_exit_code", ast.error_exit_code),
+    ]
+    .into_iter()
+    .fold(String::new(), |mut exit_codes, (name, code)| {
+        if let Some(code) = code {
+            write!(exit_codes, "fn {name}() -> i32 {{ {code} }}").unwrap();
+        }
+        exit_codes
+    });
+    // Arguments are streamed from the iterator, unless they are preprocessed.
+    let preprocess_args = if ast.preprocess.is_some() {
+        format!(
+            "let args = <{name} as ::onlyargs::OnlyArgs>::preprocess(args.collect())?.into_iter();"
+        )
+    } else {
+        String::new()
+    };
+    // Arguments are scanned for the config path before they are parsed.
+    let collect_args = if ast.options.iter().any(|opt| opt.config_path) {
+        "let args = args.into_iter().collect::<Vec<_>>();"
+    } else {
+        ""
+    };
+    let preprocess_impl = ast
+        .preprocess
+        .as_ref()
+        .map(|path| {
+            format!(
+                "fn preprocess(args: Vec<::std::ffi::OsString>) ->
+                    ::std::result::Result<Vec<::std::ffi::OsString>, ::onlyargs::CliError>
+                {{
+                    {path}(args)
+                }}"
+            )
+        })
+        .unwrap_or_default();
+    let validate_impl = ast
+        .validate
+        .as_ref()
+        .map(|path| {
+            format!(
+                "fn validate(&self) -> ::std::result::Result<(), ::onlyargs::CliError> {{
+                    {path}(self)
+                }}"
+            )
+        })
+        .unwrap_or_default();
+    let validate_call = unless_captured(
+        "::onlyargs::OnlyArgs::validate(&parsed_)?;".to_string(),
+        String::new(),
+    );
+    // Completion candidates for options with `#[complete(fn = path)]`.
+    let complete_arms = ast
+        .options
+        .iter()
+        .chain(&ast.positional)
+        .map(|opt| (opt, to_arg_name(&opt.name)))
+        .chain(ast.ordered.iter().map(|opt| (opt, to_ordered_name(opt))))
+        .filter_map(|(opt, name)| match opt.complete.as_ref() {
+            Some(Complete::Fn(path)) => Some(format!("{name:?} => {path}(word_),")),
+            _ => None,
+        })
+        .collect::<String>();
+    let complete_impl = if complete_arms.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "fn complete(name_: &str, word_: &str) -> Vec<String> {{
+                match name_ {{
+                    {complete_arms}
+                    _ => ::std::vec::Vec::new(),
+                }}
+            }}"
+        )
+    };
+    let pager_impl = if ast.pager {
+        "fn pager() -> bool { true }"
     } else {
         ""
     };
-    let bin_name = bin_name.unwrap_or_else(|| "{bin_name}".to_string());
+    let generate_impl = if ast.generate {
+        "fn generate_arg() -> bool { true }"
+    } else {
+        ""
+    };
+    // The binary name is known when the derive is used in a binary target. Otherwise it is replaced
+    // at runtime.
+    let bin_name = std::env::var("CARGO_BIN_NAME").unwrap_or_else(|_| "{bin_name}".to_string());
+
+    // Produce the usage line. Required options and positional arguments are listed by name.
+    let usage = if ast.usage.is_empty() {
+        let required = ast.options.iter().filter(|&opt| !opt.hidden).fold(
+            String::new(),
+            |mut required, opt| {
+                let ty = opt.value_name();
+                match opt.property {
+                    ArgProperty::Required if opt.default.is_none() => {
+                        write!(required, " --{} <{ty}>", to_arg_name(&opt.name)).unwrap();
+                    }
+                    ArgProperty::MultiValue { required: true } => {
+                        write!(required, " --{} <{ty}>...", to_arg_name(&opt.name)).unwrap();
+                    }
+                    _ => (),
+                }
+                required
+            },
+        );
+        let ordered = ast.ordered.iter().fold(String::new(), |mut usage, opt| {
+            let value_name = opt.value_name();
+            let value = match opt.property {
+                ArgProperty::Required => format!(" <{value_name}>"),
+                ArgProperty::Optional => format!(" [{value_name}]"),
+                _ => format!(" [{value_name}...]"),
+            };
+            usage.push_str(&value);
+            usage
+        });
+        let positional =
+            ast.positional
+                .as_ref()
+                .filter(|&opt| !opt.hidden)
+                .map_or(ordered, |opt| match opt.property {
+                    ArgProperty::Positional { required: true } => format!(" <{}>...", opt.name),
+                    _ => format!(" [{}...]", opt.name),
+                });
+
+        let options = if ast.options.iter().any(|opt| !opt.hidden) {
+            " [options]"
+        } else {
+            ""
+        };
+
+        format!("{bin_name}{required} [flags]{options}{positional}")
+    } else {
+        ast.usage.join("\n  ").replace("{bin_name}", &bin_name)
+    };
+    let examples = if ast.examples.is_empty() {
+        String::new()
+    } else {
+        format!("\nExamples:\n  {}\n", ast.examples.join("\n  ")).replace("{bin_name}", &bin_name)
+    };
+    // Package metadata comes from Cargo unless it is overridden.
+    let app_name = ast.app_name.as_ref().map_or_else(
+        || r#"env!("CARGO_PKG_NAME")"#.to_string(),
+        |name| format!("{name:?}"),
+    );
+    let app_version = ast.app_version.as_ref().map_or_else(
+        || r#"env!("CARGO_PKG_VERSION")"#.to_string(),
+        |version| format!("{version:?}"),
+    );
+    let metadata = [
+        ("name", app_name.as_str()),
+        ("version", app_version.as_str()),
+        ("description", r#"env!("CARGO_PKG_DESCRIPTION")"#),
+    ];
+    // Contact and project links are listed below the description.
+    let links = [
+        ("Authors", &ast.authors),
+        ("Homepage", &ast.homepage),
+        ("Repository", &ast.repository),
+    ]
+    .into_iter()
+    .fold(String::new(), |mut links, (title, value)| {
+        if let Some(value) = value {
+            writeln!(links, "{title}: {value}").unwrap();
+        }
+        links
+    });
+    let package_var = |key: &str, value: &Option<String>| {
+        value.clone().unwrap_or_else(|| {
+            let value = std::env::var(format!("CARGO_PKG_{key}")).unwrap_or_default();
+
+            // Cargo separates authors with colons.
+            if key == "AUTHORS" {
+                value.replace(':', ", ")
+            } else {
+                value
+            }
+        })
+    };
+    let authors = package_var("AUTHORS", &ast.authors);
+    let homepage = package_var("HOMEPAGE", &ast.homepage);
+    let repository = package_var("REPOSITORY", &ast.repository);
+    let long_version = ast
+        .long_version
+        .as_ref()
+        .map(|long_version| format!(r#"{long_version}, "\n","#))
+        .unwrap_or_default();
+    // Argument metadata for `OnlyArgs::SPEC`.
+    let flags_spec = flags
+        .iter()
+        .filter(|&flag| !flag.hidden)
+        .map(|flag| {
+            let default = flag.default.then_some("true");
+
+            to_spec(
+                &to_arg_name(&flag.name),
+                flag.as_view(),
+                "Flag",
+                flag.counted,
+                false,
+                default,
+                "Default",
+            )
+        })
+        .collect::<String>();
+    let options_spec = ast
+        .options
+        .iter()
+        .chain(&ast.positional)
+        .map(|opt| (opt, to_arg_name(&opt.name), false))
+        .chain(
+            ast.ordered
+                .iter()
+                .map(|opt| (opt, to_ordered_name(opt), true)),
+        )
+        .filter(|&(opt, _, _)| !opt.hidden)
+        .map(|(opt, name, ordered)| {
+            // Positional arguments do not use their short name.
+            let mut view = opt.as_view();
+            let (kind, multiple, required) = match opt.property {
+                _ if ordered => (
+                    "Positional",
+                    matches!(opt.property, ArgProperty::MultiValue { .. }),
+                    opt.is_required(),
+                ),
+                ArgProperty::Positional { required } => {
+                    view.short = None;
+                    ("Positional", true, required)
+                }
+                ArgProperty::MultiValue { .. } => ("Option", true, opt.is_required()),
+                ArgProperty::Required | ArgProperty::Optional => {
+                    ("Option", false, opt.is_required())
+                }
+            };
+            let kind = format!(
+                "{kind}(::onlyargs::ValueType::{})",
+                opt.ty_help.value_type()
+            );
+            let complete = match opt.complete {
+                None => "Default",
+                Some(Complete::File) => "File",
+                Some(Complete::Dir) => "Dir",
+                Some(Complete::Hostname) => "Hostname",
+                Some(Complete::Fn(_)) => "Callback",
+            };
+
+            to_spec(
+                &name,
+                view,
+                &kind,
+                multiple,
+                required,
+                opt.default_value().as_deref(),
+                complete,
+            )
+        })
+        .collect::<String>();
+    // The default layout leaves out the "Options:" section when there are no options to list.
+    let template = match ast.template.as_deref() {
+        Some(template) => template.to_string(),
+        None if options_help.is_empty() => DEFAULT_TEMPLATE.replace("\nOptions:\n{options}", ""),
+        None => DEFAULT_TEMPLATE.to_string(),
+    };
+    let help = to_help_str(
+        &template,
+        &metadata,
+        &[
+            ("authors", &authors),
+            ("homepage", &homepage),
+            ("repository", &repository),
+            ("links", &links),
+            ("header", &header),
+            ("doc", &doc_comment),
+            ("usage", &usage),
+            ("required", &required_help),
+            ("flags", &flags_help),
+            ("options", &options_help),
+            ("headings", &headings_help),
+            ("positional", &positional_help),
+            ("examples", &examples),
+            ("footer", &footer),
+        ],
+    );
+    let short_help = to_help_str(
+        &template,
+        &metadata,
+        &[
+            ("authors", &authors),
+            ("homepage", &homepage),
+            ("repository", &repository),
+            ("links", &links),
+            ("header", &header),
+            ("doc", &doc_comment),
+            ("usage", &usage),
+            ("required", &required_short_help),
+            ("flags", &flags_short_help),
+            ("options", &options_short_help),
+            ("headings", &headings_short_help),
+            ("positional", &positional_short_help),
+            ("examples", &examples),
+            ("footer", &footer),
+        ],
+    );
+
+    // Push each field to the command line in `to_args()`. Positional values follow `--`, so they
+    // are not read as options.
+    let to_args = flags
+        .iter()
+        .filter(|&flag| flag.output)
+        .map(|flag| {
+            format!(
+                r#"if self.{name} {{ args_.push(::std::ffi::OsString::from("--{arg}")); }}"#,
+                name = flag.name,
+                arg = to_arg_name(&flag.name),
+            )
+        })
+        .chain(ast.verbosity.iter().map(|name| {
+            format!(
+                r#"
+                    for _ in 0..self.{name}.verbose {{
+                        args_.push(::std::ffi::OsString::from("--verbose"));
+                    }}
+                    for _ in 0..self.{name}.quiet {{
+                        args_.push(::std::ffi::OsString::from("--quiet"));
+                    }}
+                "#
+            )
+        }))
+        .chain(ast.flag_enums.iter().map(|flag_enum| {
+            let arms = flag_enum
+                .variants
+                .iter()
+                .fold(String::new(), |mut arms, variant| {
+                    let pattern = format!("{}::{variant}", flag_enum.ty);
+                    write!(
+                        arms,
+                        r#"{pattern} => args_.push(::std::ffi::OsString::from("--{arg}")),"#,
+                        pattern = if flag_enum.optional {
+                            format!("Some({pattern})")
+                        } else {
+                            pattern
+                        },
+                        arg = to_variant_arg_name(variant),
+                    )
+                    .unwrap();
+                    arms
+                });
+
+            // Variants without a flag, and `None`, are left out.
+            format!(
+                "#[allow(unreachable_patterns)]
+                match &self.{name} {{
+                    {arms}
+                    _ => (),
+                }}",
+                name = flag_enum.name,
+            )
+        }))
+        .chain(ast.options.iter().chain(&ast.positional).map(|opt| {
+            let name = &opt.name;
+            let arg = format!(r#"::std::ffi::OsString::from("--{}")"#, to_arg_name(name));
+
+            match opt.property {
+                ArgProperty::Required => format!(
+                    "args_.push({arg}); args_.push({});",
+                    to_os_string(opt, &format!("&self.{name}")),
+                ),
+                ArgProperty::Optional => format!(
+                    "if let Some(value_) = &self.{name} {{
+                        args_.push({arg}); args_.push({});
+                    }}",
+                    to_os_string(opt, "value_"),
+                ),
+                ArgProperty::MultiValue { .. } => format!(
+                    "for value_ in self.{name}.iter() {{
+                        args_.push({arg}); args_.push({});
+                    }}",
+                    to_os_string(opt, "value_"),
+                ),
+                ArgProperty::Positional { .. } => format!(
+                    r#"if !self.{name}.is_empty() {{
+                        args_.push(::std::ffi::OsString::from("--"));
+                    }}
+                    for value_ in self.{name}.iter() {{
+                        args_.push({});
+                    }}"#,
+                    to_os_string(opt, "value_"),
+                ),
+            }
+        }))
+        .collect::<String>();
+    let to_args_ordered = if ast.ordered.is_empty() {
+        String::new()
+    } else {
+        let values = ast
+            .ordered
+            .iter()
+            .enumerate()
+            .map(|(index, opt)| match opt.property {
+                ArgProperty::Required => format!(
+                    "values_.push({});",
+                    to_os_string(opt, &format!("&self.{index}")),
+                ),
+                ArgProperty::Optional => format!(
+                    "if let Some(value_) = &self.{index} {{ values_.push({}); }}",
+                    to_os_string(opt, "value_"),
+                ),
+                _ => format!(
+                    "for value_ in self.{index}.iter() {{ values_.push({}); }}",
+                    to_os_string(opt, "value_"),
+                ),
+            })
+            .collect::<String>();
+
+        format!(
+            r#"
+                let mut values_ = ::std::vec::Vec::new();
+                {values}
+                if !values_.is_empty() {{
+                    args_.push(::std::ffi::OsString::from("--"));
+                    args_.extend(values_);
+                }}
+            "#
+        )
+    };
+
+    // Rows of field names, values, and origins for `show_config()`.
+    let config_rows = flags
+        .iter()
+        .filter(|&flag| flag.output)
+        .map(|flag| (flag.name.to_string(), format!("&self.{}", flag.name)))
+        .chain(
+            ast.options
+                .iter()
+                .chain(&ast.positional)
+                .map(|opt| (opt.name.to_string(), opt))
+                .chain(
+                    ast.ordered
+                        .iter()
+                        .enumerate()
+                        .map(|(index, opt)| (index.to_string(), opt)),
+                )
+                .map(|(name, opt)| {
+                    let value = if opt.occurrences {
+                        format!("self.{name}.values()")
+                    } else {
+                        format!("&self.{name}")
+                    };
+
+                    (name, value)
+                }),
+        )
+        .chain(ast.flag_enums.iter().map(|flag_enum| {
+            (
+                flag_enum.name.to_string(),
+                format!("&self.{}", flag_enum.name),
+            )
+        }))
+        .chain(
+            ast.verbosity
+                .iter()
+                .map(|name| (name.to_string(), format!("self.{name}.level()"))),
+        )
+        .fold(String::new(), |mut rows, (name, value)| {
+            let origin = ast.origins.as_ref().map_or_else(
+                || "None".to_string(),
+                |origins| format!("self.{origins}.source_of({name:?})"),
+            );
+            write!(
+                rows,
+                "({name:?}, ::std::format!(\"{{:?}}\", {value}), {origin}),"
+            )
+            .unwrap();
+            rows
+        });
+
+    // `impl Default` with the declared defaults, for `#[impl_default]`.
+    let default_impl = if ast.impl_default {
+        let fields = flags
+            .iter()
+            .filter(|&flag| flag.output)
+            .map(|flag| format!("{}: {},", flag.name, flag.default))
+            .chain(ast.options.iter().chain(&ast.positional).map(|opt| {
+                let value = match (&opt.default, opt.property) {
+                    (Some(default), _) if opt.color_choice => format!(
+                        "::onlyargs::types::ColorChoice::from_name({default}).unwrap_or_default()"
+                    ),
+                    (Some(default), _) => to_default_value(opt, default),
+                    (None, ArgProperty::Optional) => "None".to_string(),
+                    (None, _) => "::std::default::Default::default()".to_string(),
+                };
+
+                format!("{}: {value},", opt.name)
+            }))
+            .chain(ast.ordered.iter().enumerate().map(|(index, opt)| {
+                let value = match opt.property {
+                    ArgProperty::Optional => "None",
+                    _ => "::std::default::Default::default()",
+                };
+
+                format!("{index}: {value},")
+            }))
+            .chain(ast.flag_enums.iter().map(|flag_enum| {
+                let value = if flag_enum.optional {
+                    "None"
+                } else {
+                    "::std::default::Default::default()"
+                };
+
+                format!("{}: {value},", flag_enum.name)
+            }))
+            .chain(
+                ast.origins
+                    .iter()
+                    .chain(&ast.verbosity)
+                    .map(|name| format!("{name}: ::std::default::Default::default(),")),
+            )
+            .collect::<String>();
+
+        format!(
+            "impl ::std::default::Default for {name} {{
+                fn default() -> Self {{
+                    Self {{ {fields} }}
+                }}
+            }}"
+        )
+    } else {
+        String::new()
+    };
+
+    // `init_logging()` is only available with a `Verbosity` field.
+    let init_logging = ast
+        .verbosity
+        .as_ref()
+        .map(|verbosity| {
+            format!(
+                "/// Install the loggers enabled by `onlyargs` features, at the parsed verbosity.
+                #[allow(dead_code)]
+                pub fn init_logging(&self) {{
+                    ::onlyargs::logging::init(self.{verbosity});
+                }}"
+            )
+        })
+        .unwrap_or_default();
+
+    // Track included files so the help text is rebuilt when they change.
+    let includes = ast
+        .includes
+        .iter()
+        .fold(String::new(), |mut includes, path| {
+            write!(includes, "const _: &str = ::std::include_str!({path:?});").unwrap();
+            includes
+        });
 
     // Produce final code.
     let code = TokenStream::from_str(&format!(
         r#"
-            impl ::onlyargs::OnlyArgs for {name} {{
-                const HELP: &'static str = ::std::concat!(
-                    env!("CARGO_PKG_NAME"),
-                    " v",
-                    env!("CARGO_PKG_VERSION"),
-                    "\n",
-                    env!("CARGO_PKG_DESCRIPTION"),
-                    "\n",
-                    {doc_comment:?},
-                    "\nUsage:\n  ",
-                    {bin_name:?},
-                    " [flags] [options]",
-                    {positional_header:?},
-                    "\n\nFlags:\n",
-                    {flags_help:?},
-                    "\nOptions:\n",
-                    {options_help:?},
-                    {positional_help:?},
-                    {footer:?},
-                );
+            const _: () = {{
+                {includes}
 
-                const VERSION: &'static str = concat!(
-                    env!("CARGO_PKG_NAME"),
-                    " v",
-                    env!("CARGO_PKG_VERSION"),
-                    "\n",
-                );
+                    // With `partial_`, parsing stops at the first unknown argument, which is returned
+                    // with the remaining arguments.
+                    fn parse_<I: Iterator<Item = ::std::ffi::OsString>>(
+                        args: I,
+                        layers_: &::onlyargs::source::Layers,
+                        partial_: bool,
+                    ) -> ::std::result::Result<
+                        (
+                            ::onlyargs::ParseResult<{name}>,
+                            Vec<::onlyargs::Warning>,
+                            Vec<::std::ffi::OsString>,
+                        ),
+                        ::onlyargs::CliError,
+                    >
+                    {{
+                        use ::onlyargs::traits::*;
+                        use ::std::option::Option::{{None, Some}};
+                        use ::std::result::Result::{{Err, Ok}};
 
-                {help_impl}
+                        {preprocess_args}
+                        {error_macros}
 
-                fn parse(args: Vec<::std::ffi::OsString>) ->
-                    ::std::result::Result<Self, ::onlyargs::CliError>
-                {{
-                    use ::onlyargs::traits::*;
-                    use ::std::option::Option::{{None, Some}};
-                    use ::std::result::Result::{{Err, Ok}};
+                        {warnings_var}
+                        {remainder_var}
 
-                    {flags_vars}
-                    {options_vars}
-                    {positional_var}
+                        {flags_vars}
+                        {options_vars}
+                        {positional_var}
+                        {ordered_vars}
+                        {flag_enums_vars}
+                        {verbosity_var}
+                        {origins_var}
 
-                    let mut args = args.into_iter();
-                    while let Some(arg) = args.next() {{
-                        match arg.to_str() {{
-                            // TODO: Add an attribute to disable help/version.
-                            Some("--help") | Some("-h") => Self::help(),
-                            Some("--version") | Some("-V") => Self::version(),
-                            {flags_matchers}
-                            {options_matchers}
-                            {positional_matcher}
+                        let mut args = args.enumerate();
+                        while let Some((index_, arg)) = args.next() {{
+                            match arg.to_str() {{
+                                {help_matchers}
+                                {version_matchers}
+                                {flags_matchers}
+                                {verbosity_matchers}
+                                {flag_enums_matchers}
+                                {options_matchers}
+                                {positional_matcher}
+                            }}
                         }}
+
+                        {options_fallbacks}
+                        {origins_positional}
+                        {errors_check}
+
+                        let parsed_ = {name} {{
+                            {flags_idents}
+                            {options_idents}
+                            {positional_ident}
+                            {ordered_idents}
+                            {flag_enums_idents}
+                            {verbosity_ident}
+                            {origins_ident}
+                        }};
+                        {validate_call}
+
+                        Ok((::onlyargs::ParseResult::Args(parsed_), warnings_, remainder_))
                     }}
 
-                    Ok(Self {{
-                        {flags_idents}
-                        {options_idents}
-                        {positional_ident}
-                    }})
+                impl ::onlyargs::OnlyArgs for {name} {{
+                    const HELP: &'static str = {help};
+
+                    const SHORT_HELP: &'static str = {short_help};
+
+                    const VERSION: &'static str = concat!(
+                        {app_name},
+                        " v",
+                        {app_version},
+                        "\n",
+                        {long_version}
+                    );
+
+                    const USAGE: &'static str = {usage:?};
+
+                    const SPEC: &'static [::onlyargs::ArgSpec] = &[{flags_spec}{options_spec}];
+
+                    const SHORT_VERSION: &'static str = concat!(
+                        {app_name},
+                        " v",
+                        {app_version},
+                        "\n",
+                    );
+
+                    {help_width_impl}
+
+                    {plain_help_impl}
+
+                    {pager_impl}
+
+                    {generate_impl}
+
+                    {exit_codes_impl}
+
+                    {help_output_impl}
+
+                    {preprocess_impl}
+
+                    {validate_impl}
+
+                    {complete_impl}
+
+                    fn layers() -> ::std::result::Result<
+                        ::onlyargs::source::Layers,
+                        ::onlyargs::CliError,
+                    > {{
+                        ::std::result::Result::Ok(
+                            ::onlyargs::source::Layers::new()
+                                .push(::onlyargs::source::Env)
+                                {dotenv_layer}
+                                {config_layer}
+                        )
+                    }}
+
+                    fn parse(args: Vec<::std::ffi::OsString>) ->
+                        ::std::result::Result<Self, ::onlyargs::CliError>
+                    {{
+                        Self::try_parse(args).map(::onlyargs::ParseResult::or_exit)
+                    }}
+
+                    fn try_parse(args: Vec<::std::ffi::OsString>) ->
+                        ::std::result::Result<::onlyargs::ParseResult<Self>, ::onlyargs::CliError>
+                    {{
+                        use ::onlyargs::traits::*;
+                        use ::std::option::Option::{{None, Some}};
+
+                        {config_path_layers}
+
+                        Self::try_parse_with_layers(args, &layers_)
+                    }}
+
+                    fn parse_with_layers(
+                        args: Vec<::std::ffi::OsString>,
+                        layers_: &::onlyargs::source::Layers,
+                    ) -> ::std::result::Result<Self, ::onlyargs::CliError>
+                    {{
+                        Self::try_parse_with_layers(args, layers_).map(::onlyargs::ParseResult::or_exit)
+                    }}
+
+                    fn try_parse_with_layers(
+                        args: Vec<::std::ffi::OsString>,
+                        layers_: &::onlyargs::source::Layers,
+                    ) -> ::std::result::Result<::onlyargs::ParseResult<Self>, ::onlyargs::CliError>
+                    {{
+                        let (result_, warnings_) = Self::try_parse_with_warnings(args, layers_)?;
+                        for warning_ in &warnings_ {{
+                            <Self as ::onlyargs::OnlyArgs>::warn(warning_);
+                        }}
+
+                        ::std::result::Result::Ok(result_)
+                    }}
+
+                    fn parse_with_warnings(args: Vec<::std::ffi::OsString>) -> ::std::result::Result<
+                        (Self, Vec<::onlyargs::Warning>),
+                        ::onlyargs::CliError,
+                    >
+                    {{
+                        use ::onlyargs::traits::*;
+                        use ::std::option::Option::{{None, Some}};
+
+                        {config_path_layers}
+
+                        Self::try_parse_with_warnings(args, &layers_)
+                            .map(|(result_, warnings_)| (result_.or_exit(), warnings_))
+                    }}
+
+                    fn try_parse_with_warnings(
+                        args: Vec<::std::ffi::OsString>,
+                        layers_: &::onlyargs::source::Layers,
+                    ) -> ::std::result::Result<
+                        (::onlyargs::ParseResult<Self>, Vec<::onlyargs::Warning>),
+                        ::onlyargs::CliError,
+                    >
+                    {{
+                        parse_(args.into_iter(), layers_, false).map(|(result_, warnings_, _)| (result_, warnings_))
+                    }}
+
+                    fn parse_iter<I>(args: I) -> ::std::result::Result<Self, ::onlyargs::CliError>
+                    where
+                        I: IntoIterator<Item = ::std::ffi::OsString>,
+                    {{
+                        use ::onlyargs::traits::*;
+                        use ::std::option::Option::{{None, Some}};
+
+                        {collect_args}
+                        {config_path_layers}
+
+                        let (result_, warnings_, _) = parse_(args.into_iter(), &layers_, false)?;
+                        for warning_ in &warnings_ {{
+                            <Self as ::onlyargs::OnlyArgs>::warn(warning_);
+                        }}
+
+                        ::std::result::Result::Ok(result_.or_exit())
+                    }}
+
+                    fn parse_partial(args: Vec<::std::ffi::OsString>) -> ::std::result::Result<
+                        (Self, Vec<::std::ffi::OsString>),
+                        ::onlyargs::CliError,
+                    >
+                    {{
+                        use ::onlyargs::traits::*;
+                        use ::std::option::Option::{{None, Some}};
+
+                        {config_path_layers}
+
+                        let (result_, warnings_, remainder_) = parse_(args.into_iter(), &layers_, true)?;
+                        for warning_ in &warnings_ {{
+                            <Self as ::onlyargs::OnlyArgs>::warn(warning_);
+                        }}
+
+                        ::std::result::Result::Ok((result_.or_exit(), remainder_))
+                    }}
                 }}
-            }}
+
+                {default_impl}
+
+                impl {name} {{
+                    /// Reconstruct a command line that parses back into the same values.
+                    ///
+                    /// Options use their long names, and positional arguments follow `--`.
+                    #[allow(dead_code)]
+                    #[must_use]
+                    pub fn to_args(&self) -> ::std::vec::Vec<::std::ffi::OsString> {{
+                        #[allow(unused_mut)]
+                        let mut args_ = ::std::vec::Vec::new();
+                        {to_args}
+                        {to_args_ordered}
+
+                        args_
+                    }}
+
+                    /// Format every field with its value and origin in aligned columns.
+                    ///
+                    /// Origins are only shown when the struct has an `#[origins]` field.
+                    #[allow(dead_code)]
+                    #[must_use]
+                    pub fn show_config(&self) -> ::std::string::String {{
+                        ::onlyargs::source::format_config(&[{config_rows}])
+                    }}
+
+                    {init_logging}
+                }}
+            }};
         "#
     ));
 
@@ -470,6 +2664,11 @@ pub fn derive_parser(input: TokenStream) -> TokenStream {
     }
 }
 
+// The help layout used without `#[help_template(...)]`.
+const DEFAULT_TEMPLATE: &str =
+    "{name} v{version}\n{description}\n{links}{header}{doc}\nUsage:\n  {usage}\n{required}\n\
+    Flags:\n{flags}\nOptions:\n{options}{headings}{positional}{examples}{footer}";
+
 // 1 hyphen + 1 char + 1 trailing space.
 const SHORT_PAD: usize = 3;
 // 2 leading spaces + 2 hyphens + 2 trailing spaces.
@@ -482,12 +2681,185 @@ fn to_arg_name(ident: &Ident) -> String {
     name
 }
 
+/// The flag for an enum variant, like `--dry-run` for `DryRun`.
+fn to_variant_arg_name(variant: &Ident) -> String {
+    to_snake_case(variant).replace('_', "-")
+}
+
+/// Statement that warns about a deprecated argument on the command line, if it is deprecated.
+fn deprecation_warning(name: &Ident, deprecated: Option<&str>) -> String {
+    deprecated
+        .map(|note| {
+            let note = if note.is_empty() {
+                "None".to_string()
+            } else {
+                format!("Some({note:?}.to_string())")
+            };
+
+            format!(
+                r#"warnings_.push(::onlyargs::Warning::Deprecated {{
+                    argument: "--{arg}".to_string(),
+                    note: {note},
+                }});"#,
+                arg = to_arg_name(name),
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Produce an `onlyargs::ArgSpec` expression for `OnlyArgs::SPEC`.
+fn to_spec(
+    name: &str,
+    view: ArgView<'_>,
+    kind: &str,
+    multiple: bool,
+    required: bool,
+    default: Option<&str>,
+    complete: &str,
+) -> String {
+    let mut spec = format!(
+        "::onlyargs::ArgSpec::new({name:?}, ::onlyargs::ArgKind::{kind}, {help:?})
+            .with_multiple({multiple})
+            .with_required({required})
+            .with_complete(::onlyargs::Complete::{complete})",
+        help = view.doc.join("\n"),
+    );
+    if let Some(short) = view.short {
+        write!(spec, ".with_short({short:?})").unwrap();
+    }
+    if let Some(value_name) = view.value_name {
+        write!(spec, ".with_value_name({value_name:?})").unwrap();
+    }
+    if let Some(default) = default {
+        write!(spec, ".with_default({default:?})").unwrap();
+    }
+    spec.push(',');
+
+    spec
+}
+
+/// Convert a reference to an option value to an `OsString` for `to_args()`.
+fn to_os_string(opt: &ArgOption, value: &str) -> String {
+    match opt.ty_help {
+        _ if opt.color_choice => {
+            format!("::std::ffi::OsString::from(::std::string::ToString::to_string({value}))")
+        }
+        ArgType::Float | ArgType::Integer | ArgType::Url | ArgType::Uuid | ArgType::Version => {
+            format!("::std::ffi::OsString::from(::std::string::ToString::to_string({value}))")
+        }
+        ArgType::CString => {
+            format!("::std::ffi::OsString::from({value}.to_string_lossy().into_owned())")
+        }
+        ArgType::OsString | ArgType::Path | ArgType::String if opt.convert => {
+            format!("::std::ffi::OsString::from(&**{value})")
+        }
+        ArgType::OsString | ArgType::Path | ArgType::String => {
+            format!("::std::ffi::OsString::from({value})")
+        }
+    }
+}
+
+/// Call the `ArgExt` parser for an option on `value`, and convert the result for pointer types.
+fn to_parser_call(opt: &ArgOption, value: &str, name: &str) -> String {
+    let parser = if opt.expand_tilde {
+        "parse_expanded_path"
+    } else {
+        opt.ty_help.parser()
+    };
+    let call = format!("{value}.{parser}({name})");
+
+    if opt.convert {
+        format!("{call}.map(::std::convert::Into::into)")
+    } else {
+        call
+    }
+}
+
+/// The expression for the `#[default(...)]` value of an option.
+fn to_default_value(opt: &ArgOption, default: &Literal) -> String {
+    match opt.ty_help {
+        // Parsing a `&OsStr` as a path cannot fail.
+        _ if opt.expand_tilde => format!(
+            r#"::std::convert::Into::into({{
+                use ::onlyargs::traits::ArgExt as _;
+                ::std::ffi::OsStr::new({default}).parse_expanded_path("").unwrap_or_default()
+            }})"#
+        ),
+        ArgType::Path if opt.convert => {
+            format!("::std::convert::Into::into(::std::path::PathBuf::from({default}))")
+        }
+        _ if opt.convert => {
+            format!("::std::convert::Into::into(::std::string::String::from({default}))")
+        }
+        ty => ty.default_value(default),
+    }
+}
+
+/// Initialize an option field in the struct literal with its parsed value.
+fn to_field_init(opt: &ArgOption, value: &str) -> String {
+    if opt.occurrences {
+        format!(
+            "{name}: ::onlyargs::Occurrences::from({value}),",
+            name = opt.name
+        )
+    } else if opt.color_choice {
+        format!(
+            "{name}: ::onlyargs::types::ColorChoice::from_name(&{value}).unwrap_or_default(),",
+            name = opt.name
+        )
+    } else {
+        format!("{name}: {value},", name = opt.name)
+    }
+}
+
+/// The name of a tuple struct field in `OnlyArgs::SPEC`, e.g. `path`.
+fn to_ordered_name(opt: &ArgOption) -> String {
+    opt.value_name().to_ascii_lowercase()
+}
+
+/// The tuple struct field name used in error messages, e.g. `<PATH>`.
+fn to_ordered_label(opt: &ArgOption) -> String {
+    format!("<{}>", opt.value_name())
+}
+
+/// The "Arguments" help section for tuple struct fields. The summary only has the first line of
+/// each description.
+fn to_ordered_help(ordered: &[ArgOption], summary: bool) -> String {
+    if ordered.is_empty() {
+        return String::new();
+    }
+
+    let labels = ordered.iter().map(to_ordered_label).collect::<Vec<_>>();
+    let width = labels.iter().map(String::len).max().unwrap_or_default();
+    let pad = format!("\n{}", " ".repeat(width + 4));
+
+    ordered
+        .iter()
+        .zip(labels)
+        .fold("\nArguments:\n".to_string(), |mut help, (opt, label)| {
+            let doc = if summary {
+                &opt.doc[..opt.doc.len().min(1)]
+            } else {
+                &opt.doc[..]
+            };
+            writeln!(help, "  {label:<width$}  {}", doc.join(&pad)).unwrap();
+            help
+        })
+}
+
+/// The option name used in errors, e.g. `--width`.
+///
+/// `OnlyArgs::format_error()` shows the short name and value placeholder from `OnlyArgs::SPEC`.
+fn to_label(opt: &ArgOption) -> String {
+    format!("--{}", to_arg_name(&opt.name))
+}
+
 fn to_help(view: ArgView, max_width: usize) -> String {
     let name = to_arg_name(view.name);
-    let ty = match view.ty_help.as_ref() {
-        Some(ty_help) => ty_help.as_str(),
-        None => "",
-    };
+    let ty = view
+        .value_name
+        .map(|value_name| format!(" {value_name}"))
+        .unwrap_or_default();
     let pad = " ".repeat(max_width + LONG_PAD);
     let help = view.doc.join(&format!("\n{pad}"));
 
@@ -501,18 +2873,69 @@ fn to_help(view: ArgView, max_width: usize) -> String {
     }
 }
 
+/// Produce a `concat!()` expression for the help text from a template.
+///
+/// Package metadata placeholders are replaced with the expressions in `metadata`, e.g. `env!()`
+/// calls. Other placeholders are replaced with the string literals in `values`. `{bin_name}` is
+/// left in place to be replaced at runtime.
+fn to_help_str(template: &str, metadata: &[(&str, &str)], values: &[(&str, &String)]) -> String {
+    let parts = split_template(template)
+        .into_iter()
+        .fold(String::new(), |mut parts, part| {
+            match part {
+                TemplatePart::Placeholder(name) => {
+                    if let Some((_, expr)) = metadata.iter().find(|(key, _)| *key == name) {
+                        write!(parts, "{expr},").unwrap();
+                    } else if let Some((_, value)) = values.iter().find(|(key, _)| *key == name) {
+                        write!(parts, "{value:?},").unwrap();
+                    } else {
+                        write!(parts, "{:?},", format!("{{{name}}}")).unwrap();
+                    }
+                }
+                TemplatePart::Text(text) => write!(parts, "{text:?},").unwrap(),
+            }
+            parts
+        });
+
+    format!("::std::concat!({parts})")
+}
+
+/// Produce the long and short help for a section of the help text.
+///
+/// Arguments with `#[display_order(n)]` are listed first. The rest keep their declaration order,
+/// or are sorted by name with `#[sort_help]`.
+fn to_help_section(mut views: Vec<ArgView>, sort: bool) -> (String, String) {
+    views.sort_by_key(|view| {
+        (
+            view.order.unwrap_or(usize::MAX),
+            sort.then(|| to_arg_name(view.name)),
+        )
+    });
+
+    let max_width = get_max_width(views.iter().copied());
+    views.iter().fold(
+        (String::new(), String::new()),
+        |(mut help, mut short_help), &view| {
+            help.push_str(&to_help(view, max_width));
+            short_help.push_str(&to_help(view.summary(), max_width));
+
+            (help, short_help)
+        },
+    )
+}
+
 fn get_max_width<'a, I>(iter: I) -> usize
 where
     I: Iterator<Item = ArgView<'a>>,
 {
     iter.fold(0, |acc, view| {
         let short = view.short.map(|_| SHORT_PAD).unwrap_or_default();
-        let ty = match view.ty_help.as_ref() {
-            Some(ty_help) => ty_help.as_str(),
-            None => "",
-        };
+        let ty = view
+            .value_name
+            .map(|value_name| value_name.len() + 1)
+            .unwrap_or_default();
 
-        acc.max(view.name.to_string().len() + ty.len() + short)
+        acc.max(view.name.to_string().len() + ty + short)
     })
 }
 
@@ -1,22 +1,177 @@
 use myn::prelude::*;
-use proc_macro::{Delimiter, Ident, Literal, Span, TokenStream};
+use onlyargs::suggest::did_you_mean;
+use proc_macro::{Delimiter, Ident, Literal, Span, TokenStream, TokenTree};
+use std::{collections::HashMap, env, fs, path::PathBuf};
 
+// Struct attributes are mostly independent switches.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug)]
 pub(crate) struct ArgumentStruct {
     pub(crate) name: Ident,
     pub(crate) flags: Vec<ArgFlag>,
     pub(crate) options: Vec<ArgOption>,
     pub(crate) positional: Option<ArgOption>,
+    // Positional arguments of a tuple struct, in field order.
+    pub(crate) ordered: Vec<ArgOption>,
     pub(crate) doc: Vec<String>,
+    pub(crate) header: Vec<String>,
     pub(crate) footer: Vec<String>,
+    pub(crate) config_file: Option<String>,
+    pub(crate) dotenv: Option<String>,
+    pub(crate) origins: Option<Ident>,
+    pub(crate) verbosity: Option<Ident>,
+    pub(crate) flag_enums: Vec<ArgFlagEnum>,
+    pub(crate) headings: Vec<String>,
+    pub(crate) usage: Vec<String>,
+    pub(crate) sort_help: bool,
+    pub(crate) template: Option<String>,
+    pub(crate) help_width: Option<usize>,
+    pub(crate) includes: Vec<String>,
+    pub(crate) examples: Vec<String>,
+    pub(crate) plain_help: bool,
+    pub(crate) pager: bool,
+    pub(crate) generate: bool,
+    pub(crate) impl_default: bool,
+    pub(crate) group_required: bool,
+    pub(crate) app_name: Option<String>,
+    pub(crate) app_version: Option<String>,
+    pub(crate) long_version: Option<String>,
+    pub(crate) authors: Option<String>,
+    pub(crate) homepage: Option<String>,
+    pub(crate) repository: Option<String>,
+    pub(crate) help_stderr: bool,
+    pub(crate) help_exit_code: Option<i32>,
+    pub(crate) error_exit_code: Option<i32>,
+    pub(crate) capture_help: bool,
+    pub(crate) capture_version: bool,
+    pub(crate) collect_errors: bool,
+    pub(crate) preprocess: Option<String>,
+    pub(crate) validate: Option<String>,
+}
+
+/// Placeholders supported by `#[help_template(...)]`.
+const TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "authors",
+    "bin_name",
+    "description",
+    "doc",
+    "examples",
+    "flags",
+    "footer",
+    "header",
+    "headings",
+    "homepage",
+    "links",
+    "name",
+    "options",
+    "positional",
+    "repository",
+    "required",
+    "usage",
+    "version",
+];
+
+/// Attributes of the argument struct.
+const STRUCT_ATTRS: &[&str] = &[
+    "authors",
+    "capture_help",
+    "capture_version",
+    "collect_errors",
+    "config_file",
+    "deny_missing_docs",
+    "dotenv",
+    "env_prefix",
+    "error_exit_code",
+    "example",
+    "footer",
+    "generate",
+    "group_required",
+    "header",
+    "help_exit_code",
+    "help_stderr",
+    "help_template",
+    "help_width",
+    "homepage",
+    "impl_default",
+    "long_version",
+    "name",
+    "pager",
+    "plain_help",
+    "preprocess",
+    "repository",
+    "sort_help",
+    "usage",
+    "validate",
+    "verbatim_doc_comment",
+    "version",
+];
+
+/// Attributes of the argument fields.
+///
+/// `#[deprecated]` is left out because it is a built-in attribute that is also valid on the struct.
+const FIELD_ATTRS: &[&str] = &[
+    "alias",
+    "arg_type",
+    "complete",
+    "config_path",
+    "default",
+    "display_order",
+    "env",
+    "expand_tilde",
+    "flag_enum",
+    "help",
+    "help_heading",
+    "hide",
+    "long",
+    "origins",
+    "positional",
+    "possible_values",
+    "required",
+    "short",
+    "value_name",
+    "verbatim_doc_comment",
+];
+
+/// Attributes of tuple struct fields.
+const TUPLE_FIELD_ATTRS: &[&str] = &[
+    "arg_type",
+    "complete",
+    "help",
+    "value_name",
+    "verbatim_doc_comment",
+];
+
+/// A piece of a help template.
+#[derive(Debug)]
+pub(crate) enum TemplatePart<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
 }
 
 #[derive(Debug)]
 pub(crate) enum Argument {
     Flag(ArgFlag),
     Option(ArgOption),
+    Origins(Ident),
+    Verbosity(Ident),
+    FlagEnum(ArgFlagEnum),
 }
 
+/// A field set by one of several mutually exclusive flags, from `#[flag_enum(...)]`.
+#[derive(Debug)]
+pub(crate) struct ArgFlagEnum {
+    pub(crate) name: Ident,
+    // The path of the enum, without `Option<...>`.
+    pub(crate) ty: String,
+    pub(crate) variants: Vec<Ident>,
+    // One flag for each variant, in the same order.
+    pub(crate) flags: Vec<ArgFlag>,
+    pub(crate) optional: bool,
+    pub(crate) heading: Option<String>,
+}
+
+// Flag attributes are mostly independent switches.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug)]
 pub(crate) struct ArgFlag {
     pub(crate) name: Ident,
@@ -24,8 +179,17 @@ pub(crate) struct ArgFlag {
     pub(crate) doc: Vec<String>,
     pub(crate) default: bool,
     pub(crate) output: bool,
+    pub(crate) hidden: bool,
+    pub(crate) heading: Option<String>,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) order: Option<usize>,
+    pub(crate) deprecated: Option<String>,
+    // The flag can be repeated, like `-vv`.
+    pub(crate) counted: bool,
 }
 
+// Option attributes are mostly independent switches.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug)]
 pub(crate) struct ArgOption {
     pub(crate) name: Ident,
@@ -33,24 +197,57 @@ pub(crate) struct ArgOption {
     pub(crate) ty_help: ArgType,
     pub(crate) doc: Vec<String>,
     pub(crate) default: Option<Literal>,
+    pub(crate) env: Option<String>,
+    pub(crate) config_path: bool,
+    pub(crate) expand_tilde: bool,
+    pub(crate) hidden: bool,
+    pub(crate) heading: Option<String>,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) choices: Vec<String>,
+    pub(crate) order: Option<usize>,
+    pub(crate) value_name: Option<String>,
     pub(crate) property: ArgProperty,
+    pub(crate) deprecated: Option<String>,
+    // The field is `Occurrences<T>`, which is parsed like `Vec<T>`.
+    pub(crate) occurrences: bool,
+    // The field is `ColorChoice`, which is parsed like `String`.
+    pub(crate) color_choice: bool,
+    // The field is a pointer like `Box<str>`, converted from the parsed `String` or `PathBuf`.
+    pub(crate) convert: bool,
+    pub(crate) complete: Option<Complete>,
+}
+
+/// A completion hint from `#[complete(...)]`.
+#[derive(Debug)]
+pub(crate) enum Complete {
+    File,
+    Dir,
+    Hostname,
+    // The path of a function that returns the candidates.
+    Fn(String),
 }
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct ArgView<'a> {
     pub(crate) name: &'a Ident,
     pub(crate) short: Option<char>,
-    pub(crate) ty_help: Option<ArgType>,
+    pub(crate) value_name: Option<&'a str>,
     pub(crate) doc: &'a [String],
+    pub(crate) order: Option<usize>,
 }
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) enum ArgType {
+    CString,
     Float,
     Integer,
     OsString,
     Path,
     String,
+    // Types from optional `onlyargs` features.
+    Url,
+    Uuid,
+    Version,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -62,23 +259,216 @@ pub(crate) enum ArgProperty {
 }
 
 impl ArgumentStruct {
+    #[allow(clippy::too_many_lines)]
     pub(crate) fn parse(input: TokenStream) -> Result<Self, TokenStream> {
         let mut input = input.into_token_iter();
-        let attrs = input.parse_attributes()?;
+        let mut attrs = input.parse_attributes()?;
         input.parse_visibility()?;
         input.expect_ident("struct")?;
 
+        let mut env_prefix = None;
+        let mut config_file = None;
+        let mut dotenv = None;
+        let mut usage = vec![];
+        let mut examples = vec![];
+        let mut sort_help = false;
+        let mut plain_help = false;
+        let mut pager = false;
+        let mut generate = false;
+        let mut impl_default = false;
+        let mut help_stderr = false;
+        let mut help_exit_code = None;
+        let mut error_exit_code = None;
+        let mut capture_help = false;
+        let mut capture_version = false;
+        let mut collect_errors = false;
+        let mut preprocess = None;
+        let mut validate = None;
+        let mut group_required = false;
+        let mut deny_missing_docs = false;
+        let mut app_name = None;
+        let mut app_version = None;
+        let mut long_version = None;
+        let mut authors = None;
+        let mut homepage = None;
+        let mut repository = None;
+        let mut template = None;
+        let mut help_width = None;
+        for attr in &mut attrs {
+            match attr.name.to_string().as_str() {
+                "authors" => authors = Some(get_package_attr(attr, "authors")?),
+                "capture_help" => capture_help = true,
+                "capture_version" => capture_version = true,
+                "collect_errors" => collect_errors = true,
+                "config_file" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    config_file = Some(stream.try_lit()?.as_string()?);
+                }
+                "deny_missing_docs" => deny_missing_docs = true,
+                "dotenv" => {
+                    dotenv = Some(if attr.tree.peek().is_some() {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                        stream.try_lit()?.as_string()?
+                    } else {
+                        ".env".to_string()
+                    });
+                }
+                "env_prefix" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    env_prefix = Some(stream.try_lit()?.as_string()?);
+                }
+                "error_exit_code" => error_exit_code = Some(get_exit_code(attr)?),
+                "example" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    examples.push(stream.try_lit()?.as_string()?);
+                }
+                "group_required" => group_required = true,
+                "homepage" => homepage = Some(get_package_attr(attr, "homepage")?),
+                "impl_default" => impl_default = true,
+                "help_exit_code" => help_exit_code = Some(get_exit_code(attr)?),
+                "help_stderr" => help_stderr = true,
+                "help_template" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+                    let lit = stream.try_lit()?;
+                    let span = lit.span();
+                    let text = lit.as_string()?;
+
+                    for part in split_template(&text) {
+                        if let TemplatePart::Placeholder(name) = part {
+                            if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+                                return Err(spanned_error(
+                                    format!("Unknown placeholder `{{{name}}}` in #[help_template]"),
+                                    span,
+                                ));
+                            }
+                        }
+                    }
+
+                    template = Some(text);
+                }
+                "help_width" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+                    let lit = stream.try_lit()?;
+
+                    help_width = Some(
+                        lit.to_string()
+                            .parse()
+                            .ok()
+                            .filter(|&width| width > 0)
+                            .ok_or_else(|| {
+                                spanned_error("Expected a positive integer", lit.span())
+                            })?,
+                    );
+                }
+                "long_version" => {
+                    let stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    // The arguments are passed to `concat!()` as-is.
+                    long_version = Some(stream.collect::<TokenStream>().to_string());
+                }
+                "name" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    app_name = Some(stream.try_lit()?.as_string()?);
+                }
+                "generate" => generate = true,
+                "pager" => pager = true,
+                "plain_help" => plain_help = true,
+                "preprocess" => {
+                    let stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    // The path to the preprocessing function is called as-is.
+                    preprocess = Some(stream.collect::<TokenStream>().to_string());
+                }
+                "repository" => repository = Some(get_package_attr(attr, "repository")?),
+                "sort_help" => sort_help = true,
+                "usage" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    usage.push(stream.try_lit()?.as_string()?);
+                }
+                "validate" => {
+                    let stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    // The path to the validation function is called as-is.
+                    validate = Some(stream.collect::<TokenStream>().to_string());
+                }
+                "version" => {
+                    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                    app_version = Some(stream.try_lit()?.as_string()?);
+                }
+                _ => check_unknown_attr(attr, STRUCT_ATTRS, FIELD_ATTRS, "the struct")?,
+            }
+        }
+
         let name = input.try_ident()?;
-        let content = input.expect_group(Delimiter::Brace)?;
-        let fields = Argument::parse(content)?;
+        let (fields, ordered) = match input.peek() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                let content = input.expect_group(Delimiter::Parenthesis)?;
+                input.expect_punct(';')?;
+
+                (vec![], Argument::parse_tuple(content, deny_missing_docs)?)
+            }
+            _ => {
+                let content = input.expect_group(Delimiter::Brace)?;
+                let fields = Argument::parse(
+                    content,
+                    env_prefix.as_deref(),
+                    group_required,
+                    deny_missing_docs,
+                )?;
+
+                (fields, vec![])
+            }
+        };
 
         let mut flags = vec![];
         let mut options = vec![];
         let mut positional = None;
+        let mut origins = None;
+        let mut verbosity = None;
+        let mut flag_enums = vec![];
+        let mut headings = vec![];
 
         for field in fields {
+            let heading = match &field {
+                Argument::Flag(ArgFlag { heading, .. })
+                | Argument::Option(ArgOption { heading, .. })
+                | Argument::FlagEnum(ArgFlagEnum { heading, .. }) => heading.as_ref(),
+                Argument::Origins(_) | Argument::Verbosity(_) => None,
+            };
+            if let Some(heading) = heading {
+                if !headings.contains(heading) {
+                    headings.push(heading.clone());
+                }
+            }
+
             match field {
                 Argument::Flag(flag) => flags.push(flag),
+                Argument::FlagEnum(flag_enum) => flag_enums.push(flag_enum),
+                Argument::Origins(name) => match origins {
+                    None => origins = Some(name),
+                    Some(_) => {
+                        return Err(spanned_error(
+                            "#[origins] can only be specified once.",
+                            name.span(),
+                        ));
+                    }
+                },
+                Argument::Verbosity(name) => match verbosity {
+                    None => verbosity = Some(name),
+                    Some(_) => {
+                        return Err(spanned_error(
+                            "`Verbosity` can only be specified once.",
+                            name.span(),
+                        ));
+                    }
+                },
                 Argument::Option(opt) => match (opt.property, &positional) {
                     (ArgProperty::Positional { .. }, None) => positional = Some(opt),
                     (ArgProperty::Positional { .. }, Some(_)) => {
@@ -87,20 +477,58 @@ impl ArgumentStruct {
                             opt.name.span(),
                         ));
                     }
+                    _ if opt.config_path
+                        && options.iter().any(|opt: &ArgOption| opt.config_path) =>
+                    {
+                        return Err(spanned_error(
+                            "#[config_path] can only be specified once.",
+                            opt.name.span(),
+                        ));
+                    }
                     _ => options.push(opt),
                 },
             }
         }
 
-        let doc = get_doc_comment(&attrs)
-            .into_iter()
-            .map(trim_with_indent)
-            .collect();
+        // Captured help and version arguments are parsed into user-defined flags.
+        for (capture, field) in [(capture_help, "help"), (capture_version, "version")] {
+            if capture
+                && !flags
+                    .iter()
+                    .any(|flag: &ArgFlag| flag.name.to_string() == field)
+            {
+                return Err(spanned_error(
+                    format!("#[capture_{field}] requires a `{field}: bool` field"),
+                    name.span(),
+                ));
+            }
+        }
 
-        let footer = get_attr_strings(&attrs, "footer")
-            .into_iter()
-            .map(|line| line.trim_end().to_string())
-            .collect();
+        // `impl Default` needs a value for every field.
+        if impl_default {
+            let required = options.iter().chain(&positional).find(|&opt| {
+                opt.is_required()
+                    || matches!(opt.property, ArgProperty::Positional { required: true })
+            });
+            if let Some(opt) = required {
+                return Err(spanned_error(
+                    "#[impl_default] requires a #[default(...)] on required options",
+                    opt.name.span(),
+                ));
+            }
+            if let Some(opt) = ordered.iter().find(|&opt| opt.is_required()) {
+                return Err(spanned_error(
+                    "#[impl_default] cannot be used with required tuple struct fields",
+                    opt.name.span(),
+                ));
+            }
+        }
+
+        let doc = get_doc_lines(&attrs);
+
+        let mut includes = vec![];
+        let header = get_help_text(&attrs, "header", &mut includes)?;
+        let footer = get_help_text(&attrs, "footer", &mut includes)?;
 
         match input.next() {
             None => Ok(Self {
@@ -108,8 +536,41 @@ impl ArgumentStruct {
                 flags,
                 options,
                 positional,
+                ordered,
                 doc,
+                header,
                 footer,
+                config_file,
+                dotenv,
+                origins,
+                verbosity,
+                flag_enums,
+                headings,
+                usage,
+                sort_help,
+                template,
+                help_width,
+                includes,
+                examples,
+                plain_help,
+                pager,
+                generate,
+                impl_default,
+                group_required,
+                app_name,
+                app_version,
+                long_version,
+                authors,
+                homepage,
+                repository,
+                help_stderr,
+                help_exit_code,
+                error_exit_code,
+                capture_help,
+                capture_version,
+                collect_errors,
+                preprocess,
+                validate,
             }),
             tree => Err(spanned_error("Unexpected token", tree.as_span())),
         }
@@ -117,26 +578,56 @@ impl ArgumentStruct {
 }
 
 impl Argument {
-    fn parse(mut input: TokenIter) -> Result<Vec<Self>, TokenStream> {
+    #[allow(clippy::too_many_lines)]
+    fn parse(
+        mut input: TokenIter,
+        env_prefix: Option<&str>,
+        group_required: bool,
+        deny_missing_docs: bool,
+    ) -> Result<Vec<Self>, TokenStream> {
         let mut args = vec![];
 
         while input.peek().is_some() {
             let attrs = input.parse_attributes()?;
 
             // Parse attributes
-            let doc = get_doc_comment(&attrs)
-                .into_iter()
-                .map(trim_with_indent)
-                .collect();
+            let doc = get_doc_lines(&attrs);
+            let mut aliases = vec![];
+            let mut arg_type = None;
+            let mut choices = vec![];
+            let mut complete = None;
+            let mut config_path = false;
             let mut default = None;
+            let mut deprecated = None;
+            let mut order = None;
+            let mut env = None;
+            let mut expand_tilde = false;
+            let mut flag_enum = None;
+            let mut heading = None;
+            let mut help = vec![];
+            let mut hidden = false;
             let mut long = false;
+            let mut origins = false;
             let mut short = None;
             let mut required = false;
             let mut positional = false;
+            let mut value_name = None;
+
+            // Errors for misused attributes point at the attribute name.
+            let mut spans = HashMap::new();
 
             for mut attr in attrs {
                 let name = attr.name.to_string();
+                spans.insert(name.clone(), attr.name.span());
                 match name.as_str() {
+                    "alias" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                        aliases.push(stream.try_lit()?.as_string()?);
+                    }
+                    "arg_type" => arg_type = Some(parse_arg_type(&mut attr)?),
+                    "complete" => complete = Some(parse_complete(&mut attr)?),
+                    "config_path" => config_path = true,
                     "default" => {
                         let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
 
@@ -149,8 +640,61 @@ impl Argument {
                                 })
                         })?);
                     }
+                    "deprecated" => deprecated = Some(parse_deprecated(&mut attr)?),
+                    "display_order" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+                        let lit = stream.try_lit()?;
+
+                        order = Some(lit.to_string().parse().map_err(|_| {
+                            spanned_error("Expected an unsigned integer", lit.span())
+                        })?);
+                    }
+                    "env" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                        env = Some(stream.try_lit()?.as_string()?);
+                    }
+                    "expand_tilde" => expand_tilde = true,
+                    "flag_enum" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+                        let mut variants = vec![];
+
+                        while stream.peek().is_some() {
+                            variants.push(stream.try_ident()?);
+                            let _ = stream.expect_punct(',');
+                        }
+                        if variants.is_empty() {
+                            return Err(spanned_error(
+                                "#[flag_enum(...)] requires at least one variant",
+                                attr.name.span(),
+                            ));
+                        }
+
+                        flag_enum = Some(variants);
+                    }
+                    "help" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+                        let text = stream.try_lit()?.as_string()?;
+
+                        help.extend(text.lines().map(str::to_string));
+                    }
+                    "help_heading" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                        heading = Some(stream.try_lit()?.as_string()?);
+                    }
+                    "hide" => hidden = true,
                     "long" => long = true,
+                    "origins" => origins = true,
                     "positional" => positional = true,
+                    "possible_values" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                        while stream.peek().is_some() {
+                            choices.push(stream.try_lit()?.as_string()?);
+                            let _ = stream.expect_punct(',');
+                        }
+                    }
                     "required" => required = true,
                     "short" => {
                         let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
@@ -158,18 +702,124 @@ impl Argument {
 
                         short = Some(lit.as_char()?);
                     }
-                    _ => (),
+                    "value_name" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                        value_name = Some(stream.try_lit()?.as_string()?);
+                    }
+                    _ => check_unknown_attr(&attr, FIELD_ATTRS, STRUCT_ATTRS, "fields")?,
                 }
             }
 
+            // `#[help(...)]` replaces the doc comment in the help text.
+            let doc = if help.is_empty() { doc } else { help };
+
             input.parse_visibility()?;
             let name = input.try_ident()?;
             input.expect_punct(':')?;
             let (path, span) = input.parse_path()?;
             let _ = input.expect_punct(',');
+            let attr_span = |attr: &str| spans.get(attr).copied().unwrap_or(span);
+
+            if origins {
+                if path != "Origins" && !path.ends_with("::Origins") {
+                    return Err(field_error(
+                        "#[origins] can only be used on `onlyargs::source::Origins`",
+                        &name,
+                        span,
+                    ));
+                }
+
+                args.push(Self::Origins(name));
+                continue;
+            }
+
+            // Each variant of a `#[flag_enum(...)]` field is a flag.
+            if let Some(variants) = flag_enum {
+                let misused = [
+                    (short.is_some(), "short"),
+                    (default.is_some(), "default"),
+                    (env.is_some(), "env"),
+                    (required, "required"),
+                    (positional, "positional"),
+                ];
+                if let Some((_, attr)) = misused.iter().find(|(misused, _)| *misused) {
+                    return Err(field_error(
+                        &format!("#[{attr}] cannot be used with #[flag_enum(...)]"),
+                        &name,
+                        attr_span(attr),
+                    ));
+                }
+
+                if deny_missing_docs && !hidden {
+                    require_doc(&doc, &name)?;
+                }
+
+                let flag_names = variants.iter().map(to_snake_case).collect::<Vec<_>>();
+                let flags = variants
+                    .iter()
+                    .zip(&flag_names)
+                    .map(|(variant, flag_name)| {
+                        let others = flag_names
+                            .iter()
+                            .filter(|&other| other != flag_name)
+                            .map(|other| format!("--{}", other.replace('_', "-")))
+                            .collect::<Vec<_>>();
+                        let mut doc = doc.clone();
+                        if !others.is_empty() {
+                            push_doc_note(
+                                &mut doc,
+                                &format!("[conflicts with: {}]", others.join(", ")),
+                            );
+                        }
+
+                        let mut flag =
+                            ArgFlag::new_priv(Ident::new(flag_name, variant.span()), None, doc);
+                        flag.hidden = hidden;
+                        flag.heading.clone_from(&heading);
+                        flag.order = order;
+                        flag
+                    })
+                    .collect();
+
+                let inner = path
+                    .strip_prefix("Option<")
+                    .and_then(|inner| inner.strip_suffix('>'));
+                args.push(Self::FlagEnum(ArgFlagEnum {
+                    name,
+                    ty: inner.unwrap_or(&path).to_string(),
+                    variants,
+                    flags,
+                    optional: inner.is_some(),
+                    heading,
+                }));
+                continue;
+            }
+
+            // `Verbosity` stands for the `--verbose` and `--quiet` flags.
+            if path == "Verbosity" || path.ends_with("::Verbosity") {
+                args.push(Self::Verbosity(name));
+                continue;
+            }
+
+            if deny_missing_docs && !hidden {
+                require_doc(&doc, &name)?;
+            }
+
+            // `ColorChoice` is a `String` option with fixed values and no short name by default.
+            let color_choice = path == "ColorChoice" || path.ends_with("::ColorChoice");
+            if color_choice {
+                if choices.is_empty() {
+                    choices = ["auto", "always", "never"].map(str::to_string).to_vec();
+                }
+                default = default.or_else(|| Some(Literal::string("auto")));
+                value_name = value_name.or_else(|| Some("WHEN".to_string()));
+            }
 
             let short = if long {
                 None
+            } else if color_choice {
+                short
             } else {
                 short.or_else(|| {
                     // TODO: Add an attribute to disable short names
@@ -178,50 +828,113 @@ impl Argument {
             };
 
             if path == "bool" {
-                if required {
-                    return Err(spanned_error(
+                let misused = [
+                    (
+                        arg_type.is_some(),
+                        "arg_type",
+                        "#[arg_type(...)] cannot be used on `bool`",
+                    ),
+                    (
+                        required,
+                        "required",
                         "#[required] can only be used on `Vec<T>`",
-                        span,
-                    ));
-                }
-                if positional {
-                    return Err(spanned_error(
+                    ),
+                    (
+                        positional,
+                        "positional",
                         "#[positional] can only be used on `Vec<T>`",
-                        span,
-                    ));
+                    ),
+                    (env.is_some(), "env", "#[env(...)] cannot be used on `bool`"),
+                    (
+                        complete.is_some(),
+                        "complete",
+                        "#[complete(...)] cannot be used on `bool`",
+                    ),
+                    (
+                        config_path,
+                        "config_path",
+                        "#[config_path] can only be used on `PathBuf` and `Option<PathBuf>`",
+                    ),
+                    (
+                        expand_tilde,
+                        "expand_tilde",
+                        "#[expand_tilde] can only be used on path types",
+                    ),
+                    (
+                        !choices.is_empty(),
+                        "possible_values",
+                        "#[possible_values(...)] can only be used on `String` types",
+                    ),
+                ];
+                if let Some((_, attr, msg)) = misused.iter().find(|(misused, _, _)| *misused) {
+                    return Err(field_error(msg, &name, attr_span(attr)));
                 }
 
                 let mut flag = ArgFlag::new(name, short, doc);
+                flag.hidden = hidden;
+                flag.heading = heading;
+                flag.order = order;
+                if !aliases.is_empty() {
+                    push_doc_note(&mut flag.doc, &aliases_note(&aliases));
+                }
+                if let Some(note) = deprecated.as_ref() {
+                    push_doc_note(&mut flag.doc, &deprecated_note(note));
+                }
+                flag.aliases = aliases;
+                flag.deprecated = deprecated;
                 match default {
                     Some(lit) if lit.to_string() == r#""true""# => flag.default = true,
                     _ => (),
                 }
                 args.push(Self::Flag(flag));
             } else {
-                let mut opt = ArgOption::new(span, name, short, doc, &path)?;
+                let path = if color_choice { "String" } else { &path };
+                let mut opt = ArgOption::new(span, name, short, doc, path, arg_type)?;
+                opt.color_choice = color_choice;
+                opt.hidden = hidden;
+                opt.heading = heading;
+                opt.order = order;
+                opt.value_name = value_name;
+                opt.complete = complete;
+
+                // Options without an explicit `#[env(...)]` get one from the struct-level prefix.
+                let env = match (env, env_prefix, opt.property) {
+                    (None, Some(prefix), ArgProperty::Required | ArgProperty::Optional) => {
+                        Some(to_env_name(prefix, &opt.name))
+                    }
+                    (env, _, _) => env,
+                };
 
-                apply_default(span, &mut opt, default)?;
-                apply_required(span, &mut opt, required)?;
-                apply_positional(span, &mut opt, positional)?;
+                apply_default(attr_span("default"), &mut opt, default)?;
+                apply_required(attr_span("required"), &mut opt, required)?;
+                apply_positional(attr_span("positional"), &mut opt, positional)?;
+                apply_env(attr_span("env"), &mut opt, env)?;
+                apply_config_path(attr_span("config_path"), &mut opt, config_path)?;
+                apply_expand_tilde(attr_span("expand_tilde"), &mut opt, expand_tilde)?;
+                apply_choices(attr_span("possible_values"), &mut opt, choices)?;
+                apply_deprecated(attr_span("deprecated"), &mut opt, deprecated)?;
+                opt.aliases = aliases;
 
+                if let Some(env) = opt.env.as_ref() {
+                    push_doc_note(&mut opt.doc, &format!("[env: {env}]"));
+                }
+                if !opt.aliases.is_empty() {
+                    push_doc_note(&mut opt.doc, &aliases_note(&opt.aliases));
+                }
+                if !opt.choices.is_empty() {
+                    let note = format!("[possible values: {}]", opt.choices.join(", "));
+                    push_doc_note(&mut opt.doc, &note);
+                }
+                if let Some(note) = opt.deprecated.as_ref() {
+                    push_doc_note(&mut opt.doc, &deprecated_note(note));
+                }
+                // Required options are obvious from their section with `#[group_required]`.
                 if let Some(default) = opt.default.as_ref() {
-                    let default = default.to_string();
-                    if let Some(line) = opt.doc.last_mut() {
-                        line.push_str(&format!(" [default: {default}]"));
-                    } else {
-                        opt.doc.push(format!("[default: {default}]"));
-                    }
-                } else if matches!(
-                    opt.property,
-                    ArgProperty::Required
-                        | ArgProperty::Positional { required: true }
-                        | ArgProperty::MultiValue { required: true }
-                ) {
-                    if let Some(line) = opt.doc.last_mut() {
-                        line.push_str(" [required]");
-                    } else {
-                        opt.doc.push("[required]".to_string());
-                    }
+                    push_doc_note(&mut opt.doc, &format!("[default: {default}]"));
+                } else if matches!(opt.property, ArgProperty::Positional { required: true })
+                    || (opt.is_required() && !group_required)
+                {
+                    push_doc_note(&mut opt.doc, "[required]");
                 }
 
                 args.push(Self::Option(opt));
@@ -230,6 +943,138 @@ impl Argument {
 
         Ok(args)
     }
+
+    /// Parse the fields of a tuple struct. Each field is a positional argument, in order.
+    fn parse_tuple(
+        mut input: TokenIter,
+        deny_missing_docs: bool,
+    ) -> Result<Vec<ArgOption>, TokenStream> {
+        let mut ordered: Vec<ArgOption> = vec![];
+
+        while input.peek().is_some() {
+            let attrs = input.parse_attributes()?;
+
+            // Parse attributes
+            let doc = get_doc_lines(&attrs);
+            let mut arg_type = None;
+            let mut complete = None;
+            let mut help = vec![];
+            let mut value_name = None;
+
+            for mut attr in attrs {
+                match attr.name.to_string().as_str() {
+                    "arg_type" => arg_type = Some(parse_arg_type(&mut attr)?),
+                    "complete" => complete = Some(parse_complete(&mut attr)?),
+                    "help" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+                        let text = stream.try_lit()?.as_string()?;
+
+                        help.extend(text.lines().map(str::to_string));
+                    }
+                    "value_name" => {
+                        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+                        value_name = Some(stream.try_lit()?.as_string()?);
+                    }
+                    _ => check_unknown_attr(
+                        &attr,
+                        TUPLE_FIELD_ATTRS,
+                        FIELD_ATTRS,
+                        "tuple struct fields",
+                    )?,
+                }
+            }
+
+            // `#[help(...)]` replaces the doc comment in the help text.
+            let doc = if help.is_empty() { doc } else { help };
+
+            input.parse_visibility()?;
+            let (path, span) = input.parse_path()?;
+            let _ = input.expect_punct(',');
+
+            // The parser state for field `N` is kept in a variable named `_N`.
+            let name = Ident::new(&format!("_{}", ordered.len()), span);
+            if path == "bool" {
+                return Err(spanned_error(
+                    "Tuple struct fields take a value and cannot be `bool`",
+                    span,
+                ));
+            }
+            if deny_missing_docs {
+                require_doc(&doc, &name)?;
+            }
+
+            let mut opt = ArgOption::new(span, name, None, doc, &path, arg_type)?;
+            opt.value_name = value_name;
+            opt.complete = complete;
+
+            // Values fill the fields in order, so optional fields and a `Vec` come last.
+            match (ordered.last().map(|prev| prev.property), opt.property) {
+                (Some(ArgProperty::MultiValue { .. }), _) => {
+                    return Err(spanned_error(
+                        "Only the last field of a tuple struct can be `Vec<T>`",
+                        span,
+                    ));
+                }
+                (Some(ArgProperty::Optional), ArgProperty::Required) => {
+                    return Err(spanned_error(
+                        "Required fields must come before `Option<T>` fields in a tuple struct",
+                        span,
+                    ));
+                }
+                _ => (),
+            }
+            if ordered
+                .iter()
+                .any(|prev| prev.value_name() == opt.value_name())
+            {
+                return Err(spanned_error(
+                    format!(
+                        "Another field is also named `{}`, use #[value_name(...)] to tell them apart",
+                        opt.value_name(),
+                    ),
+                    span,
+                ));
+            }
+
+            ordered.push(opt);
+        }
+
+        Ok(ordered)
+    }
+}
+
+/// Split a help template into text and `{placeholder}` parts.
+///
+/// Braces that do not surround a lowercase identifier are treated as text.
+pub(crate) fn split_template(template: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = vec![];
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let placeholder = rest[start + 1..].find('}').and_then(|end| {
+            let name = &rest[start + 1..start + 1 + end];
+
+            (!name.is_empty() && name.chars().all(|ch| ch.is_ascii_lowercase() || ch == '_'))
+                .then_some(name)
+        });
+
+        if let Some(name) = placeholder {
+            if start > 0 {
+                parts.push(TemplatePart::Text(&rest[..start]));
+            }
+            parts.push(TemplatePart::Placeholder(name));
+            rest = &rest[start + name.len() + 2..];
+        } else {
+            parts.push(TemplatePart::Text(&rest[..=start]));
+            rest = &rest[start + 1..];
+        }
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest));
+    }
+
+    parts
 }
 
 fn apply_default(
@@ -237,27 +1082,142 @@ fn apply_default(
     opt: &mut ArgOption,
     default: Option<Literal>,
 ) -> Result<(), TokenStream> {
-    match (default.is_some(), &opt.property) {
-        (true, ArgProperty::Required) => opt.default = default,
-        (true, _) => {
-            return Err(spanned_error(
+    match (default, &opt.property) {
+        (Some(default), ArgProperty::Required) => {
+            check_default(opt.ty_help, &default)?;
+            opt.default = Some(default);
+        }
+        (Some(_), _) => {
+            return Err(field_error(
                 "#[default(...)] can only be used on primitive types",
+                &opt.name,
                 span,
             ));
         }
-        (false, _) => (),
+        (None, _) => (),
     }
 
     Ok(())
 }
 
+/// Check a `#[default(...)]` literal that is parsed at runtime, so mistakes are compile errors.
+fn check_default(ty: ArgType, default: &Literal) -> Result<(), TokenStream> {
+    let msg = match ty {
+        ArgType::CString if default.as_string()?.contains('\0') => {
+            "#[default(...)] must not contain a NUL byte"
+        }
+        ArgType::Url if !is_url(&default.as_string()?) => "#[default(...)] is not a URL",
+        ArgType::Uuid if !is_uuid(&default.as_string()?) => "#[default(...)] is not a UUID",
+        ArgType::Version if !is_version(&default.as_string()?) => {
+            "#[default(...)] is not a semantic version"
+        }
+        _ => return Ok(()),
+    };
+
+    Err(spanned_error(msg, default.span()))
+}
+
+/// Check for an absolute URL, like `https://example.com/`.
+///
+/// This catches missing schemes, empty hosts, and invalid ports. The `url` crate checks the rest.
+fn is_url(value: &str) -> bool {
+    let (scheme, rest) = match value.split_once(':') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let valid_scheme = scheme.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "+-.".contains(ch));
+    if !valid_scheme {
+        return false;
+    }
+    if !["ftp", "http", "https", "ws", "wss"].contains(&scheme.to_ascii_lowercase().as_str()) {
+        return true;
+    }
+
+    // Special schemes require a host, with an optional port.
+    let authority = rest
+        .trim_start_matches(['/', '\\'])
+        .split(['/', '\\', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let (host, port) = match host.rfind(':') {
+        Some(index) if !host[index..].contains(']') => (&host[..index], Some(&host[index + 1..])),
+        _ => (host, None),
+    };
+
+    !host.is_empty()
+        && !host.contains([' ', '<', '>', '^', '|'])
+        && port.map_or(true, |port| port.is_empty() || port.parse::<u16>().is_ok())
+}
+
+/// Check for a UUID, like `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+///
+/// The hyphens are optional, unless the UUID is in braces or has a `urn:uuid:` prefix.
+fn is_uuid(value: &str) -> bool {
+    let wrapped = value
+        .strip_prefix("urn:uuid:")
+        .or_else(|| value.strip_prefix('{')?.strip_suffix('}'));
+    let groups = wrapped
+        .unwrap_or(value)
+        .split('-')
+        .map(|group| {
+            group
+                .chars()
+                .all(|ch| ch.is_ascii_hexdigit())
+                .then_some(group.len())
+        })
+        .collect::<Option<Vec<_>>>();
+
+    match groups.as_deref() {
+        Some([8, 4, 4, 4, 12]) => true,
+        Some([32]) => wrapped.is_none(),
+        _ => false,
+    }
+}
+
+/// Check for a semantic version, like `1.2.3-beta.1+build.5`.
+fn is_version(value: &str) -> bool {
+    let number = |part: &str| {
+        part.chars().all(|ch| ch.is_ascii_digit()) && (part == "0" || !part.starts_with('0'))
+    };
+    let identifier = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+    };
+
+    let (value, build) = value
+        .split_once('+')
+        .map_or((value, None), |(v, b)| (v, Some(b)));
+    let (core, pre) = value
+        .split_once('-')
+        .map_or((value, None), |(c, p)| (c, Some(p)));
+    let core = core.split('.').collect::<Vec<_>>();
+
+    core.len() == 3
+        && core
+            .iter()
+            .all(|part| !part.is_empty() && number(part) && part.parse::<u64>().is_ok())
+        && pre.map_or(true, |pre| {
+            pre.split('.').all(|part| {
+                identifier(part) && (!part.chars().all(|ch| ch.is_ascii_digit()) || number(part))
+            })
+        })
+        && build.map_or(true, |build| build.split('.').all(identifier))
+}
+
 fn apply_required(span: Span, opt: &mut ArgOption, required: bool) -> Result<(), TokenStream> {
     match (required, &mut opt.property) {
         (false, _) => (),
         (true, ArgProperty::MultiValue { required }) => *required = true,
         _ => {
-            return Err(spanned_error(
+            return Err(field_error(
                 "#[required] can only be used on `Vec<T>`",
+                &opt.name,
                 span,
             ));
         }
@@ -274,8 +1234,65 @@ fn apply_positional(span: Span, opt: &mut ArgOption, positional: bool) -> Result
             }
         }
         (true, _) => {
-            return Err(spanned_error(
+            return Err(field_error(
                 "#[positional] can only be used on `Vec<T>`",
+                &opt.name,
+                span,
+            ));
+        }
+        (false, _) => (),
+    }
+
+    Ok(())
+}
+
+fn apply_deprecated(
+    span: Span,
+    opt: &mut ArgOption,
+    deprecated: Option<String>,
+) -> Result<(), TokenStream> {
+    match (deprecated.is_some(), &opt.property) {
+        (true, ArgProperty::Positional { .. }) => {
+            return Err(field_error(
+                "#[deprecated] cannot be used on positional arguments",
+                &opt.name,
+                span,
+            ));
+        }
+        (true, _) => opt.deprecated = deprecated,
+        (false, _) => (),
+    }
+
+    Ok(())
+}
+
+fn apply_env(span: Span, opt: &mut ArgOption, env: Option<String>) -> Result<(), TokenStream> {
+    match (env.is_some(), &opt.property) {
+        (true, ArgProperty::Required | ArgProperty::Optional) => opt.env = env,
+        (true, _) => {
+            return Err(field_error(
+                "#[env(...)] can only be used on primitive types and `Option<T>`",
+                &opt.name,
+                span,
+            ));
+        }
+        (false, _) => (),
+    }
+
+    Ok(())
+}
+
+fn apply_expand_tilde(
+    span: Span,
+    opt: &mut ArgOption,
+    expand_tilde: bool,
+) -> Result<(), TokenStream> {
+    match (expand_tilde, opt.ty_help) {
+        (true, ArgType::Path) => opt.expand_tilde = true,
+        (true, _) => {
+            return Err(field_error(
+                "#[expand_tilde] can only be used on path types",
+                &opt.name,
                 span,
             ));
         }
@@ -285,6 +1302,216 @@ fn apply_positional(span: Span, opt: &mut ArgOption, positional: bool) -> Result
     Ok(())
 }
 
+fn apply_config_path(
+    span: Span,
+    opt: &mut ArgOption,
+    config_path: bool,
+) -> Result<(), TokenStream> {
+    match (config_path, &opt.property, opt.ty_help) {
+        (true, ArgProperty::Required | ArgProperty::Optional, ArgType::Path) => {
+            opt.config_path = true;
+        }
+        (true, _, _) => {
+            return Err(field_error(
+                "#[config_path] can only be used on `PathBuf` and `Option<PathBuf>`",
+                &opt.name,
+                span,
+            ));
+        }
+        (false, _, _) => (),
+    }
+
+    Ok(())
+}
+
+fn apply_choices(span: Span, opt: &mut ArgOption, choices: Vec<String>) -> Result<(), TokenStream> {
+    match (choices.is_empty(), &opt.property, opt.ty_help) {
+        (true, _, _) => (),
+        (
+            false,
+            ArgProperty::Required | ArgProperty::Optional | ArgProperty::MultiValue { .. },
+            ArgType::String,
+        ) => opt.choices = choices,
+        (false, _, _) => {
+            return Err(field_error(
+                "#[possible_values(...)] can only be used on `String` types",
+                &opt.name,
+                span,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// An error for an attribute that does not fit the field `name`.
+fn field_error(msg: &str, name: &Ident, span: Span) -> TokenStream {
+    spanned_error(format!("{msg} (field `{name}`)"), span)
+}
+
+/// Fail for a field without a description in the help text, for `#[deny_missing_docs]`.
+fn require_doc(doc: &[String], name: &Ident) -> Result<(), TokenStream> {
+    if doc.iter().all(|line| line.trim().is_empty()) {
+        Err(spanned_error(
+            format!("Missing doc comment or #[help(...)] for `{name}` (#[deny_missing_docs])"),
+            name.span(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check an attribute that is not handled where it appears.
+///
+/// Attributes that are handled elsewhere are fine. Attributes that belong in the other position and
+/// likely misspellings of `known` attributes are errors. Anything else belongs to another crate or
+/// the compiler, and is ignored.
+fn check_unknown_attr(
+    attr: &Attribute,
+    known: &[&str],
+    others: &[&str],
+    position: &str,
+) -> Result<(), TokenStream> {
+    let name = attr.name.to_string();
+    let span = attr.name.span();
+
+    if known.contains(&name.as_str()) {
+        Ok(())
+    } else if others.contains(&name.as_str()) {
+        Err(spanned_error(
+            format!("#[{name}] cannot be used on {position}"),
+            span,
+        ))
+    } else if let Some(suggestion) = did_you_mean(&name, known) {
+        Err(spanned_error(
+            format!("Unknown attribute #[{name}], did you mean #[{suggestion}]?"),
+            span,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Parse `#[arg_type(...)]`, which names the kind of value for a type the derive cannot detect.
+fn parse_arg_type(attr: &mut Attribute) -> Result<ArgType, TokenStream> {
+    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+    let ident = stream.try_ident()?;
+
+    match ident.to_string().as_str() {
+        "float" => Ok(ArgType::Float),
+        "integer" => Ok(ArgType::Integer),
+        "os_string" => Ok(ArgType::OsString),
+        "path" => Ok(ArgType::Path),
+        "string" => Ok(ArgType::String),
+        "url" => Ok(ArgType::Url),
+        "uuid" => Ok(ArgType::Uuid),
+        "version" => Ok(ArgType::Version),
+        _ => Err(spanned_error(
+            "Expected `float`, `integer`, `os_string`, `path`, `string`, `url`, `uuid`, or `version`",
+            ident.span(),
+        )),
+    }
+}
+
+/// Parse `#[complete(file)]`, `#[complete(dir)]`, `#[complete(hostname)]`, or
+/// `#[complete(fn = path)]`.
+fn parse_complete(attr: &mut Attribute) -> Result<Complete, TokenStream> {
+    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+    let ident = stream.try_ident()?;
+
+    match ident.to_string().as_str() {
+        "file" => Ok(Complete::File),
+        "dir" => Ok(Complete::Dir),
+        "hostname" => Ok(Complete::Hostname),
+        "fn" => {
+            stream.expect_punct('=')?;
+
+            Ok(Complete::Fn(stream.parse_path()?.0))
+        }
+        _ => Err(spanned_error(
+            "Expected `file`, `dir`, `hostname`, or `fn = path`",
+            ident.span(),
+        )),
+    }
+}
+
+/// Parse Rust's own `#[deprecated]` attribute. Only the note is used.
+fn parse_deprecated(attr: &mut Attribute) -> Result<String, TokenStream> {
+    match attr.tree.peek() {
+        None => Ok(String::new()),
+        Some(TokenTree::Punct(_)) => {
+            attr.tree.expect_punct('=')?;
+
+            attr.tree.try_lit()?.as_string()
+        }
+        Some(_) => {
+            let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+            let mut note = String::new();
+
+            while stream.peek().is_some() {
+                let key = stream.try_ident()?;
+                stream.expect_punct('=')?;
+                let value = stream.try_lit()?.as_string()?;
+                if key.to_string() == "note" {
+                    note = value;
+                }
+                let _ = stream.expect_punct(',');
+            }
+
+            Ok(note)
+        }
+    }
+}
+
+fn deprecated_note(note: &str) -> String {
+    if note.is_empty() {
+        "[deprecated]".to_string()
+    } else {
+        format!("[deprecated: {note}]")
+    }
+}
+
+fn aliases_note(aliases: &[String]) -> String {
+    let aliases = aliases
+        .iter()
+        .map(|alias| format!("--{alias}"))
+        .collect::<Vec<_>>();
+
+    format!("[aliases: {}]", aliases.join(", "))
+}
+
+fn to_env_name(prefix: &str, ident: &Ident) -> String {
+    let mut name = ident.to_string();
+    name.make_ascii_uppercase();
+
+    format!("{prefix}{name}")
+}
+
+/// Convert an enum variant like `DryRun` to a field-like name like `dry_run`.
+pub(crate) fn to_snake_case(variant: &Ident) -> String {
+    variant
+        .to_string()
+        .chars()
+        .enumerate()
+        .fold(String::new(), |mut name, (i, ch)| {
+            if ch.is_ascii_uppercase() && i > 0 {
+                name.push('_');
+            }
+            name.push(ch.to_ascii_lowercase());
+            name
+        })
+}
+
+/// Append a note like `[required]` to the last line of the help text.
+fn push_doc_note(doc: &mut Vec<String>, note: &str) {
+    if let Some(line) = doc.last_mut() {
+        line.push(' ');
+        line.push_str(note);
+    } else {
+        doc.push(note.to_string());
+    }
+}
+
 impl ArgFlag {
     fn new(name: Ident, short: Option<char>, doc: Vec<String>) -> Self {
         ArgFlag {
@@ -293,6 +1520,12 @@ impl ArgFlag {
             doc,
             default: false,
             output: true,
+            hidden: false,
+            heading: None,
+            aliases: vec![],
+            order: None,
+            deprecated: None,
+            counted: false,
         }
     }
 
@@ -303,15 +1536,22 @@ impl ArgFlag {
             doc,
             default: false,
             output: false,
+            hidden: false,
+            heading: None,
+            aliases: vec![],
+            order: None,
+            deprecated: None,
+            counted: false,
         }
     }
 
-    pub(crate) fn as_view(&self) -> ArgView {
+    pub(crate) fn as_view(&self) -> ArgView<'_> {
         ArgView {
             name: &self.name,
             short: self.short,
-            ty_help: None,
+            value_name: None,
             doc: &self.doc,
+            order: self.order,
         }
     }
 }
@@ -362,6 +1602,39 @@ const MULTI_INTEGERS: [&str; 12] = [
     "Vec<u128>",
     "Vec<usize>",
 ];
+const OCCURRENCES: [&str; 3] = [
+    "::onlyargs::Occurrences<",
+    "onlyargs::Occurrences<",
+    "Occurrences<",
+];
+// Pointer types are parsed as `String` or `PathBuf` and converted with `Into`.
+const SHARED_STRS: [&str; 9] = [
+    "Box<str>",
+    "::std::rc::Rc<str>",
+    "std::rc::Rc<str>",
+    "rc::Rc<str>",
+    "Rc<str>",
+    "::std::sync::Arc<str>",
+    "std::sync::Arc<str>",
+    "sync::Arc<str>",
+    "Arc<str>",
+];
+const BOXED_PATHS: [&str; 4] = [
+    "Box<::std::path::Path>",
+    "Box<std::path::Path>",
+    "Box<path::Path>",
+    "Box<Path>",
+];
+const C_STRINGS: [&str; 4] = [
+    "::std::ffi::CString",
+    "std::ffi::CString",
+    "ffi::CString",
+    "CString",
+];
+// Types from optional `onlyargs` features.
+const URLS: [&str; 3] = ["::url::Url", "url::Url", "Url"];
+const UUIDS: [&str; 3] = ["::uuid::Uuid", "uuid::Uuid", "Uuid"];
+const VERSIONS: [&str; 3] = ["::semver::Version", "semver::Version", "Version"];
 const OPTIONAL_PATHS: [&str; 4] = [
     "Option<::std::path::PathBuf>",
     "Option<std::path::PathBuf>",
@@ -391,15 +1664,66 @@ const OPTIONAL_INTEGERS: [&str; 12] = [
 ];
 
 impl ArgOption {
+    #[allow(clippy::too_many_lines)]
     fn new(
         span: Span,
         name: Ident,
         short: Option<char>,
         doc: Vec<String>,
         path: &str,
+        arg_type: Option<ArgType>,
     ) -> Result<Self, TokenStream> {
+        // `Occurrences<T>` is collected into a `Vec<T>` and converted when the struct is built.
+        let occurrences = OCCURRENCES
+            .iter()
+            .find_map(|prefix| path.strip_prefix(prefix)?.strip_suffix('>'));
+        let path = &occurrences.map_or_else(|| path.to_string(), |inner| format!("Vec<{inner}>"));
+        let path = path.as_str();
+
+        // Feature and pointer types are accepted bare, in `Option<T>`, and in `Vec<T>`.
+        let feature_type = |path: &str| {
+            [
+                (&SHARED_STRS[..], ArgType::String),
+                (&BOXED_PATHS, ArgType::Path),
+                (&C_STRINGS, ArgType::CString),
+                (&URLS, ArgType::Url),
+                (&UUIDS, ArgType::Uuid),
+                (&VERSIONS, ArgType::Version),
+            ]
+            .into_iter()
+            .find_map(|(paths, ty)| paths.contains(&path).then_some(ty))
+        };
+        let wrapped = |prefix: &str| path.strip_prefix(prefix)?.strip_suffix('>');
+        let convert = [Some(path), wrapped("Option<"), wrapped("Vec<")]
+            .into_iter()
+            .flatten()
+            .any(|path| SHARED_STRS.contains(&path) || BOXED_PATHS.contains(&path));
+
+        // `#[arg_type(...)]` names the value type, so only the wrapper needs to be recognized.
+        let explicit = arg_type.map(|ty| {
+            let property = if wrapped("Option<").is_some() {
+                ArgProperty::Optional
+            } else if wrapped("Vec<").is_some() {
+                ArgProperty::MultiValue { required: false }
+            } else {
+                ArgProperty::Required
+            };
+
+            (property, ty)
+        });
+        let feature = explicit
+            .or_else(|| Some((ArgProperty::Required, feature_type(path)?)))
+            .or_else(|| Some((ArgProperty::Optional, feature_type(wrapped("Option<")?)?)))
+            .or_else(|| {
+                let ty = feature_type(wrapped("Vec<")?)?;
+
+                Some((ArgProperty::MultiValue { required: false }, ty))
+            });
+
         // Parse the argument type and decide what properties it should start with.
-        let property = if OPTIONAL_PATHS.contains(&path)
+        let property = if let Some((property, _)) = feature {
+            property
+        } else if OPTIONAL_PATHS.contains(&path)
             || OPTIONAL_OS_STRINGS.contains(&path)
             || OPTIONAL_FLOATS.contains(&path)
             || OPTIONAL_INTEGERS.contains(&path)
@@ -421,14 +1745,19 @@ impl ArgOption {
         {
             ArgProperty::Required
         } else {
-            return Err(spanned_error(
-                "Expected bool, PathBuf, String, OsString, integer, or float",
+            return Err(field_error(
+                "Expected bool, PathBuf, String, OsString, integer, float, Url, Uuid, Version, \
+                Box<str>, Rc<str>, Arc<str>, Box<Path>, or CString; \
+                use #[arg_type(...)] for other names of these types",
+                &name,
                 span,
             ));
         };
 
         // Decide the type to show in the help message.
-        let ty_help = if OPTIONAL_PATHS.contains(&path)
+        let ty_help = if let Some((_, ty)) = feature {
+            ty
+        } else if OPTIONAL_PATHS.contains(&path)
             || REQUIRED_PATHS.contains(&path)
             || MULTI_PATHS.contains(&path)
         {
@@ -460,16 +1789,64 @@ impl ArgOption {
             ty_help,
             doc,
             default: None,
+            env: None,
+            config_path: false,
+            expand_tilde: false,
+            hidden: false,
+            heading: None,
+            aliases: vec![],
+            choices: vec![],
+            order: None,
+            value_name: None,
             property,
+            deprecated: None,
+            occurrences: occurrences.is_some(),
+            color_choice: false,
+            convert: arg_type.is_none() && convert,
+            complete: None,
         })
     }
 
-    pub(crate) fn as_view(&self) -> ArgView {
+    pub(crate) fn as_view(&self) -> ArgView<'_> {
         ArgView {
             name: &self.name,
             short: self.short,
-            ty_help: Some(self.ty_help),
+            value_name: Some(self.value_name()),
             doc: &self.doc,
+            order: self.order,
+        }
+    }
+
+    /// Whether the option must be given (on the command line or by a layered source).
+    pub(crate) fn is_required(&self) -> bool {
+        match self.property {
+            ArgProperty::Required => self.default.is_none(),
+            ArgProperty::MultiValue { required } => required,
+            ArgProperty::Optional | ArgProperty::Positional { .. } => false,
+        }
+    }
+
+    /// The default value without quotes, e.g. `nobody` for `#[default("nobody")]`.
+    pub(crate) fn default_value(&self) -> Option<String> {
+        self.default
+            .as_ref()
+            .map(|lit| lit.as_string().unwrap_or_else(|_| lit.to_string()))
+    }
+
+    /// The value placeholder shown in the help text, e.g. `PATH`.
+    pub(crate) fn value_name(&self) -> &str {
+        self.value_name
+            .as_deref()
+            .unwrap_or_else(|| self.ty_help.as_str().trim_start())
+    }
+}
+
+impl ArgView<'_> {
+    /// A view with only the first line of the description, for the short help.
+    pub(crate) fn summary(self) -> Self {
+        Self {
+            doc: &self.doc[..self.doc.len().min(1)],
+            ..self
         }
     }
 }
@@ -479,16 +1856,148 @@ impl ArgType {
         match self {
             Self::Float => " FLOAT",
             Self::Integer => " INTEGER",
-            Self::OsString | Self::String => " STRING",
+            Self::CString | Self::OsString | Self::String => " STRING",
             Self::Path => " PATH",
+            Self::Url => " URL",
+            Self::Uuid => " UUID",
+            Self::Version => " VERSION",
+        }
+    }
+
+    /// The `onlyargs::ValueType` variant for this type.
+    pub(crate) fn value_type(&self) -> &str {
+        match self {
+            Self::Float => "Float",
+            Self::Integer => "Integer",
+            Self::OsString => "OsString",
+            Self::Path => "Path",
+            Self::CString | Self::String | Self::Url | Self::Uuid | Self::Version => "String",
+        }
+    }
+
+    pub(crate) fn parser(&self) -> &str {
+        match self {
+            Self::CString => "parse_cstring",
+            Self::Float => "parse_float",
+            Self::Integer => "parse_int",
+            Self::OsString => "parse_osstr",
+            Self::Path => "parse_path",
+            Self::String => "parse_str",
+            Self::Url => "parse_url",
+            Self::Uuid => "parse_uuid",
+            Self::Version => "parse_version",
         }
     }
 
-    pub(crate) fn converter(&self) -> &str {
+    /// The expression for a `#[default(...)]` literal of this type.
+    ///
+    /// Literals that are converted at runtime are checked by `check_default()` first.
+    pub(crate) fn default_value(self, default: &Literal) -> String {
         match self {
-            Self::Float | Self::Integer => "",
-            Self::OsString | Self::Path | Self::String => ".into()",
+            Self::CString => {
+                format!(
+                    r#"::std::ffi::CString::new({default}).expect("invalid #[default(...)] value")"#
+                )
+            }
+            Self::Float | Self::Integer => default.to_string(),
+            Self::OsString | Self::Path | Self::String => format!("{default}.into()"),
+            Self::Url | Self::Uuid | Self::Version => {
+                format!(r#"{default}.parse().expect("invalid #[default(...)] value")"#)
+            }
+        }
+    }
+}
+
+/// Get the exit code from an attribute like `#[help_exit_code(n)]`.
+fn get_exit_code(attr: &mut Attribute) -> Result<i32, TokenStream> {
+    let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+    let lit = stream.try_lit()?;
+
+    lit.to_string()
+        .parse()
+        .map_err(|_| spanned_error("Expected an integer exit code", lit.span()))
+}
+
+/// Get the value of a package metadata attribute like `#[homepage]`.
+///
+/// The value is given with `#[homepage("...")]`, or read from the `package.homepage` key in
+/// `Cargo.toml` when no value is given.
+fn get_package_attr(attr: &mut Attribute, key: &str) -> Result<String, TokenStream> {
+    if attr.tree.peek().is_some() {
+        let mut stream = attr.tree.expect_group(Delimiter::Parenthesis)?;
+
+        return stream.try_lit()?.as_string();
+    }
+
+    let var = format!("CARGO_PKG_{}", key.to_ascii_uppercase());
+    match env::var(var) {
+        // Cargo separates authors with colons.
+        Ok(value) if !value.is_empty() && key == "authors" => Ok(value.replace(':', ", ")),
+        Ok(value) if !value.is_empty() => Ok(value),
+        _ => Err(spanned_error(
+            format!("#[{key}] requires a value or `package.{key}` in Cargo.toml"),
+            attr.name.span(),
+        )),
+    }
+}
+
+/// Get the lines of a `#[header]` or `#[footer]`.
+///
+/// Lines are given with `#[footer = "..."]`, or read from a file relative to the crate root with
+/// `#[footer(include = "path")]`. The paths of included files are added to `includes`.
+fn get_help_text(
+    attrs: &[Attribute],
+    name: &str,
+    includes: &mut Vec<String>,
+) -> Result<Vec<String>, TokenStream> {
+    let mut lines = vec![];
+
+    for attr in attrs.iter().filter(|attr| attr.name.to_string() == name) {
+        let mut tree = attr.tree.clone();
+        if tree.expect_punct('=').is_ok() {
+            lines.push(tree.try_lit()?.as_string()?.trim_end().to_string());
+            continue;
         }
+
+        let mut stream = tree.expect_group(Delimiter::Parenthesis)?;
+        stream.expect_ident("include")?;
+        stream.expect_punct('=')?;
+        let lit = stream.try_lit()?;
+        let path = env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(lit.as_string()?);
+        let text = fs::read_to_string(&path).map_err(|err| {
+            spanned_error(
+                format!("Unable to read `{}`: {err}", path.display()),
+                lit.span(),
+            )
+        })?;
+
+        lines.extend(text.lines().map(|line| line.trim_end().to_string()));
+        includes.push(path.to_string_lossy().into_owned());
+    }
+
+    Ok(lines)
+}
+
+/// Get the doc comment lines for the help text.
+///
+/// Lines are trimmed unless the item has the `#[verbatim_doc_comment]` attribute. Verbatim doc
+/// comments only lose the space following `///` or `/**`.
+fn get_doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    let doc = get_doc_comment(attrs);
+
+    if attrs
+        .iter()
+        .any(|attr| attr.name.to_string() == "verbatim_doc_comment")
+    {
+        doc.iter()
+            .flat_map(|doc| doc.strip_prefix(' ').unwrap_or(doc).split('\n'))
+            .map(str::to_string)
+            .collect()
+    } else {
+        doc.into_iter().map(trim_with_indent).collect()
     }
 }
 
@@ -1,6 +1,9 @@
-use onlyargs::{CliError, OnlyArgs as _};
+use onlyargs::types::{ColorChoice, Verbosity};
+use onlyargs::{CliError, Occurrences, OnlyArgs as _, ParseResult, Warning};
 use onlyargs_derive::OnlyArgs;
-use std::{ffi::OsString, path::PathBuf};
+use std::ffi::{CString, OsString};
+use std::path::{Path, PathBuf};
+use std::{rc::Rc, sync::Arc};
 
 #[test]
 fn test_multivalue_paths() -> Result<(), CliError> {
@@ -67,10 +70,12 @@ fn test_required_multivalue() -> Result<(), CliError> {
     }
 
     // Empty `--names` is not allowed.
-    assert!(matches!(
-        Args::parse(vec![]),
-        Err(CliError::MissingRequired(name)) if name == "--names",
-    ));
+    assert_eq!(
+        Args::parse(vec![]).unwrap_err(),
+        CliError::MissingRequired {
+            argument: "--names".into(),
+        },
+    );
 
     // At least one `--names` is required.
     let args = Args::parse(
@@ -95,10 +100,12 @@ fn test_required_positional() -> Result<(), CliError> {
     }
 
     // Empty positional is not allowed.
-    assert!(matches!(
-        dbg!(Args::parse(vec![])),
-        Err(CliError::MissingRequired(name)) if name == "rest",
-    ));
+    assert_eq!(
+        Args::parse(vec![]).unwrap_err(),
+        CliError::MissingRequired {
+            argument: "rest".into(),
+        },
+    );
 
     // At least one positional is required.
     let args = Args::parse(["Bob"].into_iter().map(OsString::from).collect())?;
@@ -159,3 +166,2246 @@ fn test_positional_escape() -> Result<(), CliError> {
 
     Ok(())
 }
+
+#[test]
+fn test_env_fallback() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        #[env("ONLYARGS_TEST_ENV_NAME")]
+        name: String,
+
+        #[env("ONLYARGS_TEST_ENV_WIDTH")]
+        #[default(42)]
+        width: i32,
+
+        #[env("ONLYARGS_TEST_ENV_OUTPUT")]
+        output: Option<PathBuf>,
+    }
+
+    // Without the environment, required arguments are still required.
+    assert!(matches!(
+        Args::parse(vec![]),
+        Err(CliError::MissingRequired { argument }) if argument == "--name",
+    ));
+
+    std::env::set_var("ONLYARGS_TEST_ENV_NAME", "Alice");
+
+    // The environment satisfies required arguments, and defaults are used as a last resort.
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.name, "Alice");
+    assert_eq!(args.width, 42);
+    assert_eq!(args.output, None);
+
+    std::env::set_var("ONLYARGS_TEST_ENV_WIDTH", "100");
+    std::env::set_var("ONLYARGS_TEST_ENV_OUTPUT", "/tmp/output");
+
+    // The environment takes precedence over defaults.
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.width, 100);
+    assert_eq!(args.output, Some(PathBuf::from("/tmp/output")));
+
+    // The command line takes precedence over the environment.
+    let args = Args::parse(
+        ["--name", "Bob", "--width", "3"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.name, "Bob");
+    assert_eq!(args.width, 3);
+
+    // Parsing errors report the environment variable name.
+    std::env::set_var("ONLYARGS_TEST_ENV_WIDTH", "wide");
+
+    assert!(matches!(
+        Args::parse(vec![]),
+        Err(CliError::ParseIntError { argument, .. }) if argument == "ONLYARGS_TEST_ENV_WIDTH",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_env_prefix() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[env_prefix("ONLYARGS_TEST_PREFIX_")]
+    struct Args {
+        log_level: String,
+
+        #[env("ONLYARGS_TEST_PREFIX_OVERRIDE")]
+        width: Option<i32>,
+
+        verbose: bool,
+    }
+
+    std::env::set_var("ONLYARGS_TEST_PREFIX_LOG_LEVEL", "debug");
+    std::env::set_var("ONLYARGS_TEST_PREFIX_WIDTH", "1");
+    std::env::set_var("ONLYARGS_TEST_PREFIX_OVERRIDE", "2");
+    std::env::set_var("ONLYARGS_TEST_PREFIX_VERBOSE", "true");
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.log_level, "debug");
+    assert_eq!(args.width, Some(2));
+    assert!(!args.verbose);
+
+    Ok(())
+}
+
+#[test]
+fn test_dotenv() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[dotenv("tests/fixtures/test.env")]
+    struct Args {
+        #[env("ONLYARGS_TEST_DOTENV_NAME")]
+        name: String,
+
+        #[env("ONLYARGS_TEST_DOTENV_WIDTH")]
+        width: i32,
+    }
+
+    // The `.env` file fills in missing environment variables.
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.name, "Alice");
+    assert_eq!(args.width, 100);
+
+    // The process environment takes precedence over the `.env` file.
+    std::env::set_var("ONLYARGS_TEST_DOTENV_WIDTH", "3");
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.width, 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_config_file() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[config_file("tests/fixtures/config.toml")]
+    struct Args {
+        username: String,
+
+        #[default(42)]
+        width: i32,
+
+        #[default(7)]
+        depth: i32,
+
+        names: Vec<String>,
+    }
+
+    // The config file fills in options that were not provided.
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.username, "Alice");
+    assert_eq!(args.width, 100);
+    assert_eq!(args.depth, 7);
+    assert_eq!(args.names, ["Bob", "Carol"]);
+
+    // The command line takes precedence over the config file.
+    let args = Args::parse(
+        ["--username", "David", "--names", "Eve"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.username, "David");
+    assert_eq!(args.names, ["Eve"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_json_config_file() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[config_file("tests/fixtures/config.json")]
+    struct Args {
+        username: String,
+        width: Option<i32>,
+        names: Vec<String>,
+    }
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.username, "Alice");
+    assert_eq!(args.width, Some(100));
+    assert_eq!(args.names, ["Bob", "Carol"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_config_file() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[config_file("tests/fixtures/missing.toml")]
+    struct Args {
+        #[default(42)]
+        width: i32,
+    }
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.width, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_layers() -> Result<(), CliError> {
+    use onlyargs::source::{Key, Source};
+
+    struct Remote;
+
+    impl Source for Remote {
+        fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+            match key.name {
+                "username" => vec!["Alice".into()],
+                "names" => vec!["Bob".into(), "Carol".into()],
+                "width" => vec!["wide".into()],
+                _ => vec![],
+            }
+        }
+
+        fn describe(&self, key: &Key<'_>) -> String {
+            format!("remote:{}", key.name)
+        }
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        username: String,
+        names: Vec<String>,
+
+        #[default(42)]
+        width: i32,
+    }
+
+    let layers = Args::layers()?.push(Remote);
+
+    // Errors name the source that provided the value.
+    assert!(matches!(
+        Args::parse_with_layers(vec![], &layers),
+        Err(CliError::ParseIntError { argument, .. }) if argument == "remote:width",
+    ));
+
+    let args = Args::parse_with_layers(
+        ["--width", "3"].into_iter().map(OsString::from).collect(),
+        &layers,
+    )?;
+
+    assert_eq!(args.username, "Alice");
+    assert_eq!(args.names, ["Bob", "Carol"]);
+    assert_eq!(args.width, 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_origins() -> Result<(), CliError> {
+    use onlyargs::source::{Key, Origin, Origins, Source};
+
+    struct Remote;
+
+    impl Source for Remote {
+        fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+            match key.name {
+                "username" => vec!["Alice".into()],
+                _ => vec![],
+            }
+        }
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbose: bool,
+        username: String,
+
+        #[env("ONLYARGS_TEST_ORIGINS_WIDTH")]
+        #[default(42)]
+        width: i32,
+
+        #[long]
+        height: Option<i32>,
+
+        #[positional]
+        rest: Vec<String>,
+
+        #[origins]
+        origins: Origins,
+    }
+
+    std::env::set_var("ONLYARGS_TEST_ORIGINS_WIDTH", "80");
+
+    let layers = Args::layers()?.push(Remote);
+    let args = Args::parse_with_layers(
+        ["-v", "foo"].into_iter().map(OsString::from).collect(),
+        &layers,
+    )?;
+
+    assert!(args.verbose);
+    assert_eq!(args.username, "Alice");
+    assert_eq!(args.width, 80);
+    assert_eq!(args.height, None);
+    assert_eq!(args.rest, ["foo"]);
+    assert_eq!(args.origins.source_of("verbose"), Some(Origin::Cli));
+    assert_eq!(args.origins.source_of("username"), Some(Origin::Config));
+    assert_eq!(args.origins.source_of("width"), Some(Origin::Env));
+    assert_eq!(args.origins.source_of("height"), Some(Origin::Default));
+    assert_eq!(args.origins.source_of("rest"), Some(Origin::Cli));
+    assert_eq!(args.origins.source_of("origins"), None);
+
+    let args = Args::parse_with_layers(
+        ["--width", "3"].into_iter().map(OsString::from).collect(),
+        &layers,
+    )?;
+
+    assert_eq!(args.origins.source_of("verbose"), Some(Origin::Default));
+    assert_eq!(args.origins.source_of("width"), Some(Origin::Cli));
+    assert_eq!(args.origins.source_of("rest"), Some(Origin::Default));
+
+    Ok(())
+}
+
+#[test]
+fn test_config_path() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[config_file("tests/fixtures/config.toml")]
+    struct Args {
+        #[config_path]
+        config: Option<PathBuf>,
+
+        username: String,
+        width: i32,
+    }
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.config, None);
+    assert_eq!(args.username, "Alice");
+
+    // The named config file takes precedence over `#[config_file(...)]`.
+    let args = Args::parse(
+        ["--config", "tests/fixtures/override.toml"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(
+        args.config,
+        Some(PathBuf::from("tests/fixtures/override.toml"))
+    );
+    assert_eq!(args.username, "Dave");
+    assert_eq!(args.width, 100);
+
+    // The command line takes precedence over the named config file.
+    let args = Args::parse(
+        ["--username", "Eve", "-c", "tests/fixtures/override.toml"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.username, "Eve");
+
+    // Values of other options are not mistaken for the config path.
+    let args = Args::parse(
+        ["--username", "--config"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.config, None);
+    assert_eq!(args.username, "--config");
+    assert_eq!(args.width, 100);
+
+    // A named config file must exist.
+    assert!(matches!(
+        Args::parse(
+            ["--config", "tests/fixtures/missing.toml"]
+                .into_iter()
+                .map(OsString::from)
+                .collect(),
+        ),
+        Err(CliError::ConfigIoError { path, .. }) if path == Path::new("tests/fixtures/missing.toml"),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_config_path_preprocess() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[preprocess(rename_args)]
+    struct Args {
+        #[config_path]
+        config: Option<PathBuf>,
+
+        username: Option<String>,
+    }
+
+    // `--settings` was renamed to `--config`.
+    fn rename_args(args: Vec<OsString>) -> Result<Vec<OsString>, CliError> {
+        Ok(args
+            .into_iter()
+            .map(|arg| {
+                if arg == "--settings" {
+                    "--config".into()
+                } else {
+                    arg
+                }
+            })
+            .collect())
+    }
+
+    let args = Args::parse(
+        ["--settings", "tests/fixtures/override.toml"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(
+        args.config,
+        Some(PathBuf::from("tests/fixtures/override.toml"))
+    );
+    assert_eq!(args.username.as_deref(), Some("Dave"));
+
+    Ok(())
+}
+
+#[test]
+fn test_short_help() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// Set the width.
+        /// Wider is better.
+        #[default(42)]
+        width: i32,
+    }
+
+    assert!(Args::HELP.contains("Set the width.\n"));
+    assert!(Args::HELP.contains("Wider is better. [default: 42]\n"));
+    assert!(Args::SHORT_HELP.contains("Set the width.\n"));
+    assert!(!Args::SHORT_HELP.contains("Wider is better."));
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.width, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_hide() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// Enable verbose output.
+        verbose: bool,
+
+        /// Dump internal state.
+        #[hide]
+        debug: bool,
+
+        /// Experimental level.
+        #[hide]
+        level: Option<i32>,
+    }
+
+    assert!(Args::HELP.contains("--verbose"));
+    assert!(!Args::HELP.contains("--debug"));
+    assert!(!Args::HELP.contains("--level"));
+    assert!(!Args::SHORT_HELP.contains("--debug"));
+
+    // Hidden arguments can still be parsed.
+    let args = Args::parse(
+        ["--debug", "--level", "3"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert!(!args.verbose);
+    assert!(args.debug);
+    assert_eq!(args.level, Some(3));
+
+    Ok(())
+}
+
+#[test]
+fn test_help_heading() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// Your username.
+        username: Option<String>,
+
+        /// Server port.
+        #[help_heading("Network options")]
+        port: Option<u16>,
+
+        /// Use IPv6.
+        #[help_heading("Network options")]
+        #[long]
+        ipv6: bool,
+    }
+
+    assert!(Args::HELP.contains(
+        "\nOptions:\n  -u --username STRING  Your username.\n\n\
+        Network options:\n  --ipv6             Use IPv6.\n  -p --port INTEGER  Server port.\n"
+    ));
+
+    let args = Args::parse(
+        ["--port", "8080", "--ipv6"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.username, None);
+    assert_eq!(args.port, Some(8080));
+    assert!(args.ipv6);
+
+    Ok(())
+}
+
+#[test]
+fn test_help_order() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[sort_help]
+    struct Args {
+        /// Your username.
+        username: Option<String>,
+
+        /// Server address.
+        address: Option<String>,
+
+        /// Server port.
+        #[display_order(0)]
+        port: Option<u16>,
+    }
+
+    assert!(Args::HELP.contains(
+        "\nOptions:\n  -p --port INTEGER     Server port.\n  \
+        -a --address STRING   Server address.\n  \
+        -u --username STRING  Your username.\n"
+    ));
+}
+
+#[test]
+fn test_help_template() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[footer = "See the manual for details."]
+    #[help_template("Usage: {usage}\n{footer}\nArguments:\n{options}{positional}")]
+    struct Args {
+        /// Your username.
+        username: Option<String>,
+
+        /// Files to process.
+        #[positional]
+        files: Vec<String>,
+    }
+
+    assert!(Args::HELP.starts_with(
+        "Usage: {bin_name} [flags] [options] [files...]\n\
+        \nSee the manual for details.\n\n\
+        Arguments:\n  -u --username STRING  Your username.\n\
+        \nfiles:\n  Files to process.\n"
+    ));
+}
+
+#[test]
+fn test_help_width() {
+    #[derive(Debug, OnlyArgs)]
+    #[help_width(60)]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert_eq!(Args::help_width(), 60);
+
+    let args = Args::parse(vec![OsString::from("-v")]).unwrap();
+
+    assert!(args.verbose);
+}
+
+#[test]
+fn test_help_attribute() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// The number of worker threads to spawn. Defaults to the number of logical CPUs.
+        #[help("Number of threads.")]
+        threads: Option<usize>,
+    }
+
+    assert!(Args::HELP.contains("\n  -t --threads INTEGER  Number of threads.\n"));
+    assert!(!Args::HELP.contains("worker threads"));
+}
+
+#[test]
+fn test_verbatim_doc_comment() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /** Output format:
+        json  Machine readable.
+        text  Human readable.  */
+        #[verbatim_doc_comment]
+        format: Option<String>,
+    }
+
+    // Continuation lines are indented to the description column, followed by the comment's own
+    // indentation.
+    let indent = " ".repeat(22 + 8);
+    assert!(Args::HELP.contains(&format!(
+        "\n  -f --format STRING  Output format:\n\
+        {indent}json  Machine readable.\n\
+        {indent}text  Human readable.  \n"
+    )));
+}
+
+#[test]
+fn test_footer_include() {
+    #[derive(Debug, OnlyArgs)]
+    #[footer = "Links:"]
+    #[footer(include = "tests/fixtures/footer.txt")]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert!(Args::HELP.ends_with(
+        "\nLinks:\n  Homepage: https://example.com\n  Issues: https://example.com/issues\n"
+    ));
+
+    let args = Args::parse(vec![]).unwrap();
+
+    assert!(!args.verbose);
+}
+
+#[test]
+fn test_examples() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[example("{bin_name} --input a.txt")]
+    #[example("{bin_name} --input a.txt --verbose")]
+    #[footer = "Footer"]
+    struct Args {
+        input: Option<PathBuf>,
+        verbose: bool,
+    }
+
+    assert!(Args::HELP.ends_with(
+        "\nExamples:\n  {bin_name} --input a.txt\n  {bin_name} --input a.txt --verbose\n\
+        \nFooter\n"
+    ));
+}
+
+#[test]
+fn test_plain_help() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[plain_help]
+    struct Args {
+        /// Your username.
+        #[default("nobody")]
+        username: String,
+    }
+
+    assert!(Args::plain_help());
+    assert!(Args::render_help(Args::HELP)
+        .contains("\n-u --username STRING: Your username. [default: \"nobody\"]\n"));
+}
+
+#[test]
+fn test_pager() {
+    #[derive(Debug, OnlyArgs)]
+    #[pager]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert!(Args::pager());
+
+    let args = Args::parse(vec![]).unwrap();
+
+    assert!(!args.verbose);
+}
+
+#[test]
+fn test_value_name() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// Output file.
+        #[value_name("FILE")]
+        output: PathBuf,
+
+        /// Number of jobs.
+        #[value_name("N")]
+        jobs: Option<usize>,
+    }
+
+    assert!(Args::HELP.contains("\nUsage:\n  {bin_name} --output <FILE> [flags] [options]\n"));
+    assert!(Args::HELP.contains(
+        "\nOptions:\n  -o --output FILE  Output file. [required]\n  -j --jobs N       Number of jobs.\n"
+    ));
+}
+
+#[test]
+fn test_group_required() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[group_required]
+    struct Args {
+        /// Your username.
+        username: String,
+
+        /// Server port.
+        port: Option<u16>,
+
+        /// Input files.
+        #[required]
+        input: Vec<PathBuf>,
+    }
+
+    assert!(Args::HELP.contains(
+        "\n\nRequired:\n  -u --username STRING  Your username.\n  \
+        -i --input PATH       Input files.\n\nFlags:\n"
+    ));
+    assert!(Args::HELP.contains("\nOptions:\n  -p --port INTEGER  Server port.\n"));
+    assert!(!Args::HELP.contains("[required]"));
+}
+
+#[test]
+fn test_name_and_version() {
+    #[derive(Debug, OnlyArgs)]
+    #[name("mytool")]
+    #[version("2.1.0-beta")]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert!(Args::HELP.starts_with("mytool v2.1.0-beta\n"));
+    assert_eq!(Args::VERSION, "mytool v2.1.0-beta\n");
+
+    let args = Args::parse(vec![]).unwrap();
+
+    assert!(!args.verbose);
+}
+
+#[test]
+fn test_authors_and_links() {
+    #[derive(Debug, OnlyArgs)]
+    #[authors]
+    #[homepage("https://example.com")]
+    #[repository]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert!(Args::HELP.contains(
+        "\nAuthors: Jay Oster <jay@kodewerx.org>\n\
+        Homepage: https://example.com\n\
+        Repository: https://github.com/parasyte/onlyargs\n\n"
+    ));
+
+    let args = Args::parse(vec![]).unwrap();
+
+    assert!(!args.verbose);
+}
+
+#[test]
+fn test_package_placeholders() {
+    #[derive(Debug, OnlyArgs)]
+    #[help_template("{authors}|{homepage}|{repository}")]
+    struct Args {
+        verbose: bool,
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    #[authors("Alice: Bob")]
+    struct Explicit {
+        verbose: bool,
+    }
+
+    assert_eq!(
+        Args::HELP,
+        "Jay Oster <jay@kodewerx.org>||https://github.com/parasyte/onlyargs",
+    );
+    assert!(Explicit::HELP.contains("\nAuthors: Alice: Bob\n"));
+}
+
+#[test]
+fn test_help_stderr() {
+    #[derive(Debug, OnlyArgs)]
+    #[help_stderr]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert_eq!(Args::help_output(), onlyargs::help::Output::Stderr);
+
+    let args = Args::parse(vec![]).unwrap();
+
+    assert!(!args.verbose);
+}
+
+#[test]
+fn test_exit_codes() {
+    #[derive(Debug, OnlyArgs)]
+    #[help_exit_code(1)]
+    #[error_exit_code(64)]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert_eq!(Args::help_exit_code(), 1);
+    assert_eq!(Args::error_exit_code(), onlyargs::EX_USAGE);
+
+    let args = Args::parse(vec![]).unwrap();
+
+    assert!(!args.verbose);
+}
+
+#[test]
+fn test_capture_help_and_version() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[capture_help]
+    #[capture_version]
+    struct Args {
+        /// Show this help message.
+        help: bool,
+
+        /// Show the application version.
+        #[short('V')]
+        version: bool,
+    }
+
+    assert!(Args::HELP.contains(
+        "\nFlags:\n  -h --help     Show this help message.\n  \
+        -V --version  Show the application version.\n"
+    ));
+
+    let args = Args::parse(vec!["-h".into()])?;
+
+    assert!(args.help);
+    assert!(!args.version);
+
+    let args = Args::parse(vec!["--version".into()])?;
+
+    assert!(!args.help);
+    assert!(args.version);
+
+    Ok(())
+}
+
+#[test]
+fn test_capture_help_with_required() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[capture_help]
+    #[validate(Args::check)]
+    struct Args {
+        /// Show this help message.
+        help: bool,
+
+        /// Output file.
+        output: PathBuf,
+
+        /// Input files.
+        #[required]
+        inputs: Vec<PathBuf>,
+    }
+
+    impl Args {
+        fn check(&self) -> Result<(), CliError> {
+            Err(CliError::custom("--output", "always invalid"))
+        }
+    }
+
+    let args = Args::parse(vec!["--help".into()])?;
+
+    assert!(args.help);
+    assert_eq!(args.output, PathBuf::new());
+    assert!(args.inputs.is_empty());
+
+    let err = Args::parse(vec![]).unwrap_err();
+
+    assert!(matches!(err.inner(), CliError::MissingRequired { .. }));
+
+    Ok(())
+}
+
+#[test]
+fn test_try_parse() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbose: bool,
+    }
+
+    assert!(matches!(
+        Args::try_parse(vec!["--help".into()])?,
+        ParseResult::Help,
+    ));
+    assert!(matches!(
+        Args::try_parse(vec!["-h".into()])?,
+        ParseResult::ShortHelp,
+    ));
+    assert!(matches!(
+        Args::try_parse(vec!["--version".into()])?,
+        ParseResult::Version,
+    ));
+    assert!(matches!(
+        Args::try_parse(vec!["-V".into()])?,
+        ParseResult::ShortVersion,
+    ));
+    assert!(matches!(
+        Args::try_parse(vec!["-v".into()])?,
+        ParseResult::Args(Args { verbose: true }),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_help_string() {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbose: bool,
+    }
+
+    let bin_name = onlyargs::bin_name();
+    let help = Args::help_string();
+
+    assert!(!help.contains("{bin_name}"));
+    assert!(help.contains(&format!("\nUsage:\n  {bin_name} [flags]\n")));
+
+    let mut output = vec![];
+    Args::write_help(&mut output).unwrap();
+
+    assert_eq!(output, help.as_bytes());
+
+    let mut output = vec![];
+    Args::write_version(&mut output).unwrap();
+
+    assert_eq!(output, Args::VERSION.as_bytes());
+
+    let args = Args::parse(vec![]).unwrap();
+
+    assert!(!args.verbose);
+}
+
+#[test]
+fn test_did_you_mean() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbose: bool,
+        #[alias("out")]
+        output: Option<PathBuf>,
+        #[hide]
+        secret: Option<String>,
+    }
+
+    assert!(matches!(
+        Args::parse(vec!["--outpt".into()]).unwrap_err().inner(),
+        CliError::UnknownSuggestion { argument, suggestion }
+            if argument == "--outpt" && suggestion == "--output",
+    ));
+    assert!(matches!(
+        Args::parse(vec!["--ot".into()]).unwrap_err().inner(),
+        CliError::UnknownSuggestion { suggestion, .. } if suggestion == "--out",
+    ));
+    assert!(matches!(
+        Args::parse(vec!["--hepl".into()]).unwrap_err().inner(),
+        CliError::UnknownSuggestion { suggestion, .. } if suggestion == "--help",
+    ));
+    assert!(matches!(
+        Args::parse(vec!["--secrt".into()]).unwrap_err().inner(),
+        CliError::Unknown { argument } if argument == "--secrt",
+    ));
+    assert!(matches!(
+        Args::parse(vec!["--frobnicate".into()]).unwrap_err().inner(),
+        CliError::Unknown { argument } if argument == "--frobnicate",
+    ));
+}
+
+#[test]
+fn test_collect_errors() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[collect_errors]
+    struct Args {
+        width: Option<i32>,
+        #[possible_values("fast", "slow")]
+        speed: Option<String>,
+        #[required]
+        input: Vec<PathBuf>,
+    }
+
+    let err = Args::parse(vec![
+        "--width".into(),
+        "wide".into(),
+        "--outpt".into(),
+        "--speed".into(),
+        "medium".into(),
+    ])
+    .unwrap_err();
+
+    let errors = match err {
+        CliError::Multiple { errors } => errors,
+        err => panic!("Expected multiple errors, got {err:?}"),
+    };
+    assert_eq!(errors.len(), 4);
+    assert!(
+        matches!(errors[0].inner(), CliError::ParseIntError { argument, .. } if argument == "--width")
+    );
+    assert!(matches!(errors[1].inner(), CliError::Unknown { argument } if argument == "--outpt"));
+    assert!(
+        matches!(&errors[2], CliError::InvalidChoice { argument, .. } if argument == "--speed")
+    );
+    assert!(matches!(&errors[3], CliError::MissingRequired { argument } if argument == "--input"));
+
+    let args = Args::parse(vec!["--input".into(), "a.txt".into()]).unwrap();
+
+    assert_eq!(args.input, [PathBuf::from("a.txt")]);
+}
+
+#[test]
+fn test_error_positions() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbose: bool,
+        width: Option<i32>,
+        #[positional]
+        numbers: Vec<i32>,
+    }
+
+    let err = Args::parse(vec!["-v".into(), "--width".into(), "wide".into()]).unwrap_err();
+
+    assert_eq!(err.position(), Some(2));
+    assert_eq!(err.token(), Some("wide".as_ref()));
+    assert!(
+        matches!(err.inner(), CliError::ParseIntError { argument, value, .. }
+        if argument == "--width" && value == "wide")
+    );
+
+    let err = Args::parse(vec!["-v".into(), "--width".into()]).unwrap_err();
+
+    assert_eq!(err.position(), Some(1));
+    assert_eq!(err.token(), Some("--width".as_ref()));
+    assert_eq!(
+        err,
+        CliError::MissingValue {
+            argument: "--width".into(),
+        },
+    );
+
+    let err = Args::parse(vec!["1".into(), "--".into(), "2".into(), "x".into()]).unwrap_err();
+
+    assert_eq!(err.position(), Some(3));
+    assert_eq!(err.token(), Some("x".as_ref()));
+    assert!(matches!(err.inner(), CliError::ParseIntError { value, .. } if value == "x"));
+}
+
+#[test]
+fn test_format_error() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[usage("{bin_name} --name <STRING>")]
+    struct Args {
+        name: String,
+    }
+
+    let bin_name = onlyargs::bin_name();
+    let err = Args::parse(vec![]).unwrap_err();
+
+    assert_eq!(
+        Args::format_error(&err),
+        format!(
+            "Error: Missing required argument `-n/--name <STRING>`\n\nUsage:\n  {bin_name} --name \
+            <STRING>\n\nFor more information, try `--help`.\n",
+        ),
+    );
+}
+
+#[test]
+fn test_validate() {
+    #[derive(Debug, OnlyArgs)]
+    #[validate(validate_args)]
+    struct Args {
+        width: Option<i32>,
+        verbose: bool,
+    }
+
+    fn validate_args(args: &Args) -> Result<(), CliError> {
+        if args.verbose && args.width.is_none() {
+            return Err(CliError::MissingRequired {
+                argument: "--width".into(),
+            });
+        }
+
+        Ok(())
+    }
+
+    let args = Args::parse(vec!["--verbose".into(), "--width".into(), "42".into()]).unwrap();
+    assert_eq!(args.width, Some(42));
+
+    assert_eq!(
+        Args::parse(vec!["--verbose".into()]).unwrap_err(),
+        CliError::MissingRequired {
+            argument: "--width".into(),
+        },
+    );
+}
+
+#[test]
+fn test_deprecated() {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// Old name.
+        #[deprecated = "use --new-name"]
+        old_name: Option<String>,
+        /// New name.
+        new_name: Option<String>,
+        /// Quiet.
+        #[deprecated]
+        quiet: bool,
+    }
+
+    let args = Args::parse(vec!["--old-name".into(), "foo".into(), "--quiet".into()]).unwrap();
+    #[allow(deprecated)]
+    {
+        assert_eq!(args.old_name.as_deref(), Some("foo"));
+        assert!(args.quiet);
+    }
+    assert_eq!(args.new_name, None);
+
+    let (_, warnings) = Args::parse_with_warnings(vec!["--old-name".into(), "foo".into()]).unwrap();
+    assert_eq!(
+        warnings,
+        [Warning::Deprecated {
+            argument: "--old-name".into(),
+            note: Some("use --new-name".into()),
+        }],
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "`--old-name` is deprecated: use --new-name",
+    );
+
+    let (_, warnings) = Args::parse_with_warnings(vec!["--new-name".into(), "foo".into()]).unwrap();
+    assert!(warnings.is_empty());
+
+    assert!(Args::HELP.contains("Old name. [deprecated: use --new-name]"));
+    assert!(Args::HELP.contains("Quiet. [deprecated]"));
+}
+
+#[test]
+fn test_parse_partial() {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbose: bool,
+        width: Option<i32>,
+    }
+
+    let (args, rest) = Args::parse_partial(vec![
+        "--verbose".into(),
+        "--width".into(),
+        "42".into(),
+        "build".into(),
+        "--release".into(),
+    ])
+    .unwrap();
+    assert!(args.verbose);
+    assert_eq!(args.width, Some(42));
+    assert_eq!(rest, ["build", "--release"]);
+
+    let (args, rest) =
+        Args::parse_partial(vec!["-v".into(), "--".into(), "--width".into()]).unwrap();
+    assert!(args.verbose);
+    assert_eq!(args.width, None);
+    assert_eq!(rest, ["--width"]);
+
+    assert!(Args::parse(vec!["-v".into(), "build".into()]).is_err());
+}
+
+#[test]
+fn test_occurrences() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        include: onlyargs::Occurrences<String>,
+        #[required]
+        level: Occurrences<u8>,
+        #[positional]
+        files: Occurrences<PathBuf>,
+    }
+
+    let args = Args::parse(
+        ["-i", "a", "-l", "1", "--include", "b", "-l", "3", "x.txt"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.include.count(), 2);
+    assert_eq!(args.include.values(), ["a", "b"]);
+    assert_eq!(args.level.last(), Some(&3));
+    assert_eq!(args.files.values(), [PathBuf::from("x.txt")]);
+
+    assert!(matches!(
+        Args::parse(vec![]),
+        Err(CliError::MissingRequired { argument }) if argument == "--level",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_zsh_completions() {
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    struct Args {
+        /// Enable verbose output.
+        verbose: bool,
+        /// Write output to a file.
+        output: Option<PathBuf>,
+        /// Exclude files [glob].
+        #[long]
+        exclude: Vec<String>,
+        #[hide]
+        debug: bool,
+        #[positional]
+        files: Vec<PathBuf>,
+    }
+
+    assert_eq!(
+        Args::SPEC.iter().map(|arg| arg.name).collect::<Vec<_>>(),
+        ["help", "version", "verbose", "output", "exclude", "files"],
+    );
+
+    let script =
+        onlyargs::completion::generate(onlyargs::completion::Shell::Zsh, "my-tool", Args::SPEC);
+
+    assert!(script.starts_with("#compdef my-tool\n\n_my_tool() {\n  _arguments -s -S \\\n"));
+    assert!(script.contains(" '(-h --help)'{-h,--help}'[Show this help message.]' \\\n"));
+    assert!(script.contains(" '(-v --verbose)'{-v,--verbose}'[Enable verbose output.]' \\\n"));
+    assert!(script
+        .contains(" '(-o --output)'{-o,--output}'[Write output to a file.]:PATH:_files' \\\n"));
+    assert!(script.contains(" '*--exclude[Exclude files \\[glob\\].]:STRING:' \\\n"));
+    assert!(script.contains(" '*:PATH:_files'\n}\n"));
+    assert!(script.ends_with("\n_my_tool \"$@\"\n"));
+}
+
+#[test]
+fn test_fish_completions() {
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    struct Args {
+        /// Write output to a file.
+        output: Option<PathBuf>,
+        /// Don't exclude files.
+        #[long]
+        exclude: Vec<String>,
+    }
+
+    let script =
+        onlyargs::completion::generate(onlyargs::completion::Shell::Fish, "my-tool", Args::SPEC);
+
+    assert_eq!(
+        script,
+        "complete -c 'my-tool' -f\n\
+        complete -c 'my-tool' -s 'h' -l 'help' -d 'Show this help message.'\n\
+        complete -c 'my-tool' -s 'V' -l 'version' -d 'Show the application version.'\n\
+        complete -c 'my-tool' -s 'o' -l 'output' -r -F -d 'Write output to a file.'\n\
+        complete -c 'my-tool' -l 'exclude' -x -d 'Don\\'t exclude files.'\n",
+    );
+}
+
+#[test]
+fn test_nushell_completions() {
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    struct Args {
+        /// Set the width.
+        width: Option<u32>,
+        #[long]
+        dry_run: bool,
+        /// Input files.
+        #[positional]
+        input_files: Vec<PathBuf>,
+    }
+
+    let script =
+        onlyargs::completion::generate(onlyargs::completion::Shell::Nushell, "my-tool", Args::SPEC);
+
+    assert_eq!(
+        script,
+        "export extern \"my-tool\" [\n  \
+        --help(-h)               # Show this help message.\n  \
+        --version(-V)            # Show the application version.\n  \
+        --dry-run\n  \
+        --width(-w): int         # Set the width.\n  \
+        ...input_files: path     # Input files.\n\
+        ]\n",
+    );
+}
+
+#[test]
+fn test_completion_hints() {
+    fn branches(word: &str) -> Vec<String> {
+        ["main", "next"]
+            .iter()
+            .filter(|name| name.starts_with(word))
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    struct Args {
+        #[complete(dir)]
+        output: Option<String>,
+        #[complete(hostname)]
+        server: Option<String>,
+        #[complete(fn = branches)]
+        branch: Option<String>,
+    }
+
+    let complete = |name| {
+        Args::SPEC
+            .iter()
+            .find(|arg| arg.name == name)
+            .unwrap()
+            .complete
+    };
+    assert_eq!(complete("output"), onlyargs::Complete::Dir);
+    assert_eq!(complete("server"), onlyargs::Complete::Hostname);
+    assert_eq!(complete("branch"), onlyargs::Complete::Callback);
+    assert_eq!(complete("help"), onlyargs::Complete::Default);
+
+    assert_eq!(Args::complete("branch", "n"), ["next"]);
+    assert!(Args::complete("output", "").is_empty());
+
+    let zsh =
+        onlyargs::completion::generate(onlyargs::completion::Shell::Zsh, "my-tool", Args::SPEC);
+    assert!(zsh.contains(":STRING:_files -/'"));
+    assert!(zsh.contains(":STRING:_hosts'"));
+    assert!(zsh.contains(
+        r#":STRING:{compadd -- ${(f)"$(ONLYARGS_COMPLETE=branch my-tool "$PREFIX")"}}'"#
+    ));
+
+    let fish =
+        onlyargs::completion::generate(onlyargs::completion::Shell::Fish, "my-tool", Args::SPEC);
+    assert!(fish.contains("-l 'output' -x -a '(__fish_complete_directories)'\n"));
+    assert!(fish.contains("-l 'server' -x -a '(__fish_print_hostnames)'\n"));
+    assert!(fish.contains(
+        r"-l 'branch' -x -a '(env ONLYARGS_COMPLETE=\'branch\' \'my-tool\' (commandline -ct))'"
+    ));
+
+    let nushell =
+        onlyargs::completion::generate(onlyargs::completion::Shell::Nushell, "my-tool", Args::SPEC);
+    assert!(nushell.starts_with(
+        "def \"nu-complete my-tool branch\" [] {\n  \
+        with-env { ONLYARGS_COMPLETE: \"branch\" } { ^\"my-tool\" \"\" } | lines\n}\n\n"
+    ));
+    assert!(nushell.contains("--output(-o): directory"));
+    assert!(nushell.contains("--branch(-b): string@\"nu-complete my-tool branch\""));
+}
+
+#[test]
+fn test_generate() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    #[generate]
+    struct Args {
+        /// Set the width.
+        width: Option<u32>,
+        /// Input files.
+        #[positional]
+        input_files: Vec<PathBuf>,
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    struct NoGenerate {
+        verbose: bool,
+    }
+
+    let artifact = |args: &[&str]| {
+        let args = args.iter().map(Into::into).collect::<Vec<_>>();
+        onlyargs::generate::artifact::<Args>(&args)
+    };
+
+    let man = artifact(&["--generate", "man"])?.unwrap();
+    assert!(man.starts_with(".TH "));
+    assert!(man.contains(".SH OPTIONS\n"));
+    assert!(
+        man.contains(".TP\n\\fB\\-w\\fR, \\fB\\-\\-width\\fR \\fIINTEGER\\fR\nSet the width.\n")
+    );
+    assert!(man.contains(".SH ARGUMENTS\n.TP\n\\fIPATH\\fR...\nInput files.\n"));
+
+    let markdown = artifact(&["--generate", "markdown"])?.unwrap();
+    assert!(markdown.contains("\n## Options\n\n"));
+    assert!(markdown.contains("- `-w`, `--width <INTEGER>`: Set the width.\n"));
+    assert!(markdown.contains("\n## Arguments\n\n- `<PATH>...`: Input files.\n"));
+
+    let json = artifact(&["--generate", "json"])?.unwrap();
+    assert_eq!(json, Args::spec_json());
+    assert!(json.starts_with("{\n  \"schema\": 1,\n"));
+    assert!(json.contains(
+        "\"name\": \"input-files\",\n      \"short\": null,\n      \"kind\": \"positional\","
+    ));
+
+    let fish = artifact(&["--generate", "completions", "fish"])?.unwrap();
+    assert!(fish.starts_with("complete -c "));
+
+    assert!(matches!(
+        artifact(&["--generate", "pdf"]),
+        Err(CliError::InvalidChoice { .. }),
+    ));
+    assert!(matches!(
+        artifact(&["--generate", "completions"]),
+        Err(CliError::MissingValue { .. }),
+    ));
+    assert!(artifact(&["--width", "3"])?.is_none());
+
+    // `--generate` is not handled without the attribute, and it is never in the help text.
+    let args = ["--generate".into(), "man".into()];
+    assert!(onlyargs::generate::artifact::<NoGenerate>(&args)?.is_none());
+    assert!(!Args::HELP.contains("--generate"));
+
+    Ok(())
+}
+
+#[test]
+fn test_spec() {
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    struct Args {
+        /// Your username.
+        #[default("nobody")]
+        username: String,
+        #[default(true)]
+        color: bool,
+        #[long]
+        port: u16,
+        #[required]
+        names: Vec<String>,
+        #[hide]
+        secret: Option<String>,
+        #[positional]
+        files: Vec<PathBuf>,
+    }
+
+    let spec = |name| Args::SPEC.iter().find(|arg| arg.name == name).copied();
+
+    assert_eq!(
+        spec("username"),
+        Some(
+            onlyargs::ArgSpec::new(
+                "username",
+                onlyargs::ArgKind::Option(onlyargs::ValueType::String),
+                "Your username. [default: \"nobody\"]",
+            )
+            .with_short('u')
+            .with_value_name("STRING")
+            .with_default("nobody")
+        ),
+    );
+    let color = spec("color").unwrap();
+    assert_eq!(color.kind, onlyargs::ArgKind::Flag);
+    assert_eq!(color.default, Some("true"));
+    let port = spec("port").unwrap();
+    assert!(port.required);
+    assert_eq!(port.short, None);
+    assert_eq!(
+        port.kind,
+        onlyargs::ArgKind::Option(onlyargs::ValueType::Integer)
+    );
+    let names = spec("names").unwrap();
+    assert!(names.required && names.multiple);
+    let files = spec("files").unwrap();
+    assert_eq!(
+        files.kind,
+        onlyargs::ArgKind::Positional(onlyargs::ValueType::Path)
+    );
+    assert!(!files.required);
+    assert_eq!(spec("secret"), None);
+    assert_eq!(spec("verbose"), None);
+    assert!(spec("help").is_some() && spec("version").is_some());
+}
+
+#[test]
+fn test_clap_command() {
+    #[derive(Debug, OnlyArgs)]
+    #[allow(dead_code)]
+    struct Args {
+        /// Be noisy.
+        verbose: bool,
+        #[default(80)]
+        width: u32,
+        #[required]
+        names: Vec<String>,
+        #[complete(dir)]
+        output: Option<PathBuf>,
+        #[positional]
+        files: Vec<PathBuf>,
+    }
+
+    let command = Args::clap_command();
+    command.clone().debug_assert();
+
+    let matches = command
+        .try_get_matches_from(["my-tool", "-v", "--names", "a", "-n", "b", "x.txt", "y.txt"])
+        .unwrap();
+    assert!(matches.get_flag("verbose"));
+    assert_eq!(matches.get_one::<i128>("width"), Some(&80));
+    let names = matches.get_many::<String>("names").unwrap();
+    assert_eq!(names.collect::<Vec<_>>(), ["a", "b"]);
+    let files = matches.get_many::<PathBuf>("files").unwrap();
+    assert_eq!(files.collect::<Vec<_>>(), ["x.txt", "y.txt"]);
+
+    let output = Args::clap_command()
+        .get_arguments()
+        .find(|arg| arg.get_id() == "output")
+        .map(|arg| arg.get_value_hint());
+    assert_eq!(output, Some(clap::ValueHint::DirPath));
+
+    let err = Args::clap_command()
+        .try_get_matches_from(["my-tool"])
+        .unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+}
+
+#[test]
+fn test_to_args() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs, PartialEq)]
+    struct Args {
+        verbose: bool,
+        dry_run: bool,
+        #[default(80)]
+        width: i32,
+        ratio: Option<f64>,
+        name: Option<String>,
+        #[long]
+        kind: Option<OsString>,
+        include: Occurrences<String>,
+        #[positional]
+        files: Vec<PathBuf>,
+    }
+
+    let args = Args::parse(
+        [
+            "-v",
+            "--width",
+            "-3",
+            "--ratio",
+            "0.1",
+            "-i",
+            "a",
+            "-i",
+            "b",
+            "--kind",
+            "x y",
+            "--",
+            "-file.txt",
+        ]
+        .into_iter()
+        .map(OsString::from)
+        .collect(),
+    )?;
+
+    assert_eq!(
+        args.to_args(),
+        [
+            "--verbose",
+            "--width",
+            "-3",
+            "--ratio",
+            "0.1",
+            "--kind",
+            "x y",
+            "--include",
+            "a",
+            "--include",
+            "b",
+            "--",
+            "-file.txt",
+        ],
+    );
+    assert_eq!(Args::parse(args.to_args())?, args);
+
+    let args = Args::parse(vec![])?;
+    assert_eq!(args.to_args(), ["--width", "80"]);
+    assert_eq!(Args::parse(args.to_args())?, args);
+
+    Ok(())
+}
+
+#[test]
+fn test_impl_default() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs, PartialEq)]
+    #[impl_default]
+    struct Args {
+        verbose: bool,
+        #[default(true)]
+        color: bool,
+        #[default(80)]
+        width: u32,
+        #[default("nobody")]
+        username: String,
+        #[default("out.txt")]
+        output: PathBuf,
+        name: Option<String>,
+        include: Vec<String>,
+        #[positional]
+        files: Occurrences<PathBuf>,
+    }
+
+    let args = Args::default();
+
+    assert!(!args.verbose);
+    assert!(args.color);
+    assert_eq!(args.width, 80);
+    assert_eq!(args.username, "nobody");
+    assert_eq!(args.output, Path::new("out.txt"));
+    assert_eq!(args.name, None);
+    assert!(args.include.is_empty());
+    assert!(args.files.is_empty());
+    assert_eq!(Args::parse(vec![])?, args);
+
+    Ok(())
+}
+
+#[test]
+fn test_show_config() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbose: bool,
+        #[default(80)]
+        width: u32,
+        name: Option<String>,
+        include: Occurrences<String>,
+        #[origins]
+        origins: onlyargs::source::Origins,
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    struct NoOrigins {
+        verbose: bool,
+        #[positional]
+        files: Vec<PathBuf>,
+    }
+
+    let args = Args::parse(
+        ["-v", "--name", "Alice", "-i", "a"]
+            .map(OsString::from)
+            .to_vec(),
+    )?;
+
+    assert_eq!(
+        args.show_config(),
+        "verbose  true           command line\n\
+        width    80             default\n\
+        name     Some(\"Alice\")  command line\n\
+        include  [\"a\"]          command line\n",
+    );
+
+    let args = NoOrigins::parse(vec!["x.txt".into()])?;
+
+    assert_eq!(args.show_config(), "verbose  false\nfiles    [\"x.txt\"]\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_verbosity() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbosity: Verbosity,
+        #[long]
+        width: Option<i32>,
+    }
+
+    let args = Args::parse(
+        ["-vv", "--width", "3", "--verbose", "-q"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.verbosity, Verbosity::new(3, 1));
+    assert_eq!(args.verbosity.level(), 2);
+    assert_eq!(args.width, Some(3));
+    assert_eq!(
+        args.to_args(),
+        [
+            "--verbose",
+            "--verbose",
+            "--verbose",
+            "--quiet",
+            "--width",
+            "3"
+        ],
+    );
+
+    let args = Args::parse(vec!["-qq".into()])?;
+
+    assert_eq!(args.verbosity.level(), -2);
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.verbosity, Verbosity::default());
+    assert!(Args::HELP.contains("-v --verbose"));
+    assert!(Args::HELP.contains("-q --quiet"));
+
+    assert!(Args::parse(vec!["-vx".into()]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_init_logging() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        verbosity: Verbosity,
+    }
+
+    let args = Args::parse(vec!["-v".into()])?;
+    args.init_logging();
+
+    assert_eq!(log::max_level(), log::LevelFilter::Info);
+
+    Ok(())
+}
+
+#[test]
+fn test_feature_types() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[impl_default]
+    struct Args {
+        #[default("https://example.com/")]
+        endpoint: url::Url,
+        id: Option<uuid::Uuid>,
+        #[long]
+        versions: Vec<semver::Version>,
+    }
+
+    let args = Args::parse(
+        [
+            "--id",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "--versions",
+            "1.0.0",
+            "--versions",
+            "2.1.0-rc.1",
+        ]
+        .into_iter()
+        .map(OsString::from)
+        .collect(),
+    )?;
+
+    assert_eq!(args.endpoint.as_str(), "https://example.com/");
+    assert_eq!(args.id.map(|id| id.get_version_num()), Some(4));
+    assert_eq!(
+        args.versions[1],
+        semver::Version::parse("2.1.0-rc.1").unwrap()
+    );
+    assert_eq!(
+        args.to_args(),
+        [
+            "--endpoint",
+            "https://example.com/",
+            "--id",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "--versions",
+            "1.0.0",
+            "--versions",
+            "2.1.0-rc.1",
+        ],
+    );
+    assert_eq!(Args::default().endpoint.as_str(), "https://example.com/");
+    assert!(Args::HELP.contains("-e --endpoint URL"));
+    assert!(Args::HELP.contains("-i --id UUID"));
+    assert!(Args::HELP.contains("--versions VERSION"));
+
+    let err = Args::parse(vec!["--versions".into(), "1".into()]).unwrap_err();
+
+    assert!(matches!(
+        err.inner(),
+        CliError::ParseVersionError { argument, .. } if argument == "--versions",
+    ));
+
+    let err = Args::parse(vec!["-e".into(), "example.com".into()]).unwrap_err();
+
+    assert!(matches!(
+        err.inner(),
+        CliError::ParseUrlError { argument, .. } if argument == "--endpoint",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_color_choice() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    #[impl_default]
+    struct Args {
+        color: ColorChoice,
+        #[default("never")]
+        #[short('p')]
+        paint: ColorChoice,
+    }
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.color, ColorChoice::Auto);
+    assert_eq!(args.paint, ColorChoice::Never);
+
+    let args = Args::parse(
+        ["--color", "always", "-p", "auto"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.color, ColorChoice::Always);
+    assert_eq!(args.paint, ColorChoice::Auto);
+    assert_eq!(args.to_args(), ["--color", "always", "--paint", "auto"]);
+    assert_eq!(Args::default().paint, ColorChoice::Never);
+    assert!(Args::HELP.contains("--color WHEN"));
+    assert!(Args::HELP.contains("[possible values: auto, always, never]"));
+
+    assert!(matches!(
+        Args::parse(vec!["--color".into(), "sometimes".into()]),
+        Err(CliError::InvalidChoice { argument, .. }) if argument == "--color",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_usage() {
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        username: String,
+        output: Option<PathBuf>,
+
+        #[required]
+        names: Vec<String>,
+
+        #[positional]
+        #[required]
+        files: Vec<PathBuf>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, OnlyArgs)]
+    #[usage("{bin_name} [options] <files>...")]
+    #[usage("{bin_name} --version")]
+    struct CustomArgs {
+        #[positional]
+        files: Vec<PathBuf>,
+    }
+
+    assert!(Args::HELP.contains(
+        "\nUsage:\n  {bin_name} --username <STRING> --names <STRING>... [flags] [options] <files>...\n"
+    ));
+    assert!(CustomArgs::HELP
+        .contains("\nUsage:\n  {bin_name} [options] <files>...\n  {bin_name} --version\n"));
+}
+
+#[test]
+fn test_header() {
+    /// Doc comment.
+    #[derive(Debug, OnlyArgs)]
+    #[header = "Warning: this tool is experimental."]
+    #[header = "See https://example.com"]
+    struct Args {}
+
+    assert!(Args::HELP.contains(
+        "\n\nWarning: this tool is experimental.\nSee https://example.com\n\nDoc comment.\n\nUsage:\n"
+    ));
+}
+
+#[test]
+fn test_aliases_and_possible_values() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// Enable verbose output.
+        #[alias("debug")]
+        verbose: bool,
+
+        /// When to use colors.
+        #[alias("colour")]
+        #[possible_values("auto", "always", "never")]
+        #[default("auto")]
+        color: String,
+
+        /// Output formats.
+        #[possible_values("json", "text")]
+        formats: Vec<String>,
+    }
+
+    assert!(Args::HELP.contains("Enable verbose output. [aliases: --debug]\n"));
+    assert!(Args::HELP.contains(
+        "When to use colors. [aliases: --colour] \
+        [possible values: auto, always, never] [default: \"auto\"]\n"
+    ));
+
+    let args = Args::parse(
+        ["--debug", "--colour", "never", "-f", "json"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert!(args.verbose);
+    assert_eq!(args.color, "never");
+    assert_eq!(args.formats, ["json"]);
+
+    assert!(matches!(
+        Args::parse(
+            ["--color", "sometimes"]
+                .into_iter()
+                .map(OsString::from)
+                .collect(),
+        ),
+        Err(CliError::InvalidChoice { argument, value, .. }) if argument == "--color" && value == "sometimes",
+    ));
+    assert!(matches!(
+        Args::parse(
+            ["--formats", "json", "--formats", "xml"]
+                .into_iter()
+                .map(OsString::from)
+                .collect(),
+        ),
+        Err(CliError::InvalidChoice { argument, value, .. }) if argument == "--formats" && value == "xml",
+    ));
+
+    #[derive(Debug, OnlyArgs)]
+    struct EnvArgs {
+        #[possible_values("auto", "always", "never")]
+        #[env("ONLYARGS_TEST_CHOICE_COLOR")]
+        color: Option<String>,
+    }
+
+    // Values from the environment report the environment variable name.
+    std::env::set_var("ONLYARGS_TEST_CHOICE_COLOR", "sometimes");
+
+    assert!(matches!(
+        EnvArgs::parse(vec![]),
+        Err(CliError::InvalidChoice { argument, .. }) if argument == "ONLYARGS_TEST_CHOICE_COLOR",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_arg_type() -> Result<(), CliError> {
+    type Port = u16;
+    type Ratio = f32;
+
+    mod paths {
+        pub use std::path::PathBuf as IncludeDir;
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        #[arg_type(integer)]
+        #[default(8080)]
+        port: Port,
+        #[arg_type(float)]
+        ratio: Option<Ratio>,
+        #[arg_type(path)]
+        include: Vec<paths::IncludeDir>,
+    }
+
+    assert!(Args::HELP.contains("\n  -p --port INTEGER"));
+    assert!(Args::HELP.contains("\n  -i --include PATH"));
+
+    let args = Args::parse(
+        ["-r", "0.5", "-i", "a", "-i", "b"]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+    )?;
+
+    assert_eq!(args.port, 8080);
+    assert_eq!(args.ratio, Some(0.5));
+    assert_eq!(args.include, [PathBuf::from("a"), PathBuf::from("b")]);
+
+    let err =
+        Args::parse(["--port", "http"].into_iter().map(OsString::from).collect()).unwrap_err();
+    assert!(matches!(err.inner(), CliError::ParseIntError { .. }));
+
+    Ok(())
+}
+
+#[test]
+fn test_tuple_struct() -> Result<(), CliError> {
+    /// Copy a file.
+    #[derive(Debug, OnlyArgs)]
+    struct Args(
+        /// File to copy.
+        #[value_name("SRC")]
+        PathBuf,
+        /// Destination path.
+        #[value_name("DEST")]
+        PathBuf,
+        /// Number of copies.
+        Option<u32>,
+    );
+
+    /// Concatenate files.
+    #[derive(Debug, OnlyArgs)]
+    struct Rest(
+        /// Output file.
+        PathBuf,
+        /// Files to concatenate.
+        #[value_name("FILE")]
+        Vec<PathBuf>,
+    );
+
+    assert!(Args::USAGE.ends_with(" [flags] <SRC> <DEST> [INTEGER]"));
+    assert!(!Args::HELP.contains("Options:"));
+    assert!(Args::HELP.contains(
+        "\nArguments:\n  \
+        <SRC>      File to copy.\n  \
+        <DEST>     Destination path.\n  \
+        <INTEGER>  Number of copies.\n"
+    ));
+
+    let args = Args::parse(["a.txt", "b.txt"].map(OsString::from).to_vec())?;
+
+    assert_eq!(args.0, Path::new("a.txt"));
+    assert_eq!(args.1, Path::new("b.txt"));
+    assert_eq!(args.2, None);
+
+    let args = Args::parse(["a.txt", "--", "-b.txt", "3"].map(OsString::from).to_vec())?;
+
+    assert_eq!(args.1, Path::new("-b.txt"));
+    assert_eq!(args.2, Some(3));
+    assert_eq!(args.to_args(), ["--", "a.txt", "-b.txt", "3"]);
+    assert_eq!(
+        args.show_config(),
+        "0  \"a.txt\"\n1  \"-b.txt\"\n2  Some(3)\n"
+    );
+
+    let err = Args::parse(vec!["a.txt".into()]).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        CliError::MissingRequired { argument } if argument == "<DEST>",
+    ));
+    let err = Args::parse(["a", "b", "3", "c"].map(OsString::from).to_vec()).unwrap_err();
+    assert!(matches!(err.inner(), CliError::Unknown { argument } if argument == "c"));
+
+    let (args, remainder) = Args::parse_partial(["a", "b", "3", "c"].map(OsString::from).to_vec())?;
+    assert_eq!(args.2, Some(3));
+    assert_eq!(remainder, ["c"]);
+
+    let args = Rest::parse(["out", "a", "b"].map(OsString::from).to_vec())?;
+
+    assert_eq!(args.0, Path::new("out"));
+    assert_eq!(args.1, [PathBuf::from("a"), PathBuf::from("b")]);
+    assert!(Rest::USAGE.ends_with(" <PATH> [FILE...]"));
+
+    let spec = |name| Rest::SPEC.iter().find(|arg| arg.name == name).copied();
+    let files = spec("file").unwrap();
+    assert!(files.multiple && !files.required);
+    assert!(spec("path").unwrap().required);
+
+    let matches = onlyargs::clap::command("cp", "1.0.0", Args::SPEC)
+        .try_get_matches_from(["cp", "a.txt", "b.txt", "3"])
+        .unwrap();
+    assert_eq!(
+        matches.get_one::<PathBuf>("dest"),
+        Some(&PathBuf::from("b.txt"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_flag_enum() -> Result<(), CliError> {
+    #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+    enum Format {
+        #[default]
+        Text,
+        Json,
+        PrettyJson,
+    }
+
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    enum Mode {
+        DryRun,
+        Force,
+    }
+
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        /// Output format.
+        #[flag_enum(Json, PrettyJson)]
+        format: Format,
+        /// How to apply changes.
+        #[flag_enum(DryRun, Force)]
+        mode: Option<Mode>,
+    }
+
+    let args = Args::parse(vec![])?;
+
+    assert_eq!(args.format, Format::Text);
+    assert_eq!(args.mode, None);
+    assert!(args.to_args().is_empty());
+
+    let args = Args::parse(
+        ["--pretty-json", "--dry-run", "--dry-run"]
+            .map(OsString::from)
+            .to_vec(),
+    )?;
+
+    assert_eq!(args.format, Format::PrettyJson);
+    assert_eq!(args.mode, Some(Mode::DryRun));
+    assert_eq!(args.to_args(), ["--pretty-json", "--dry-run"]);
+
+    let err = Args::parse(["--json", "--pretty-json"].map(OsString::from).to_vec()).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        CliError::Conflict { argument, other } if argument == "--pretty-json" && other == "--json",
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Argument `--pretty-json` cannot be used with `--json` (argument 2)"
+    );
+
+    assert!(Args::HELP.contains("--json"));
+    assert!(Args::HELP.contains("Output format. [conflicts with: --pretty-json]"));
+    assert!(Args::HELP.contains("How to apply changes. [conflicts with: --dry-run]"));
+
+    Ok(())
+}
+
+#[test]
+fn test_pointer_types() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        name: Box<str>,
+        #[default("/tmp")]
+        root: Box<Path>,
+        tag: Option<Rc<str>>,
+        label: Vec<Arc<str>>,
+        #[default("eth0")]
+        interface: CString,
+        #[possible_values("a", "b")]
+        mode: Option<Box<str>>,
+    }
+
+    let args = Args::parse(
+        [
+            "--name", "ferris", "--tag", "x", "--label", "a", "--label", "b", "--mode", "a",
+        ]
+        .map(OsString::from)
+        .to_vec(),
+    )?;
+
+    assert_eq!(&*args.name, "ferris");
+    assert_eq!(&*args.root, Path::new("/tmp"));
+    assert_eq!(args.tag.as_deref(), Some("x"));
+    assert_eq!(args.label, [Arc::from("a"), Arc::from("b")]);
+    assert_eq!(args.interface.as_bytes(), b"eth0");
+    assert_eq!(args.mode.as_deref(), Some("a"));
+    assert_eq!(
+        args.to_args(),
+        [
+            "--name",
+            "ferris",
+            "--root",
+            "/tmp",
+            "--tag",
+            "x",
+            "--label",
+            "a",
+            "--label",
+            "b",
+            "--interface",
+            "eth0",
+            "--mode",
+            "a"
+        ],
+    );
+    assert!(Args::HELP.contains("--name STRING"));
+    assert!(Args::HELP.contains("--root PATH"));
+
+    let err = Args::parse(
+        ["--name", "a", "--interface", "a\0b"]
+            .map(OsString::from)
+            .to_vec(),
+    )
+    .unwrap_err();
+    assert!(matches!(err.inner(), CliError::Custom { .. }));
+    let err = Args::parse(["--name", "a", "--mode", "c"].map(OsString::from).to_vec()).unwrap_err();
+    assert!(matches!(err.inner(), CliError::InvalidChoice { .. }));
+
+    Ok(())
+}
+
+#[test]
+fn test_expand_tilde() -> Result<(), CliError> {
+    #[derive(Debug, OnlyArgs)]
+    struct Args {
+        #[expand_tilde]
+        #[default("~/.config/app.toml")]
+        config: PathBuf,
+        #[expand_tilde]
+        include: Vec<PathBuf>,
+        #[expand_tilde]
+        #[long]
+        cache: Option<Box<Path>>,
+        output: Option<PathBuf>,
+    }
+
+    let home = PathBuf::from(
+        std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).unwrap(),
+    );
+
+    let args = Args::parse(
+        [
+            "--include",
+            "~/lib",
+            "--include",
+            "~other/lib",
+            "--cache",
+            "~",
+            "--output",
+            "~/out",
+        ]
+        .map(OsString::from)
+        .to_vec(),
+    )?;
+
+    assert_eq!(args.config, home.join(".config/app.toml"));
+    assert_eq!(
+        args.include,
+        [home.join("lib"), PathBuf::from("~other/lib")]
+    );
+    assert_eq!(args.cache.as_deref(), Some(home.as_path()));
+    assert_eq!(args.output.as_deref(), Some(Path::new("~/out")));
+    assert!(Args::HELP.contains(r#"[default: "~/.config/app.toml"]"#));
+
+    let args = Args::parse(["--config", "/etc/app.toml"].map(OsString::from).to_vec())?;
+
+    assert_eq!(args.config, Path::new("/etc/app.toml"));
+
+    Ok(())
+}
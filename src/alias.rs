@@ -0,0 +1,203 @@
+//! Git-style command aliases.
+//!
+//! An alias replaces the first command line argument with a list of arguments before parsing. For
+//! example, the alias `co = "checkout --quiet"` expands `myapp co main` into
+//! `myapp checkout --quiet main`. Applications supply the alias map, e.g. from their own
+//! configuration file.
+//!
+//! The alias body is split into words like a POSIX shell would, so arguments with spaces can be
+//! quoted. See [`split_words`] for the supported syntax.
+//!
+//! Aliases may expand to other aliases. Expanding the same alias twice is an error, which protects
+//! against infinite recursion.
+//!
+//! # Example
+//!
+//! ```
+//! use onlyargs::alias::Aliases;
+//! use std::ffi::OsString;
+//!
+//! let aliases = Aliases::new()
+//!     .add("co", "checkout --quiet")
+//!     .add("cm", "co main");
+//!
+//! let args = ["cm", "--force"].into_iter().map(OsString::from).collect();
+//! let args = aliases.expand(args)?;
+//!
+//! assert_eq!(args, ["checkout", "--quiet", "main", "--force"]);
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+
+use crate::CliError;
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+/// A map of alias names to their expansions.
+#[derive(Clone, Debug, Default)]
+pub struct Aliases {
+    aliases: HashMap<String, String>,
+}
+
+impl Aliases {
+    /// Create an empty alias map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an alias. The `expansion` is split into words with [`split_words`] when it is used.
+    ///
+    /// An existing alias with the same name is replaced.
+    #[must_use]
+    pub fn add<N, E>(mut self, name: N, expansion: E) -> Self
+    where
+        N: Into<String>,
+        E: Into<String>,
+    {
+        self.aliases.insert(name.into(), expansion.into());
+        self
+    }
+
+    /// Get the expansion for an alias.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.aliases.get(name).map(String::as_str)
+    }
+
+    /// Expand aliases in the first argument.
+    ///
+    /// The arguments are returned unchanged if the first argument is not an alias.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an alias expands to itself (directly or through other aliases), or if the
+    /// expansion cannot be split into words.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::{alias::Aliases, CliError};
+    ///
+    /// let aliases = Aliases::new().add("a", "b").add("b", "a --verbose");
+    ///
+    /// assert!(matches!(
+    ///     aliases.expand(vec!["a".into()]),
+    ///     Err(CliError::AliasRecursion { alias }) if alias == "a",
+    /// ));
+    /// ```
+    pub fn expand(&self, mut args: Vec<OsString>) -> Result<Vec<OsString>, CliError> {
+        let mut seen = vec![];
+
+        while let Some((name, expansion)) = args
+            .first()
+            .and_then(|arg| arg.to_str())
+            .and_then(|arg| self.aliases.get_key_value(arg))
+        {
+            if seen.contains(&name) {
+                return Err(CliError::AliasRecursion {
+                    alias: name.clone(),
+                });
+            }
+            seen.push(name);
+
+            let words = split_words(expansion).map_err(|message| CliError::ParseAliasError {
+                alias: name.clone(),
+                message,
+            })?;
+            args.splice(..1, words.into_iter().map(OsString::from));
+        }
+
+        Ok(args)
+    }
+}
+
+impl<N, E> FromIterator<(N, E)> for Aliases
+where
+    N: Into<String>,
+    E: Into<String>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (N, E)>,
+    {
+        Self {
+            aliases: iter
+                .into_iter()
+                .map(|(name, expansion)| (name.into(), expansion.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Split a string into words like a POSIX shell.
+///
+/// - Words are separated by whitespace.
+/// - Characters in `'single quotes'` are taken literally.
+/// - Characters in `"double quotes"` are taken literally, except that `\` escapes `"`, `\`, `$`,
+///   and `` ` ``.
+/// - Outside of quotes, `\` escapes any character.
+///
+/// Variable expansion, globbing, and other shell features are not supported.
+///
+/// # Errors
+///
+/// Returns `Err` with a description of the problem if a quote is not terminated or the string
+/// ends with a `\`.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::alias::split_words;
+///
+/// let words = split_words(r#"commit -m "Hello, world!" --author='A. U. Thor'"#)?;
+///
+/// assert_eq!(words, ["commit", "-m", "Hello, world!", "--author=A. U. Thor"]);
+/// # Ok::<_, String>(())
+/// ```
+pub fn split_words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = vec![];
+    let mut word = None;
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => word.push(ch),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => word.push(ch),
+                            Some(ch) => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(ch) => word.push(ch),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(ch) => word.get_or_insert_with(String::new).push(ch),
+                None => return Err("trailing backslash".to_string()),
+            },
+            ch => word.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
@@ -0,0 +1,260 @@
+//! Support code for the [`args!`](crate::args) macro.
+//!
+//! Nothing in this module is part of the public API.
+
+use crate::help::HelpBuilder;
+use crate::lexer::{Arg, Lexer};
+use crate::runtime::FromArg;
+use crate::traits::RequiredArgExt as _;
+use crate::CliError;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Value types that options can take.
+pub trait Value: FromArg {
+    /// The value placeholder shown in the help text.
+    const VALUE_NAME: &'static str;
+}
+
+macro_rules! impl_value {
+    ($value_name:literal => $($ty:ty),*) => {
+        $(
+            impl Value for $ty {
+                const VALUE_NAME: &'static str = $value_name;
+            }
+        )*
+    };
+}
+
+impl_value!("STRING" => String, OsString);
+impl_value!("PATH" => PathBuf);
+impl_value!("CHAR" => char);
+impl_value!("INTEGER" => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_value!("FLOAT" => f32, f64);
+
+/// Field types: flags, required options, optional options, and multivalue options.
+pub trait Field: Sized {
+    /// The values collected while parsing.
+    type State: Default;
+
+    /// The value placeholder shown in the help text, or `None` for flags.
+    const VALUE_NAME: Option<&'static str>;
+
+    /// Whether the field must be given when it does not have a default value.
+    const REQUIRED: bool;
+
+    /// Read the field from the command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the value is missing or cannot be parsed.
+    fn push(state: &mut Self::State, lexer: &mut Lexer, name: &str) -> Result<(), CliError>;
+
+    /// Produce the field from the collected values.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a required field was not given.
+    fn finish(state: Self::State, default: Option<Self>, name: &str) -> Result<Self, CliError>;
+}
+
+impl Field for bool {
+    type State = bool;
+    const VALUE_NAME: Option<&'static str> = None;
+    const REQUIRED: bool = false;
+
+    fn push(state: &mut Self::State, _lexer: &mut Lexer, _name: &str) -> Result<(), CliError> {
+        *state = true;
+        Ok(())
+    }
+
+    fn finish(state: Self::State, default: Option<Self>, _name: &str) -> Result<Self, CliError> {
+        Ok(state || default.unwrap_or_default())
+    }
+}
+
+impl<T: Value> Field for T {
+    type State = Option<T>;
+    const VALUE_NAME: Option<&'static str> = Some(T::VALUE_NAME);
+    const REQUIRED: bool = true;
+
+    fn push(state: &mut Self::State, lexer: &mut Lexer, name: &str) -> Result<(), CliError> {
+        *state = Some(T::from_arg(lexer.value()?, name)?);
+        Ok(())
+    }
+
+    fn finish(state: Self::State, default: Option<Self>, name: &str) -> Result<Self, CliError> {
+        state.or(default).required(name)
+    }
+}
+
+impl<T: Value> Field for Option<T> {
+    type State = Option<T>;
+    const VALUE_NAME: Option<&'static str> = Some(T::VALUE_NAME);
+    const REQUIRED: bool = false;
+
+    fn push(state: &mut Self::State, lexer: &mut Lexer, name: &str) -> Result<(), CliError> {
+        *state = Some(T::from_arg(lexer.value()?, name)?);
+        Ok(())
+    }
+
+    fn finish(state: Self::State, default: Option<Self>, _name: &str) -> Result<Self, CliError> {
+        Ok(state.or_else(|| default.flatten()))
+    }
+}
+
+impl<T: Value> Field for Vec<T> {
+    type State = Vec<T>;
+    const VALUE_NAME: Option<&'static str> = Some(T::VALUE_NAME);
+    const REQUIRED: bool = false;
+
+    fn push(state: &mut Self::State, lexer: &mut Lexer, name: &str) -> Result<(), CliError> {
+        state.push(T::from_arg(lexer.value()?, name)?);
+        Ok(())
+    }
+
+    fn finish(state: Self::State, default: Option<Self>, _name: &str) -> Result<Self, CliError> {
+        match default {
+            Some(default) if state.is_empty() => Ok(default),
+            _ => Ok(state),
+        }
+    }
+}
+
+/// A field of the argument struct, for the help text and matching arguments.
+#[derive(Debug)]
+pub struct FieldInfo {
+    /// The field name.
+    pub name: &'static str,
+
+    /// The value placeholder shown in the help text, or `None` for flags.
+    pub value_name: Option<&'static str>,
+
+    /// Whether the field must be given.
+    pub required: bool,
+
+    /// The lines of the doc comment.
+    pub doc: &'static [&'static str],
+
+    /// The default value expression, if any.
+    pub default: Option<&'static str>,
+}
+
+/// The arguments of an argument struct.
+#[derive(Debug)]
+pub struct Fields {
+    fields: Vec<(FieldInfo, String, Option<char>)>,
+}
+
+impl Fields {
+    /// Assign long and short names to the fields.
+    ///
+    /// The long name is the field name with underscores replaced by hyphens. The short name is the
+    /// first character of the field name, unless it is already used by `--help`, `--version`, or an
+    /// earlier field.
+    #[must_use]
+    pub fn new(fields: Vec<FieldInfo>) -> Self {
+        let mut used = vec!['h', 'V'];
+        let fields = fields
+            .into_iter()
+            .map(|field| {
+                let name = field.name.trim_start_matches("r#");
+                let long = name.replace('_', "-");
+                let short = name.chars().next().filter(|ch| !used.contains(ch));
+                used.extend(short);
+
+                (field, long, short)
+            })
+            .collect();
+
+        Self { fields }
+    }
+
+    /// Find the field that an argument refers to, and return its name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is not known.
+    pub fn find(&self, arg: Arg<'_>) -> Result<&'static str, CliError> {
+        self.fields
+            .iter()
+            .find(|(_, long, short)| match &arg {
+                Arg::Short(ch) => Some(*ch) == *short,
+                Arg::Long(name) => name == long,
+                Arg::Value(_) => false,
+            })
+            .map(|(field, _, _)| field.name)
+            .ok_or_else(|| {
+                let known = self
+                    .fields
+                    .iter()
+                    .map(|(_, long, _)| format!("--{long}"))
+                    .chain(["--help".to_string(), "--version".to_string()])
+                    .collect::<Vec<_>>();
+                let known = known.iter().map(String::as_str).collect::<Vec<_>>();
+
+                CliError::unknown(arg.into(), &known)
+            })
+    }
+
+    /// The argument name of a field for error messages, like `--width`.
+    #[must_use]
+    pub fn label(&self, name: &str) -> String {
+        self.fields
+            .iter()
+            .find(|(field, _, _)| field.name == name)
+            .map(|(_, long, _)| format!("--{long}"))
+            .unwrap_or_default()
+    }
+
+    /// Produce the help text.
+    #[must_use]
+    pub fn help(&self, header: &str) -> String {
+        let builder = HelpBuilder::new()
+            .line(header.trim_end())
+            .section("Usage:")
+            .line("  {bin_name} [flags] [options]")
+            .section("Flags:")
+            .flag("help", 'h', "Show this help message.")
+            .flag("version", 'V', "Show the application version.");
+        let builder = self
+            .fields
+            .iter()
+            .filter(|(field, _, _)| field.value_name.is_none())
+            .fold(builder, |builder, (field, long, short)| {
+                builder.flag(long, *short, Self::description(field))
+            });
+
+        self.fields
+            .iter()
+            .filter_map(|(field, long, short)| Some((field, long, short, field.value_name?)))
+            .fold(
+                builder.section("Options:"),
+                |builder, (field, long, short, value_name)| {
+                    builder.option(long, *short, value_name, Self::description(field))
+                },
+            )
+            .build()
+    }
+
+    // The doc comment, with a note for the default value or a required option.
+    fn description(field: &FieldInfo) -> String {
+        let mut doc = field
+            .doc
+            .iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let note = match field.default {
+            Some(default) => format!("[default: {}]", default.trim_matches('"')),
+            None if field.required => "[required]".to_string(),
+            None => return doc,
+        };
+        if !doc.is_empty() {
+            doc.push(' ');
+        }
+        doc.push_str(&note);
+
+        doc
+    }
+}
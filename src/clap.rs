@@ -0,0 +1,113 @@
+//! Construct a [`clap::Command`](::clap::Command) from the argument descriptions in
+//! [`OnlyArgs::SPEC`](crate::OnlyArgs::SPEC).
+//!
+//! The command accepts the same arguments as the `OnlyArgs` parser, so tools from the clap
+//! ecosystem like `clap_complete` and `clap_mangen` can be used with it. This helps when migrating
+//! between the two crates. It is not meant for parsing; the `OnlyArgs` parser is the source of
+//! truth.
+//!
+//! This module requires the `clap` feature, which has a higher MSRV than the rest of the crate.
+//!
+//! # Example
+//!
+//! ```
+//! use onlyargs::{ArgKind, ArgSpec, ValueType};
+//!
+//! let spec = [
+//!     ArgSpec::new("width", ArgKind::Option(ValueType::Integer), "Set the width.")
+//!         .with_short('w')
+//!         .with_value_name("INTEGER")
+//!         .with_default("80"),
+//! ];
+//!
+//! let command = onlyargs::clap::command("mytool", "1.0.0", &spec);
+//! let matches = command.get_matches_from(["mytool", "-w", "42"]);
+//!
+//! assert_eq!(matches.get_one::<i128>("width"), Some(&42));
+//! ```
+
+use crate::{ArgKind, ArgSpec, Complete, ValueType};
+use ::clap::builder::ValueParser;
+use ::clap::{Arg, ArgAction, Command, ValueHint};
+
+/// Construct a command named `bin_name` with the given version number and arguments.
+///
+/// Arguments named `help` and `version` print the help and version messages, like they do in the
+/// `OnlyArgs` parser. Integers are parsed as `i128`, floats as `f64`, paths as `PathBuf`, and other
+/// values as `String` or `OsString`.
+#[must_use]
+pub fn command(bin_name: &str, version: &str, spec: &[ArgSpec]) -> Command {
+    spec.iter().fold(
+        Command::new(bin_name.to_string())
+            .version(version.to_string())
+            .disable_help_flag(true)
+            .disable_version_flag(true),
+        |command, spec| command.arg(arg(spec)),
+    )
+}
+
+fn arg(spec: &ArgSpec) -> Arg {
+    let mut arg = Arg::new(spec.name)
+        .help(spec.help.lines().next().unwrap_or_default())
+        .long_help(spec.help)
+        .required(spec.required);
+
+    let ty = match spec.kind {
+        ArgKind::Flag => {
+            let action = match spec.name {
+                "help" => ArgAction::Help,
+                "version" => ArgAction::Version,
+                _ if spec.multiple => ArgAction::Count,
+                _ => ArgAction::SetTrue,
+            };
+            return arg.long(spec.name).short(spec.short).action(action);
+        }
+        ArgKind::Option(ty) => {
+            arg = arg
+                .long(spec.name)
+                .short(spec.short)
+                .action(if spec.multiple {
+                    ArgAction::Append
+                } else {
+                    ArgAction::Set
+                });
+
+            ty
+        }
+        ArgKind::Positional(ty) => {
+            arg = if spec.multiple {
+                arg.num_args(1..).action(ArgAction::Append)
+            } else {
+                arg.action(ArgAction::Set)
+            };
+
+            ty
+        }
+    };
+
+    if let Some(value_name) = spec.value_name {
+        arg = arg.value_name(value_name);
+    }
+    if let Some(default) = spec.default {
+        arg = arg.default_value(default);
+    }
+
+    let hint = match (spec.complete, ty) {
+        (Complete::File, _) | (Complete::Default, ValueType::Path) => ValueHint::FilePath,
+        (Complete::Dir, _) => ValueHint::DirPath,
+        (Complete::Hostname, _) => ValueHint::Hostname,
+        (Complete::Default | Complete::Callback, _) => ValueHint::Unknown,
+    };
+
+    arg.value_parser(value_parser(ty)).value_hint(hint)
+}
+
+fn value_parser(ty: ValueType) -> ValueParser {
+    match ty {
+        ValueType::Float => ::clap::value_parser!(f64).into(),
+        ValueType::Integer => ::clap::value_parser!(i128).into(),
+        ValueType::OsString => ValueParser::os_string(),
+        ValueType::Path => ValueParser::path_buf(),
+        ValueType::String => ValueParser::string(),
+    }
+}
@@ -0,0 +1,76 @@
+//! Colored error messages.
+//!
+//! With the `color` feature, [`OnlyArgs::exit_with_error`] and [`Report`] style the error message
+//! when `stderr` is a terminal: the `Error:` label is red, and argument names and values are bold.
+//!
+//! Colors are disabled when the [`NO_COLOR`] environment variable is set to a non-empty value.
+//!
+//! Terminals are detected with [`std::io::IsTerminal`]. This feature has a higher MSRV than the
+//! rest of the crate.
+//!
+//! [`OnlyArgs::exit_with_error`]: crate::OnlyArgs::exit_with_error
+//! [`Report`]: crate::Report
+//! [`NO_COLOR`]: https://no-color.org/
+
+use crate::help::Output;
+use crate::types::ColorChoice;
+
+const RED_BOLD: &str = "\x1b[1;31m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Check whether error messages printed to `stderr` should be colored.
+///
+/// Returns `false` if `NO_COLOR` is set to a non-empty value, or if `stderr` is not a terminal.
+/// This is [`ColorChoice::Auto`] applied to `stderr`, and it does not spawn any processes.
+#[must_use]
+pub fn enabled() -> bool {
+    ColorChoice::Auto.should_colorize(Output::Stderr)
+}
+
+/// Style an error message produced by [`format_error`] with terminal colors.
+///
+/// The leading `Error:` label is colored red, and text quoted with backticks is bold. The quotes
+/// are kept, so the message reads the same when colors are not shown.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::color::style;
+///
+/// assert_eq!(
+///     style("Error: Missing value for `--name`\n"),
+///     "\x1b[1;31mError:\x1b[0m Missing value for `\x1b[1m--name\x1b[0m`\n",
+/// );
+/// ```
+///
+/// [`format_error`]: crate::format_error
+#[must_use]
+pub fn style(message: &str) -> String {
+    let (label, message) = match message.strip_prefix("Error:") {
+        Some(message) => (format!("{RED_BOLD}Error:{RESET}"), message),
+        None => (String::new(), message),
+    };
+
+    let mut output = label;
+    for line in message.split_inclusive('\n') {
+        let mut parts = line.split('`');
+        output.push_str(parts.next().unwrap_or_default());
+
+        // Only style quotes that are closed on the same line.
+        let parts = parts.collect::<Vec<_>>();
+        let closed = parts.len() - parts.len() % 2;
+        for (index, part) in parts.iter().enumerate() {
+            output.push('`');
+            if index < closed && index % 2 == 0 {
+                output.push_str(BOLD);
+                output.push_str(part);
+                output.push_str(RESET);
+            } else {
+                output.push_str(part);
+            }
+        }
+    }
+
+    output
+}
@@ -0,0 +1,334 @@
+//! Shell completion scripts.
+//!
+//! Completion scripts are generated from the argument descriptions in
+//! [`OnlyArgs::SPEC`], which `onlyargs_derive` provides. Options that take
+//! a path complete file names, unless [`ArgSpec::complete`] asks for something else.
+//!
+//! For [`Complete::Callback`], the script runs the binary with the [`COMPLETE_ENV`] environment
+//! variable set to the argument name, and the word being completed as the only argument. The
+//! binary prints the candidates from [`OnlyArgs::complete`] with [`respond`], which
+//! [`parse`](crate::parse) calls before parsing.
+//!
+//! Packagers usually install the script next to the binary:
+//!
+//! - zsh: Save it as `_{bin_name}` in a directory on `$fpath`.
+//! - fish: Save it as `{bin_name}.fish` in `~/.config/fish/completions`.
+//! - Nushell: Save it as a module and `use` it in `config.nu`.
+//!
+//! ```no_run
+//! # #[derive(Debug)]
+//! # struct Args;
+//! # impl onlyargs::OnlyArgs for Args {
+//! #     fn parse(_args: Vec<std::ffi::OsString>) -> Result<Self, onlyargs::CliError> {
+//! #         Ok(Args)
+//! #     }
+//! # }
+//! use onlyargs::completion::Shell;
+//! use onlyargs::OnlyArgs as _;
+//!
+//! print!("{}", Args::completions(Shell::Zsh));
+//! ```
+
+use crate::{ArgKind, ArgSpec, CliError, Complete, OnlyArgs, ValueType};
+use std::env;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::str::FromStr;
+
+/// The environment variable that completion scripts set to ask the binary for candidates.
+pub const COMPLETE_ENV: &str = "ONLYARGS_COMPLETE";
+
+/// A shell that completion scripts can be generated for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Shell {
+    /// The Z shell, using `_arguments`.
+    Zsh,
+
+    /// The friendly interactive shell, using `complete` commands.
+    Fish,
+
+    /// Nushell, using an `extern` signature.
+    Nushell,
+}
+
+impl FromStr for Shell {
+    type Err = CliError;
+
+    /// Parse a shell name, like `zsh`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            "nu" | "nushell" => Ok(Self::Nushell),
+            _ => Err(CliError::InvalidChoice {
+                argument: "shell".to_string(),
+                value: name.into(),
+                choices: vec!["zsh", "fish", "nushell"],
+            }),
+        }
+    }
+}
+
+/// Generate a completion script for the binary named `bin_name`.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::completion::{generate, Shell};
+/// use onlyargs::{ArgKind, ArgSpec, ValueType};
+///
+/// let spec = [
+///     ArgSpec::new("output", ArgKind::Option(ValueType::Path), "Write output to a file.")
+///         .with_short('o')
+///         .with_value_name("PATH"),
+/// ];
+///
+/// let script = generate(Shell::Zsh, "mytool", &spec);
+///
+/// assert!(script.starts_with("#compdef mytool\n"));
+/// assert!(script.contains("'(-o --output)'{-o,--output}'[Write output to a file.]:PATH:_files'"));
+/// ```
+#[must_use]
+pub fn generate(shell: Shell, bin_name: &str, spec: &[ArgSpec]) -> String {
+    match shell {
+        Shell::Zsh => zsh(bin_name, spec),
+        Shell::Fish => fish(bin_name, spec),
+        Shell::Nushell => nushell(bin_name, spec),
+    }
+}
+
+/// Answer a request for completion candidates from a completion script.
+///
+/// If the [`COMPLETE_ENV`] environment variable is set, it names the argument to complete, and the
+/// first command line argument is the word being completed. The candidates from
+/// [`OnlyArgs::complete`] are printed one per line, and the application exits. Otherwise, this does
+/// nothing.
+///
+/// Applications without a [`Complete::Callback`] argument in [`OnlyArgs::SPEC`] never answer, so
+/// the environment variable does not change how they run.
+///
+/// [`parse`](crate::parse) calls this first. Applications that read their arguments some other way
+/// can call it before parsing.
+pub fn respond<T: OnlyArgs>() {
+    if !T::SPEC.iter().any(|arg| arg.complete == Complete::Callback) {
+        return;
+    }
+
+    if let Some(name) = env::var_os(COMPLETE_ENV) {
+        let word = env::args_os().nth(1).unwrap_or_default();
+        let candidates = T::complete(&name.to_string_lossy(), &word.to_string_lossy());
+
+        let mut stdout = std::io::stdout().lock();
+        for candidate in candidates {
+            let _ = writeln!(stdout, "{candidate}");
+        }
+        let _ = stdout.flush();
+
+        std::process::exit(0);
+    }
+}
+
+// The completion for an argument value, with `Complete::Default` resolved for paths.
+fn hint(arg: &ArgSpec) -> Complete {
+    match (arg.complete, arg.kind) {
+        (
+            Complete::Default,
+            ArgKind::Option(ValueType::Path) | ArgKind::Positional(ValueType::Path),
+        ) => Complete::File,
+        (complete, _) => complete,
+    }
+}
+
+fn zsh(bin_name: &str, spec: &[ArgSpec]) -> String {
+    let function = format!(
+        "_{}",
+        bin_name.replace(|ch: char| !ch.is_ascii_alphanumeric(), "_")
+    );
+
+    let mut script = format!("#compdef {bin_name}\n\n{function}() {{\n  _arguments -s -S");
+    for arg in spec {
+        let help = zsh_escape(arg.help.lines().next().unwrap_or_default());
+        let value = match arg.kind {
+            ArgKind::Flag => String::new(),
+            ArgKind::Option(_) | ArgKind::Positional(_) => format!(
+                ":{}:{}",
+                zsh_escape(arg.value_name.unwrap_or_default()),
+                zsh_action(arg, bin_name),
+            ),
+        };
+
+        let line = match (arg.kind, arg.short) {
+            (ArgKind::Positional(_), _) => {
+                let repeat = if arg.multiple { "*" } else { "" };
+                format!("'{repeat}{value}'")
+            }
+            (_, _) if arg.multiple => match arg.short {
+                Some(ch) => format!("'*'{{-{ch},--{}}}'[{help}]{value}'", arg.name),
+                None => format!("'*--{}[{help}]{value}'", arg.name),
+            },
+            (_, Some(ch)) => format!(
+                "'(-{ch} --{name})'{{-{ch},--{name}}}'[{help}]{value}'",
+                name = arg.name,
+            ),
+            (_, None) => format!("'--{}[{help}]{value}'", arg.name),
+        };
+        write!(script, " \\\n    {line}").unwrap();
+    }
+    write!(script, "\n}}\n\n{function} \"$@\"\n").unwrap();
+
+    script
+}
+
+fn fish(bin_name: &str, spec: &[ArgSpec]) -> String {
+    let command = format!("complete -c {}", fish_quote(bin_name));
+
+    // Fish completes file names for positional arguments unless it is told not to.
+    let positional = spec
+        .iter()
+        .find(|arg| matches!(arg.kind, ArgKind::Positional(_)));
+    let mut script = match positional.map(|arg| (hint(arg), fish_candidates(arg, bin_name))) {
+        Some((_, Some(candidates))) => format!("{command} -f -a {candidates}\n"),
+        Some((Complete::File, None)) => format!("{command} -F\n"),
+        Some(_) => String::new(),
+        None => format!("{command} -f\n"),
+    };
+
+    for arg in spec {
+        let value = match (arg.kind, hint(arg)) {
+            (ArgKind::Flag, _) => String::new(),
+            (ArgKind::Positional(_), _) => continue,
+            (ArgKind::Option(_), Complete::File) => " -r -F".to_string(),
+            (ArgKind::Option(_), _) => match fish_candidates(arg, bin_name) {
+                Some(candidates) => format!(" -x -a {candidates}"),
+                None => " -x".to_string(),
+            },
+        };
+
+        script.push_str(&command);
+        if let Some(ch) = arg.short {
+            write!(script, " -s {}", fish_quote(&ch.to_string())).unwrap();
+        }
+        write!(script, " -l {}{value}", fish_quote(arg.name)).unwrap();
+        if let Some(help) = arg.help.lines().next().filter(|help| !help.is_empty()) {
+            write!(script, " -d {}", fish_quote(help)).unwrap();
+        }
+        script.push('\n');
+    }
+
+    script
+}
+
+// The quoted command substitution that lists candidates for an argument, if fish needs one.
+fn fish_candidates(arg: &ArgSpec, bin_name: &str) -> Option<String> {
+    let candidates = match hint(arg) {
+        Complete::Default | Complete::File => return None,
+        Complete::Dir => "(__fish_complete_directories)".to_string(),
+        Complete::Hostname => "(__fish_print_hostnames)".to_string(),
+        Complete::Callback => format!(
+            "(env {COMPLETE_ENV}={} {} (commandline -ct))",
+            fish_quote(arg.name),
+            fish_quote(bin_name),
+        ),
+    };
+
+    Some(fish_quote(&candidates))
+}
+
+// Quote a word for fish.
+fn fish_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn nushell(bin_name: &str, spec: &[ArgSpec]) -> String {
+    let bin_name = bin_name.replace('\\', r"\\").replace('"', r#"\""#);
+
+    let mut script = String::new();
+    // Custom completers for arguments that ask the binary for candidates.
+    for arg in spec.iter().filter(|&arg| hint(arg) == Complete::Callback) {
+        writeln!(
+            script,
+            "def \"nu-complete {bin_name} {name}\" [] {{\n  \
+            with-env {{ {COMPLETE_ENV}: \"{name}\" }} {{ ^\"{bin_name}\" \"\" }} | lines\n}}\n",
+            name = arg.name,
+        )
+        .unwrap();
+    }
+
+    writeln!(script, "export extern \"{bin_name}\" [").unwrap();
+    for arg in spec {
+        let mut param = match arg.kind {
+            ArgKind::Positional(_) if arg.multiple => format!("...{}", arg.name.replace('-', "_")),
+            ArgKind::Positional(_) if arg.required => arg.name.replace('-', "_"),
+            ArgKind::Positional(_) => format!("{}?", arg.name.replace('-', "_")),
+            ArgKind::Flag | ArgKind::Option(_) => format!("--{}", arg.name),
+        };
+        if let Some(ch) = arg.short {
+            write!(param, "(-{ch})").unwrap();
+        }
+        if let ArgKind::Option(ty) | ArgKind::Positional(ty) = arg.kind {
+            match hint(arg) {
+                Complete::File => param.push_str(": path"),
+                Complete::Dir => param.push_str(": directory"),
+                Complete::Callback => write!(
+                    param,
+                    ": {}@\"nu-complete {bin_name} {}\"",
+                    nushell_type(ty),
+                    arg.name,
+                )
+                .unwrap(),
+                Complete::Default | Complete::Hostname => {
+                    write!(param, ": {}", nushell_type(ty)).unwrap();
+                }
+            }
+        }
+
+        match arg.help.lines().next().filter(|help| !help.is_empty()) {
+            Some(help) => writeln!(script, "  {param:<24} # {help}").unwrap(),
+            None => writeln!(script, "  {param}").unwrap(),
+        }
+    }
+    script.push_str("]\n");
+
+    script
+}
+
+// The Nushell type for a value type.
+fn nushell_type(ty: ValueType) -> &'static str {
+    match ty {
+        ValueType::Float => "float",
+        ValueType::Integer => "int",
+        ValueType::OsString | ValueType::String => "string",
+        ValueType::Path => "path",
+    }
+}
+
+// The zsh completion action for an argument value.
+fn zsh_action(arg: &ArgSpec, bin_name: &str) -> String {
+    match hint(arg) {
+        Complete::Default => String::new(),
+        Complete::File => "_files".to_string(),
+        Complete::Dir => "_files -/".to_string(),
+        Complete::Hostname => "_hosts".to_string(),
+        Complete::Callback => format!(
+            r#"{{compadd -- ${{(f)"$({COMPLETE_ENV}={} {} "$PREFIX")"}}}}"#,
+            arg.name,
+            bin_name.replace('\'', r"'\''"),
+        ),
+    }
+}
+
+// Escape text for a single-quoted `_arguments` spec.
+fn zsh_escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, ch| {
+        match ch {
+            '\'' => escaped.push_str(r"'\''"),
+            '[' | ']' | ':' | '\\' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+        escaped
+    })
+}
@@ -0,0 +1,188 @@
+//! Configuration file support.
+//!
+//! A configuration file provides values for any options that were not supplied on the command
+//! line. The keys in the file mirror the field names of the argument struct, and values are stored
+//! as [`OsString`] so they can be parsed with the same [`ArgExt`] methods used for command line
+//! arguments.
+//!
+//! The following formats are available with cargo features:
+//!
+//! - `toml`: A flat [TOML](https://toml.io/) document with `key = value` pairs. Tables are not
+//!   supported.
+//! - `json`: A flat [JSON](https://www.json.org/) object. Nested objects are not supported, and
+//!   `null` values are treated as missing keys.
+//!
+//! [`ArgExt`]: crate::traits::ArgExt
+
+use crate::source::{Key, Source};
+use crate::CliError;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::Path;
+
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "toml")]
+mod toml;
+
+/// Argument values loaded from a configuration file.
+#[derive(Debug, Default)]
+pub struct Config {
+    values: HashMap<String, Vec<OsString>>,
+}
+
+impl Config {
+    /// Load a configuration file from the given path.
+    ///
+    /// Files with a `.json` extension are parsed as JSON, and all others are parsed as TOML. A
+    /// missing file is not an error; it produces an empty `Config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the file exists but cannot be read or parsed.
+    pub fn load<P>(path: P) -> Result<Self, CliError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::read(path.as_ref(), true)
+    }
+
+    /// Open a configuration file that must exist, e.g. one that was named on the command line.
+    ///
+    /// This is the same as [`Config::load`], except that a missing file is an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the file cannot be read or parsed. Parse errors name the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::config::Config;
+    ///
+    /// let path = std::env::temp_dir().join("onlyargs-broken-config.toml");
+    /// std::fs::write(&path, "width = \n")?;
+    ///
+    /// let err = Config::open(&path).unwrap_err();
+    /// assert!(err.to_string().contains("onlyargs-broken-config.toml"));
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn open<P>(path: P) -> Result<Self, CliError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::read(path.as_ref(), false)
+    }
+
+    fn read(path: &Path, missing_ok: bool) -> Result<Self, CliError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if missing_ok && err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(err) => {
+                return Err(CliError::ConfigIoError {
+                    path: path.to_path_buf(),
+                    source: err.into(),
+                })
+            }
+        };
+
+        match path.extension() {
+            #[cfg(feature = "json")]
+            Some(ext) if ext == "json" => Self::from_json(&text),
+            #[cfg(feature = "toml")]
+            _ => Self::from_toml(&text),
+            #[cfg(not(feature = "toml"))]
+            _ => Err(CliError::ParseConfigError {
+                path: None,
+                message: "unsupported config file format".to_string(),
+            }),
+        }
+        .map_err(|err| err.in_file(path))
+    }
+
+    /// Parse a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the document is not valid TOML or uses unsupported features like tables.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::config::Config;
+    ///
+    /// let config = Config::from_toml(r#"
+    ///     username = "Alice"
+    ///     width = 42 # Comments are allowed.
+    ///     numbers = [1, 2, 3]
+    /// "#)?;
+    ///
+    /// assert_eq!(config.get("username"), Some("Alice".into()));
+    /// assert_eq!(config.get("width"), Some("42".into()));
+    /// assert_eq!(config.get_all("numbers"), ["1", "2", "3"]);
+    /// assert_eq!(config.get("output"), None);
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, CliError> {
+        toml::parse(text).map(|values| Self { values })
+    }
+
+    /// Parse a JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the document is not a valid JSON object or uses unsupported features like
+    /// nested objects.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::config::Config;
+    ///
+    /// let config = Config::from_json(r#"{
+    ///     "username": "Alice",
+    ///     "width": 42,
+    ///     "numbers": [1, 2, 3],
+    ///     "output": null
+    /// }"#)?;
+    ///
+    /// assert_eq!(config.get("username"), Some("Alice".into()));
+    /// assert_eq!(config.get("width"), Some("42".into()));
+    /// assert_eq!(config.get_all("numbers"), ["1", "2", "3"]);
+    /// assert_eq!(config.get("output"), None);
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_json(text: &str) -> Result<Self, CliError> {
+        json::parse(text).map(|values| Self { values })
+    }
+
+    /// Get the value for a key.
+    ///
+    /// If the key has multiple values, the last one is returned.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<OsString> {
+        self.values
+            .get(key)
+            .and_then(|values| values.last())
+            .cloned()
+    }
+
+    /// Get all values for a key.
+    ///
+    /// Returns an empty slice if the key does not exist.
+    #[must_use]
+    pub fn get_all(&self, key: &str) -> &[OsString] {
+        self.values.get(key).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Provides values for arguments by field name.
+impl Source for Config {
+    fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+        self.get_all(key.name).to_vec()
+    }
+}
@@ -0,0 +1,187 @@
+//! A tiny parser for flat JSON documents.
+
+use crate::CliError;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::iter::Peekable;
+use std::str::Chars;
+
+pub(crate) fn parse(text: &str) -> Result<HashMap<String, Vec<OsString>>, CliError> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+        line: 1,
+    };
+    let mut values = HashMap::new();
+
+    parser.skip_whitespace();
+    parser.expect('{')?;
+    parser.skip_whitespace();
+
+    if parser.chars.next_if_eq(&'}').is_none() {
+        loop {
+            parser.skip_whitespace();
+            let key = parser.parse_string()?;
+            parser.skip_whitespace();
+            parser.expect(':')?;
+            parser.skip_whitespace();
+            let value = parser.parse_value()?;
+
+            // `null` is the same as a missing key.
+            if let Some(value) = value {
+                if values.insert(key.clone(), value).is_some() {
+                    return Err(parser.error(&format!("duplicate key `{key}`")));
+                }
+            }
+
+            parser.skip_whitespace();
+            match parser.chars.next() {
+                Some(',') => (),
+                Some('}') => break,
+                _ => return Err(parser.error("expected `,` or `}` in object")),
+            }
+        }
+    }
+
+    parser.skip_whitespace();
+    match parser.chars.next() {
+        None => Ok(values),
+        Some(_) => Err(parser.error("unexpected trailing characters")),
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> CliError {
+        CliError::ParseConfigError {
+            path: None,
+            message: format!("line {line}: {msg}", line = self.line),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.chars.next_if(char::is_ascii_whitespace) {
+            if ch == '\n' {
+                self.line += 1;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), CliError> {
+        match self.chars.next() {
+            Some(ch) if ch == expected => Ok(()),
+            _ => Err(self.error(&format!("expected `{expected}`"))),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Option<Vec<OsString>>, CliError> {
+        match self.chars.peek() {
+            Some('[') => {
+                self.chars.next();
+                self.skip_whitespace();
+
+                let mut values = vec![];
+                if self.chars.next_if_eq(&']').is_none() {
+                    loop {
+                        self.skip_whitespace();
+                        if let Some(value) = self.parse_scalar()? {
+                            values.push(value);
+                        }
+
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => (),
+                            Some(']') => break,
+                            _ => return Err(self.error("expected `,` or `]` in array")),
+                        }
+                    }
+                }
+
+                Ok(Some(values))
+            }
+            _ => Ok(self.parse_scalar()?.map(|value| vec![value])),
+        }
+    }
+
+    fn parse_scalar(&mut self) -> Result<Option<OsString>, CliError> {
+        match self.chars.peek() {
+            Some('"') => self.parse_string().map(|string| Some(string.into())),
+            Some('[') => Err(self.error("nested arrays are not supported")),
+            Some('{') => Err(self.error("nested objects are not supported")),
+            _ => {
+                // Literals and numbers.
+                let mut value = String::new();
+                while let Some(ch) = self
+                    .chars
+                    .next_if(|&ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'))
+                {
+                    value.push(ch);
+                }
+
+                match value.as_str() {
+                    "" => Err(self.error("expected a value")),
+                    "null" => Ok(None),
+                    _ => Ok(Some(value.into())),
+                }
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, CliError> {
+        self.expect('"')?;
+
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => string.push(self.parse_escape()?),
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(ch) => string.push(ch),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, CliError> {
+        let ch = match self.chars.next() {
+            Some('b') => '\u{8}',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('f') => '\u{c}',
+            Some('r') => '\r',
+            Some('"') => '"',
+            Some('/') => '/',
+            Some('\\') => '\\',
+            Some('u') => {
+                let high = self.parse_hex()?;
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    // Surrogate pairs are used for characters outside of the BMP.
+                    if self.chars.next() != Some('\\') || self.chars.next() != Some('u') {
+                        return Err(self.error("invalid unicode escape"));
+                    }
+                    let low = self.parse_hex()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(self.error("invalid unicode escape"));
+                    }
+
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+
+                char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            _ => return Err(self.error("invalid escape sequence")),
+        };
+
+        Ok(ch)
+    }
+
+    fn parse_hex(&mut self) -> Result<u32, CliError> {
+        let hex = self.chars.by_ref().take(4).collect::<String>();
+
+        u32::from_str_radix(&hex, 16).map_err(|_| self.error("invalid unicode escape"))
+    }
+}
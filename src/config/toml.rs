@@ -0,0 +1,208 @@
+//! A tiny parser for flat TOML documents.
+
+use crate::CliError;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::iter::Peekable;
+use std::str::Chars;
+
+pub(crate) fn parse(text: &str) -> Result<HashMap<String, Vec<OsString>>, CliError> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+        line: 1,
+    };
+    let mut values = HashMap::new();
+
+    loop {
+        parser.skip_whitespace(true);
+        match parser.chars.peek() {
+            None => break,
+            Some('[') => return Err(parser.error("tables are not supported")),
+            Some(_) => (),
+        }
+
+        let key = parser.parse_key()?;
+        parser.skip_whitespace(false);
+        parser.expect('=')?;
+        parser.skip_whitespace(false);
+        let value = parser.parse_value()?;
+        parser.skip_whitespace(false);
+        match parser.chars.next() {
+            None | Some('\n') => parser.line += 1,
+            Some(_) => return Err(parser.error("expected a newline after value")),
+        }
+
+        if values.insert(key.clone(), value).is_some() {
+            return Err(CliError::ParseConfigError {
+                path: None,
+                message: format!("line {line}: duplicate key `{key}`", line = parser.line - 1),
+            });
+        }
+    }
+
+    Ok(values)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> CliError {
+        CliError::ParseConfigError {
+            path: None,
+            message: format!("line {line}: {msg}", line = self.line),
+        }
+    }
+
+    /// Skip spaces, tabs, and comments. Newlines are only skipped if `newlines` is true.
+    fn skip_whitespace(&mut self, newlines: bool) {
+        while let Some(&ch) = self.chars.peek() {
+            match ch {
+                ' ' | '\t' | '\r' => (),
+                '\n' if newlines => self.line += 1,
+                '#' => {
+                    while self.chars.next_if(|&ch| ch != '\n').is_some() {}
+                    continue;
+                }
+                _ => break,
+            }
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), CliError> {
+        match self.chars.next() {
+            Some(ch) if ch == expected => Ok(()),
+            _ => Err(self.error(&format!("expected `{expected}`"))),
+        }
+    }
+
+    fn parse_key(&mut self) -> Result<String, CliError> {
+        match self.chars.peek() {
+            Some('"') => self.parse_basic_string(),
+            Some('\'') => self.parse_literal_string(),
+            _ => {
+                let mut key = String::new();
+                while let Some(ch) = self
+                    .chars
+                    .next_if(|&ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+                {
+                    key.push(ch);
+                }
+
+                if key.is_empty() {
+                    Err(self.error("expected a key"))
+                } else {
+                    Ok(key)
+                }
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Vec<OsString>, CliError> {
+        match self.chars.peek() {
+            Some('[') => {
+                self.chars.next();
+                let mut values = vec![];
+
+                loop {
+                    self.skip_whitespace(true);
+                    if self.chars.next_if_eq(&']').is_some() {
+                        break;
+                    }
+
+                    values.push(self.parse_scalar()?);
+
+                    self.skip_whitespace(true);
+                    match self.chars.next() {
+                        Some(',') => (),
+                        Some(']') => break,
+                        _ => return Err(self.error("expected `,` or `]` in array")),
+                    }
+                }
+
+                Ok(values)
+            }
+            _ => Ok(vec![self.parse_scalar()?]),
+        }
+    }
+
+    fn parse_scalar(&mut self) -> Result<OsString, CliError> {
+        match self.chars.peek() {
+            Some('"') => self.parse_basic_string().map(OsString::from),
+            Some('\'') => self.parse_literal_string().map(OsString::from),
+            Some('[') => Err(self.error("nested arrays are not supported")),
+            Some('{') => Err(self.error("inline tables are not supported")),
+            _ => {
+                // Booleans and numbers. Underscores are only used as digit separators.
+                let mut value = String::new();
+                while let Some(ch) = self.chars.next_if(|&ch| {
+                    ch.is_ascii_alphanumeric() || matches!(ch, '_' | '+' | '-' | '.')
+                }) {
+                    if ch != '_' {
+                        value.push(ch);
+                    }
+                }
+
+                if value.is_empty() {
+                    Err(self.error("expected a value"))
+                } else {
+                    Ok(value.into())
+                }
+            }
+        }
+    }
+
+    fn parse_literal_string(&mut self) -> Result<String, CliError> {
+        self.chars.next();
+
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some('\'') => return Ok(string),
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(ch) => string.push(ch),
+            }
+        }
+    }
+
+    fn parse_basic_string(&mut self) -> Result<String, CliError> {
+        self.chars.next();
+
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => string.push(self.parse_escape()?),
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(ch) => string.push(ch),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, CliError> {
+        let ch = match self.chars.next() {
+            Some('b') => '\u{8}',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('f') => '\u{c}',
+            Some('r') => '\r',
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some(size @ ('u' | 'U')) => {
+                let len = if size == 'u' { 4 } else { 8 };
+                let hex = self.chars.by_ref().take(len).collect::<String>();
+
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            _ => return Err(self.error("invalid escape sequence")),
+        };
+
+        Ok(ch)
+    }
+}
@@ -0,0 +1,172 @@
+//! `.env` file support for environment variable fallbacks.
+//!
+//! A `.env` file contains `KEY=value` pairs, one per line. Variables in the process environment
+//! always take precedence over the file, so the file only provides values that are not already set.
+//!
+//! The supported syntax is intentionally small:
+//!
+//! - Blank lines and lines starting with `#` are ignored.
+//! - An optional `export` prefix is allowed before the key.
+//! - Values may be unquoted, `'single quoted'` (taken literally), or `"double quoted"` (supporting
+//!   `\n`, `\t`, `\"`, and `\\` escapes).
+//! - Unquoted values are trimmed, and a `#` preceded by whitespace starts a comment.
+
+use crate::source::{Key, Origin, Source};
+use crate::CliError;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Environment variables loaded from a `.env` file.
+#[derive(Debug, Default)]
+pub struct DotEnv {
+    vars: HashMap<String, String>,
+}
+
+impl DotEnv {
+    /// Load a `.env` file from the given path.
+    ///
+    /// A missing file is not an error; it produces an empty `DotEnv`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the file exists but cannot be read or parsed. Parse errors name the file.
+    pub fn load<P>(path: P) -> Result<Self, CliError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|err| err.in_file(path)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(CliError::ConfigIoError {
+                path: path.to_path_buf(),
+                source: err.into(),
+            }),
+        }
+    }
+
+    /// Parse the contents of a `.env` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a line is not a valid `KEY=value` pair.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::dotenv::DotEnv;
+    ///
+    /// let dotenv = DotEnv::parse(r#"
+    ///     export MYAPP_USERNAME="Alice"
+    ///     MYAPP_WIDTH=42 # Comments are allowed.
+    ///     MYAPP_TAG=a#b
+    /// "#)?;
+    ///
+    /// assert_eq!(dotenv.get("MYAPP_USERNAME"), Some("Alice"));
+    /// assert_eq!(dotenv.get("MYAPP_WIDTH"), Some("42"));
+    /// assert_eq!(dotenv.get("MYAPP_TAG"), Some("a#b"));
+    ///
+    /// let dotenv = DotEnv::parse("MYAPP_COLOR=never\t# After any whitespace.")?;
+    /// assert_eq!(dotenv.get("MYAPP_COLOR"), Some("never"));
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, CliError> {
+        let mut vars = HashMap::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let error = |msg| CliError::ParseConfigError {
+                path: None,
+                message: format!("line {}: {msg}", index + 1),
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+
+            let (key, value) = line.split_once('=').ok_or_else(|| error("expected `=`"))?;
+            let key = key.trim();
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return Err(error("invalid key"));
+            }
+
+            let value = value.trim_start();
+            let value = if let Some(value) = value.strip_prefix('\'') {
+                let (value, _) = value
+                    .split_once('\'')
+                    .ok_or_else(|| error("unterminated string"))?;
+
+                value.to_string()
+            } else if let Some(value) = value.strip_prefix('"') {
+                let mut string = String::new();
+                let mut chars = value.chars();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => string.push('\n'),
+                            Some('t') => string.push('\t'),
+                            Some(ch @ ('"' | '\\')) => string.push(ch),
+                            _ => return Err(error("invalid escape sequence")),
+                        },
+                        Some(ch) => string.push(ch),
+                        None => return Err(error("unterminated string")),
+                    }
+                }
+
+                string
+            } else {
+                let end = value
+                    .char_indices()
+                    .zip(value.chars().skip(1))
+                    .find(|((_, ch), next)| ch.is_whitespace() && *next == '#')
+                    .map_or(value.len(), |((index, _), _)| index);
+
+                value[..end].trim_end().to_string()
+            };
+
+            vars.insert(key.to_string(), value);
+        }
+
+        Ok(Self { vars })
+    }
+
+    /// Get the value of a variable defined in the file.
+    ///
+    /// This does not consult the process environment. See [`DotEnv::var_os`].
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(String::as_str)
+    }
+
+    /// Get the value of an environment variable, falling back to the file.
+    ///
+    /// This is a drop-in replacement for [`std::env::var_os`].
+    #[must_use]
+    pub fn var_os(&self, key: &str) -> Option<OsString> {
+        std::env::var_os(key).or_else(|| self.vars.get(key).map(OsString::from))
+    }
+}
+
+/// Provides values for arguments with an environment variable name, from the file only.
+///
+/// Add [`Env`](crate::source::Env) to the chain first so the process environment takes precedence.
+impl Source for DotEnv {
+    fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+        key.env
+            .and_then(|env| self.vars.get(env))
+            .map(OsString::from)
+            .into_iter()
+            .collect()
+    }
+
+    fn describe(&self, key: &Key<'_>) -> String {
+        key.env.unwrap_or(key.name).to_string()
+    }
+
+    fn origin(&self, _key: &Key<'_>) -> Origin {
+        Origin::Env
+    }
+}
@@ -0,0 +1,423 @@
+//! Man pages, Markdown references, JSON specs, and the hidden `--generate` argument.
+//!
+//! Man pages, Markdown references, and JSON specs are generated from the argument descriptions in
+//! [`OnlyArgs::SPEC`], like [completion scripts](crate::completion).
+//!
+//! Applications can let packagers produce these artifacts from the shipped binary. When
+//! [`OnlyArgs::generate_arg`] returns `true`, [`parse`](crate::parse) handles a hidden `--generate`
+//! argument before parsing. It must be the first argument:
+//!
+//! - `--generate completions <SHELL>`: Print a completion script. See
+//!   [`Shell`](crate::completion::Shell) for the shell names.
+//! - `--generate man`: Print a man page.
+//! - `--generate markdown`: Print a Markdown reference.
+//! - `--generate json`: Print a JSON spec.
+//!
+//! `onlyargs_derive` enables it with the `#[generate]` attribute on the argument struct.
+
+use crate::traits::ArgExt as _;
+use crate::{ArgKind, ArgSpec, CliError, Complete, OnlyArgs, ValueType};
+use std::env;
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+#[derive(Clone, Copy)]
+enum Artifact {
+    Completions,
+    Json,
+    Man,
+    Markdown,
+}
+
+/// The version of the JSON spec format produced by [`json`].
+///
+/// It only changes when existing fields are removed or change their meaning. New fields can be
+/// added without changing it.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Generate a man page for the binary named `bin_name`.
+///
+/// `version` is shown in the page footer, and `usage` in the synopsis. Usage lines can be
+/// separated by newlines.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::generate::man;
+/// use onlyargs::{ArgKind, ArgSpec};
+///
+/// let spec = [ArgSpec::new("verbose", ArgKind::Flag, "Enable verbose output.").with_short('v')];
+///
+/// let page = man("mytool", "mytool v1.0.0", "mytool [flags]", &spec);
+///
+/// assert!(page.starts_with(".TH \"MYTOOL\" \"1\" \"\" \"mytool v1.0.0\"\n"));
+/// assert!(page.contains(".TP\n\\fB\\-v\\fR, \\fB\\-\\-verbose\\fR\nEnable verbose output.\n"));
+/// ```
+#[must_use]
+pub fn man(bin_name: &str, version: &str, usage: &str, spec: &[ArgSpec]) -> String {
+    let mut page = format!(
+        ".TH \"{}\" \"1\" \"\" \"{}\"\n.SH NAME\n{}\n",
+        roff_escape(&bin_name.to_uppercase()),
+        roff_escape(version.trim()),
+        roff_escape(bin_name),
+    );
+
+    if !usage.is_empty() {
+        page.push_str(".SH SYNOPSIS\n.nf\n");
+        for line in usage.lines() {
+            writeln!(page, "{}", roff_line(line.trim())).unwrap();
+        }
+        page.push_str(".fi\n");
+    }
+
+    for (heading, positional) in [("OPTIONS", false), ("ARGUMENTS", true)] {
+        let mut args = spec
+            .iter()
+            .filter(|&arg| matches!(arg.kind, ArgKind::Positional(_)) == positional)
+            .peekable();
+        if args.peek().is_none() {
+            continue;
+        }
+
+        writeln!(page, ".SH {heading}").unwrap();
+        for arg in args {
+            let value = arg.value_name.map_or_else(String::new, |value_name| {
+                format!("\\fI{}\\fR", roff_escape(value_name))
+            });
+            let label = match (arg.kind, arg.short) {
+                (ArgKind::Positional(_), _) if arg.multiple => format!("{value}..."),
+                (ArgKind::Positional(_), _) => value,
+                (_, short) => {
+                    let short = short.map_or_else(String::new, |ch| {
+                        format!("\\fB\\-{}\\fR, ", roff_escape(&ch.to_string()))
+                    });
+                    let long = format!("\\fB\\-\\-{}\\fR", roff_escape(arg.name));
+
+                    match value.as_str() {
+                        "" => format!("{short}{long}"),
+                        value => format!("{short}{long} {value}"),
+                    }
+                }
+            };
+
+            writeln!(page, ".TP\n{label}").unwrap();
+            for line in arg.help.lines() {
+                writeln!(page, "{}", roff_line(line.trim())).unwrap();
+            }
+        }
+    }
+
+    page
+}
+
+/// Generate a Markdown reference for the binary named `bin_name`.
+///
+/// `version` is shown below the title, and `usage` in a code block. Usage lines can be separated
+/// by newlines.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::generate::markdown;
+/// use onlyargs::{ArgKind, ArgSpec, ValueType};
+///
+/// let spec = [
+///     ArgSpec::new("output", ArgKind::Option(ValueType::Path), "Write output to a file.")
+///         .with_short('o')
+///         .with_value_name("PATH"),
+/// ];
+///
+/// let reference = markdown("mytool", "mytool v1.0.0", "mytool [options]", &spec);
+///
+/// assert!(reference.starts_with("# mytool\n\nmytool v1.0.0\n\n## Usage\n"));
+/// assert!(reference.contains("- `-o`, `--output <PATH>`: Write output to a file.\n"));
+/// ```
+#[must_use]
+pub fn markdown(bin_name: &str, version: &str, usage: &str, spec: &[ArgSpec]) -> String {
+    let mut reference = format!("# {bin_name}\n\n{}\n", version.trim());
+
+    if !usage.is_empty() {
+        reference.push_str("\n## Usage\n\n```text\n");
+        for line in usage.lines() {
+            writeln!(reference, "{}", line.trim()).unwrap();
+        }
+        reference.push_str("```\n");
+    }
+
+    for (heading, positional) in [("Options", false), ("Arguments", true)] {
+        let mut args = spec
+            .iter()
+            .filter(|&arg| matches!(arg.kind, ArgKind::Positional(_)) == positional)
+            .peekable();
+        if args.peek().is_none() {
+            continue;
+        }
+
+        write!(reference, "\n## {heading}\n\n").unwrap();
+        for arg in args {
+            let value = arg
+                .value_name
+                .map_or_else(String::new, |value_name| format!(" <{value_name}>"));
+            let label = match (arg.kind, arg.short) {
+                (ArgKind::Positional(_), _) if arg.multiple => {
+                    format!("`{}...`", value.trim_start())
+                }
+                (ArgKind::Positional(_), _) => format!("`{}`", value.trim_start()),
+                (_, Some(ch)) => format!("`-{ch}`, `--{}{value}`", arg.name),
+                (_, None) => format!("`--{}{value}`", arg.name),
+            };
+            let help = arg
+                .help
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            match help.as_str() {
+                "" => writeln!(reference, "- {label}").unwrap(),
+                help => writeln!(reference, "- {label}: {help}").unwrap(),
+            }
+        }
+    }
+
+    reference
+}
+
+/// Generate a JSON spec for the binary named `bin_name`, for tools that launch or document it.
+///
+/// The spec is an object with these fields:
+///
+/// - `schema`: The [format version](JSON_SCHEMA_VERSION).
+/// - `name`: The binary name.
+/// - `version`: The version message.
+/// - `usage`: The usage lines, in an array.
+/// - `arguments`: The arguments from `spec`, in an array.
+///
+/// Each argument is an object with the fields of [`ArgSpec`]. `short`, `type`, `value_name`, and
+/// `default` are `null` when they are not set. `kind` is one of `flag`, `option`, or `positional`.
+/// `type` is one of `float`, `integer`, `os_string`, `path`, or `string`. `complete` is one of
+/// `default`, `file`, `dir`, `hostname`, or `callback`.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::generate::json;
+/// use onlyargs::{ArgKind, ArgSpec, ValueType};
+///
+/// let spec = [ArgSpec::new(
+///     "width",
+///     ArgKind::Option(ValueType::Integer),
+///     "Set the \"width\".\nWider is better.",
+/// )
+/// .with_short('w')
+/// .with_value_name("INTEGER")
+/// .with_default("80")];
+///
+/// let spec = json("mytool", "mytool v1.0.0", "mytool [options]", &spec);
+/// let spec: serde_json::Value = serde_json::from_str(&spec)?;
+///
+/// assert_eq!(spec["usage"], serde_json::json!(["mytool [options]"]));
+/// assert_eq!(
+///     spec["arguments"][0],
+///     serde_json::json!({
+///         "name": "width",
+///         "short": "w",
+///         "kind": "option",
+///         "type": "integer",
+///         "value_name": "INTEGER",
+///         "multiple": false,
+///         "required": false,
+///         "default": "80",
+///         "help": "Set the \"width\".\nWider is better.",
+///         "complete": "default",
+///     }),
+/// );
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[must_use]
+pub fn json(bin_name: &str, version: &str, usage: &str, spec: &[ArgSpec]) -> String {
+    let usage = usage
+        .lines()
+        .map(|line| json_string(line.trim()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut output = format!(
+        "{{\n  \"schema\": {JSON_SCHEMA_VERSION},\n  \"name\": {},\n  \"version\": {},\n  \
+        \"usage\": [{usage}],\n  \"arguments\": [",
+        json_string(bin_name),
+        json_string(version.trim()),
+    );
+
+    for (index, arg) in spec.iter().enumerate() {
+        let (kind, ty) = match arg.kind {
+            ArgKind::Flag => ("flag", None),
+            ArgKind::Option(ty) => ("option", Some(ty)),
+            ArgKind::Positional(ty) => ("positional", Some(ty)),
+        };
+        let ty = ty.map(|ty| match ty {
+            ValueType::Float => "float",
+            ValueType::Integer => "integer",
+            ValueType::OsString => "os_string",
+            ValueType::Path => "path",
+            ValueType::String => "string",
+        });
+        let complete = match arg.complete {
+            Complete::Default => "default",
+            Complete::File => "file",
+            Complete::Dir => "dir",
+            Complete::Hostname => "hostname",
+            Complete::Callback => "callback",
+        };
+
+        let fields = [
+            ("name", json_string(arg.name)),
+            ("short", json_option(arg.short.map(String::from).as_deref())),
+            ("kind", json_string(kind)),
+            ("type", json_option(ty)),
+            ("value_name", json_option(arg.value_name)),
+            ("multiple", arg.multiple.to_string()),
+            ("required", arg.required.to_string()),
+            ("default", json_option(arg.default)),
+            ("help", json_string(arg.help)),
+            ("complete", json_string(complete)),
+        ];
+        let fields = fields
+            .iter()
+            .map(|(key, value)| format!("      \"{key}\": {value}"))
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        let separator = if index == 0 { "" } else { "," };
+        write!(output, "{separator}\n    {{\n{fields}\n    }}").unwrap();
+    }
+
+    if !spec.is_empty() {
+        output.push_str("\n  ");
+    }
+    output.push_str("]\n}\n");
+
+    output
+}
+
+/// Produce the artifact requested by a `--generate` argument.
+///
+/// Returns `None` if [`OnlyArgs::generate_arg`] returns `false`, or the first argument is not
+/// `--generate`.
+///
+/// # Errors
+///
+/// Returns `Err` if the artifact or shell name is missing or not known.
+///
+/// # Example
+///
+/// ```
+/// # use onlyargs::{CliError, OnlyArgs};
+/// # #[derive(Debug)]
+/// # struct Args;
+/// # impl OnlyArgs for Args {
+/// #     fn parse(_args: Vec<std::ffi::OsString>) -> Result<Self, CliError> {
+/// #         Ok(Self)
+/// #     }
+/// #     fn generate_arg() -> bool {
+/// #         true
+/// #     }
+/// # }
+/// use onlyargs::generate::artifact;
+///
+/// let script = artifact::<Args>(&["--generate".into(), "completions".into(), "zsh".into()])?;
+/// assert!(script.unwrap().starts_with("#compdef "));
+///
+/// assert!(artifact::<Args>(&["--verbose".into()])?.is_none());
+/// # Ok::<_, CliError>(())
+/// ```
+pub fn artifact<T: OnlyArgs>(args: &[OsString]) -> Result<Option<String>, CliError> {
+    let mut args = args.iter().map(OsString::as_os_str);
+    if !T::generate_arg() || args.next().map_or(true, |arg| arg != "--generate") {
+        return Ok(None);
+    }
+
+    let artifact = args.next().parse_enum(
+        "--generate",
+        &[
+            ("completions", Artifact::Completions),
+            ("json", Artifact::Json),
+            ("man", Artifact::Man),
+            ("markdown", Artifact::Markdown),
+        ],
+    )?;
+
+    Ok(Some(match artifact {
+        Artifact::Completions => T::completions(args.next().parse_str("--generate")?.parse()?),
+        Artifact::Json => T::spec_json(),
+        Artifact::Man => T::man_page(),
+        Artifact::Markdown => T::markdown(),
+    }))
+}
+
+/// Print the artifact requested by a `--generate` argument and exit.
+///
+/// This does nothing unless [`OnlyArgs::generate_arg`] returns `true` and the first command line
+/// argument is `--generate`. Errors are reported with [`OnlyArgs::exit_with_error`].
+///
+/// [`parse`](crate::parse) calls this first. Applications that read their arguments some other way
+/// can call it before parsing.
+pub fn respond<T: OnlyArgs>() {
+    if !T::generate_arg() {
+        return;
+    }
+
+    match artifact::<T>(&env::args_os().skip(1).take(3).collect::<Vec<_>>()) {
+        Ok(Some(output)) => {
+            let mut stdout = std::io::stdout().lock();
+            let _ = stdout.write_all(output.as_bytes());
+            let _ = stdout.flush();
+
+            std::process::exit(0);
+        }
+        Ok(None) => (),
+        Err(err) => T::exit_with_error(&err),
+    }
+}
+
+// Quote and escape a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            ch if ch.is_control() => write!(quoted, "\\u{:04x}", u32::from(ch)).unwrap(),
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+// A JSON string, or `null`.
+fn json_option(text: Option<&str>) -> String {
+    text.map_or_else(|| "null".to_string(), json_string)
+}
+
+// Escape text for roff.
+fn roff_escape(text: &str) -> String {
+    text.replace('\\', r"\e").replace('-', r"\-")
+}
+
+// Escape a line of text for roff, so it is not read as a request.
+fn roff_line(line: &str) -> String {
+    let line = roff_escape(line);
+
+    if line.starts_with('.') || line.starts_with('\'') {
+        format!("\\&{line}")
+    } else {
+        line
+    }
+}
@@ -0,0 +1,425 @@
+//! Help text formatting.
+//!
+//! Help text is wrapped to the width of the terminal when it is printed. Argument descriptions
+//! wrap with a hanging indent, so continuation lines stay aligned with the description column:
+//!
+//! ```text
+//! Options:
+//!   -w --width INTEGER  Set the width of the output. Long descriptions are wrapped
+//!                       to the terminal width.
+//! ```
+//!
+//! The width is read from the `COLUMNS` environment variable, and is [`DEFAULT_WIDTH`] when it is
+//! not set. No external programs are run to ask the terminal.
+//!
+//! Help is printed to `stdout` by default. See [`Output`].
+//!
+//! With the `pager` feature, help text that is taller than the terminal can be shown in a pager.
+//! See `help::page()`. This feature has a higher MSRV than the rest of the crate.
+//!
+//! Manual [`OnlyArgs`](crate::OnlyArgs) implementations can lay out their help text with
+//! [`HelpBuilder`], which aligns argument descriptions the same way as the derive macro.
+//!
+//! Set the `ONLYARGS_PLAIN_HELP` environment variable to print help text in a plain format instead,
+//! with one `name: description` pair per line. See [`plain`].
+
+use std::env;
+
+/// The width used when the terminal width cannot be detected.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Descriptions narrower than this are wrapped without a hanging indent.
+const MIN_DESCRIPTION_WIDTH: usize = 20;
+
+/// The height used by the pager when the terminal height cannot be detected.
+#[cfg(feature = "pager")]
+const DEFAULT_HEIGHT: usize = 24;
+
+/// Detect the width of the terminal in columns, from the `COLUMNS` environment variable.
+///
+/// Returns `None` if the variable is not set to a positive number.
+#[must_use]
+pub fn terminal_width() -> Option<usize> {
+    env_size("COLUMNS")
+}
+
+/// Detect the height of the terminal in lines, from the `LINES` environment variable.
+///
+/// Returns `None` if the variable is not set to a positive number.
+#[must_use]
+pub fn terminal_height() -> Option<usize> {
+    env_size("LINES")
+}
+
+fn env_size(var: &str) -> Option<usize> {
+    env::var(var)
+        .ok()
+        .and_then(|size| size.trim().parse().ok())
+        .filter(|&size| size > 0)
+}
+
+/// The stream that help and version messages are printed to.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Output {
+    /// Standard output. This is the convention for explicit `--help` and `--version` arguments, so
+    /// the output can be piped to other programs.
+    #[default]
+    Stdout,
+
+    /// Standard error.
+    Stderr,
+}
+
+/// Print help text to `output`.
+///
+/// If `pager` is `true`, `output` is [`Output::Stdout`], and the `pager` feature is enabled, the
+/// text is shown with `page()` when it does not fit in the terminal. Text printed to `stderr` is
+/// never paged, because the pager writes to `stdout`.
+///
+/// Errors are ignored, e.g. when `stdout` is piped to a program that exits early.
+pub fn print(text: &str, output: Output, pager: bool) {
+    use std::io::{self, Write as _};
+
+    #[cfg(feature = "pager")]
+    if pager && output == Output::Stdout && page(text) {
+        return;
+    }
+    #[cfg(not(feature = "pager"))]
+    let _ = pager;
+
+    let _ = match output {
+        Output::Stdout => writeln!(io::stdout(), "{text}"),
+        Output::Stderr => writeln!(io::stderr(), "{text}"),
+    };
+}
+
+/// Show help text in a pager if it is taller than the terminal.
+///
+/// The pager command is read from the `PAGER` environment variable and defaults to `less -R`. An
+/// empty `PAGER` disables the pager. Nothing is shown if `stdout` is not a terminal, the text fits
+/// in the terminal, or the pager cannot be started. Without `LINES`, the terminal is assumed to be
+/// 24 lines tall.
+///
+/// Returns `true` if the text was shown in the pager.
+#[cfg(feature = "pager")]
+#[must_use]
+pub fn page(text: &str) -> bool {
+    use std::io::Write as _;
+    use std::process::{Command, Stdio};
+
+    let height = terminal_height().unwrap_or(DEFAULT_HEIGHT);
+    if text.lines().count() < height || !crate::types::is_terminal(Output::Stdout) {
+        return false;
+    }
+
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less -R".to_string());
+    let words = crate::alias::split_words(&pager).unwrap_or_default();
+    let (program, args) = match words.split_first() {
+        Some(command) => command,
+        None => return false,
+    };
+
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes the pipe, which is not an error.
+        let _ = writeln!(stdin, "{text}");
+    }
+    let _ = child.wait();
+
+    true
+}
+
+/// Builds help text at runtime, with the same layout as the derive macro.
+///
+/// Arguments are added to sections, and each section aligns the descriptions of its arguments in
+/// one column. Descriptions with several lines are indented to that column. The text is not
+/// wrapped; [`OnlyArgs::help`](crate::OnlyArgs::help) wraps it when it is printed, or call [`wrap`]
+/// directly.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::help::HelpBuilder;
+///
+/// let help = HelpBuilder::new()
+///     .line("Usage: {bin_name} [flags] [options] [FILES...]")
+///     .section("Flags:")
+///     .flag("help", 'h', "Show this help message.")
+///     .flag("dry-run", None, "Print what would happen.")
+///     .section("Options:")
+///     .option("width", 'w', "INTEGER", "Set the width.\n[default: 80]")
+///     .section("Files:")
+///     .arg("FILES...", "The files to read.")
+///     .build();
+///
+/// assert_eq!(
+///     help,
+///     "Usage: {bin_name} [flags] [options] [FILES...]\n\
+///     \n\
+///     Flags:\n  \
+///     -h --help  Show this help message.\n  \
+///     --dry-run  Print what would happen.\n\
+///     \n\
+///     Options:\n  \
+///     -w --width INTEGER  Set the width.\n                      \
+///     [default: 80]\n\
+///     \n\
+///     Files:\n  \
+///     FILES...  The files to read.\n",
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct HelpBuilder {
+    text: String,
+    // Rows of the current section, as (label, description) pairs.
+    rows: Vec<(String, String)>,
+}
+
+impl HelpBuilder {
+    /// Create an empty help text.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a line of text, like a description or usage line.
+    ///
+    /// This ends the current section.
+    #[must_use]
+    pub fn line(mut self, text: impl AsRef<str>) -> Self {
+        self.finish_section();
+        self.text.push_str(text.as_ref());
+        self.text.push('\n');
+        self
+    }
+
+    /// Start a new section with a heading, like `"Options:"`.
+    ///
+    /// Sections are separated by an empty line.
+    #[must_use]
+    pub fn section(mut self, heading: impl AsRef<str>) -> Self {
+        self.finish_section();
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text.push_str(heading.as_ref());
+        self.text.push('\n');
+        self
+    }
+
+    /// Add a flag named `--{name}`, with the short name `-{short}`.
+    #[must_use]
+    pub fn flag(
+        self,
+        name: impl AsRef<str>,
+        short: impl Into<Option<char>>,
+        description: impl AsRef<str>,
+    ) -> Self {
+        let label = Self::label(name.as_ref(), short.into(), "");
+
+        self.arg(label, description)
+    }
+
+    /// Add an option named `--{name}` that takes a value, with the short name `-{short}`.
+    ///
+    /// The `value_name` describes the value, like `INTEGER` or `PATH`.
+    #[must_use]
+    pub fn option(
+        self,
+        name: impl AsRef<str>,
+        short: impl Into<Option<char>>,
+        value_name: impl AsRef<str>,
+        description: impl AsRef<str>,
+    ) -> Self {
+        let label = Self::label(
+            name.as_ref(),
+            short.into(),
+            &format!(" {}", value_name.as_ref()),
+        );
+
+        self.arg(label, description)
+    }
+
+    /// Add an argument with a custom label, like a positional argument.
+    #[must_use]
+    pub fn arg(mut self, label: impl Into<String>, description: impl AsRef<str>) -> Self {
+        self.rows
+            .push((label.into(), description.as_ref().to_string()));
+        self
+    }
+
+    /// Get the help text.
+    #[must_use]
+    pub fn build(mut self) -> String {
+        self.finish_section();
+        self.text
+    }
+
+    fn label(name: &str, short: Option<char>, value_name: &str) -> String {
+        match short {
+            Some(ch) => format!("-{ch} --{name}{value_name}"),
+            None => format!("--{name}{value_name}"),
+        }
+    }
+
+    // Write the rows of the current section, aligned to the widest label.
+    fn finish_section(&mut self) {
+        let max_width = self
+            .rows
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or_default();
+        let pad = " ".repeat(max_width + 4);
+
+        for (label, description) in self.rows.drain(..) {
+            let width = max_width - label.chars().count() + 2;
+            let description = description
+                .lines()
+                .collect::<Vec<_>>()
+                .join(&format!("\n{pad}"));
+            let row = format!("  {label}{:width$}{description}", "");
+
+            self.text.push_str(row.trim_end());
+            self.text.push('\n');
+        }
+    }
+}
+
+/// Wrap help text to the given width.
+///
+/// Lines that fit are left untouched. Longer lines are wrapped at word boundaries. If the line has
+/// a description column (text following two or more spaces, like the argument list), continuation
+/// lines are indented to that column. Otherwise they keep the line's own indentation.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::help::wrap;
+///
+/// let help = "  -v --verbose  Enable verbose output for debugging.";
+///
+/// assert_eq!(
+///     wrap(help, 40),
+///     "  -v --verbose  Enable verbose output\n                for debugging.",
+/// );
+/// ```
+#[must_use]
+pub fn wrap(text: &str, width: usize) -> String {
+    text.split('\n')
+        .map(|line| wrap_line(line, width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn wrap_line(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+
+    let leading = line.len() - line.trim_start().len();
+    let column = line[leading..]
+        .find("  ")
+        .map(|index| {
+            let index = leading + index;
+
+            index + line[index..].len() - line[index..].trim_start().len()
+        })
+        .filter(|&column| column + MIN_DESCRIPTION_WIDTH <= width)
+        .unwrap_or(leading);
+    let indent = " ".repeat(column);
+
+    let mut output = String::new();
+    let mut current = line[..column].to_string();
+    let mut current_width = current.chars().count();
+    let mut empty = true;
+    for word in line[column..].split_whitespace() {
+        let word_width = word.chars().count();
+
+        if !empty && current_width + 1 + word_width > width {
+            output.push_str(&current);
+            output.push('\n');
+            current.clone_from(&indent);
+            current_width = column;
+            empty = true;
+        }
+        if !empty {
+            current.push(' ');
+            current_width += 1;
+        }
+        current.push_str(word);
+        current_width += word_width;
+        empty = false;
+    }
+    output.push_str(&current);
+
+    output
+}
+
+/// Check whether the plain help format was requested with the `ONLYARGS_PLAIN_HELP` environment
+/// variable.
+///
+/// Any value other than an empty string or `0` enables the plain format.
+#[must_use]
+pub fn plain_requested() -> bool {
+    env::var_os("ONLYARGS_PLAIN_HELP").map_or(false, |value| !value.is_empty() && value != "0")
+}
+
+/// Convert help text to the plain format.
+///
+/// Argument lines lose their column alignment and padding, becoming one `name: description` pair
+/// per line. Continuation lines of a description are joined to it. This format works better with
+/// screen readers and when searching the help text with tools like `grep`. Other lines are left
+/// untouched, and the text is not wrapped.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::help::plain;
+///
+/// let help = "Options:\n  -v --verbose      Enable verbose output\n                    for debugging.\n  \
+///     -o --output PATH  Output file.";
+///
+/// assert_eq!(
+///     plain(help),
+///     "Options:\n-v --verbose: Enable verbose output for debugging.\n-o --output PATH: Output file.",
+/// );
+/// ```
+#[must_use]
+pub fn plain(text: &str) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut column = None;
+
+    for line in text.split('\n') {
+        let indent = line.len() - line.trim_start().len();
+        if let (Some(column), Some(last)) = (column, lines.last_mut()) {
+            if indent >= column && !line.trim().is_empty() {
+                last.push(' ');
+                last.push_str(line.trim());
+                continue;
+            }
+        }
+
+        column = None;
+        let arg = line
+            .strip_prefix("  -")
+            .map(|_| line[2..].split_once("  ").unwrap_or((&line[2..], "")));
+        match arg {
+            Some((name, description)) if !description.trim().is_empty() => {
+                column = Some(line.len() - description.trim_start().len());
+                lines.push(format!("{}: {}", name.trim_end(), description.trim()));
+            }
+            Some((name, _)) => lines.push(name.trim_end().to_string()),
+            None => lines.push(line.to_string()),
+        }
+    }
+
+    lines.join("\n")
+}
@@ -0,0 +1,235 @@
+//! A low-level lexer for command line arguments.
+//!
+//! [`Lexer`] splits arguments into tokens, so manual [`OnlyArgs`](crate::OnlyArgs)
+//! implementations do not need to classify them by hand:
+//!
+//! - `--name` and `--name=value` are long options.
+//! - `-abc` is a cluster of short options, the same as `-a -b -c`.
+//! - Everything else is a value, including `-` by itself and all arguments after `--`.
+//!
+//! Options that take a value read it with [`Lexer::value`]. The value can be attached, like in
+//! `--width=3`, `-w3`, and `-w=3`, or it can be the next argument, like in `--width 3`.
+//!
+//! The lexer reads arguments from any iterator, so it can stream them from the environment with
+//! `Lexer::new(std::env::args_os().skip(1))`.
+//!
+//! # Example
+//!
+//! ```
+//! use onlyargs::lexer::{Arg, Lexer};
+//! use onlyargs::traits::*;
+//! use onlyargs::{CliError, OnlyArgs};
+//! use std::ffi::OsString;
+//!
+//! #[derive(Debug)]
+//! struct Args {
+//!     verbose: bool,
+//!     width: i32,
+//!     files: Vec<OsString>,
+//! }
+//!
+//! impl OnlyArgs for Args {
+//!     fn parse(args: Vec<OsString>) -> Result<Self, CliError> {
+//!         let mut verbose = false;
+//!         let mut width = 80;
+//!         let mut files = vec![];
+//!
+//!         let mut lexer = Lexer::new(args);
+//!         while let Some(arg) = lexer.next_arg()? {
+//!             match arg {
+//!                 Arg::Short('h') | Arg::Long("help") => Self::help(),
+//!                 Arg::Short('v') | Arg::Long("verbose") => verbose = true,
+//!                 Arg::Short('w') | Arg::Long("width") => {
+//!                     width = lexer.value()?.parse_int("--width")?;
+//!                 }
+//!                 Arg::Value(value) => files.push(value),
+//!                 arg => return Err(CliError::unknown(arg.into(), &[])),
+//!             }
+//!         }
+//!
+//!         Ok(Self {
+//!             verbose,
+//!             width,
+//!             files,
+//!         })
+//!     }
+//! }
+//!
+//! let args = Args::parse(vec!["-vw3".into(), "a.txt".into(), "--".into(), "-b.txt".into()])?;
+//!
+//! assert!(args.verbose);
+//! assert_eq!(args.width, 3);
+//! assert_eq!(args.files, ["a.txt", "-b.txt"]);
+//! # Ok::<_, CliError>(())
+//! ```
+
+use crate::CliError;
+use std::ffi::OsString;
+
+/// A token produced by [`Lexer::next_arg`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Arg<'a> {
+    /// A short option, like `-v`. The leading hyphen is not included.
+    Short(char),
+
+    /// A long option, like `--verbose`. The leading hyphens and any attached value are not
+    /// included.
+    Long(&'a str),
+
+    /// A value that is not an option.
+    Value(OsString),
+}
+
+/// Converts the token back to the argument it was lexed from, e.g. for [`CliError::unknown`].
+impl From<Arg<'_>> for OsString {
+    fn from(arg: Arg<'_>) -> Self {
+        match arg {
+            Arg::Short(ch) => format!("-{ch}").into(),
+            Arg::Long(name) => format!("--{name}").into(),
+            Arg::Value(value) => value,
+        }
+    }
+}
+
+/// Splits command line arguments into [`Arg`] tokens.
+///
+/// Arguments are read from the iterator one at a time, like [`std::env::args_os`], without
+/// collecting them first. Tokens borrow from the lexer, so matching them does not allocate.
+#[derive(Debug)]
+pub struct Lexer<I = std::vec::IntoIter<OsString>> {
+    args: I,
+    // The current cluster of short options, and the byte offset of the next one.
+    shorts: Option<(String, usize)>,
+    // The last long option including its hyphens, and the byte offset where its name ends.
+    long: (String, usize),
+    // A value attached to the last long option with `=`.
+    attached: Option<OsString>,
+    // The last short option, or `None` if the last option was long.
+    last_short: Option<char>,
+    finished: bool,
+}
+
+impl<I: Iterator<Item = OsString>> Lexer<I> {
+    /// Create a lexer for `args`, which do not include the program name.
+    #[must_use]
+    pub fn new<A>(args: A) -> Self
+    where
+        A: IntoIterator<IntoIter = I, Item = OsString>,
+    {
+        Self {
+            args: args.into_iter(),
+            shorts: None,
+            long: (String::new(), 0),
+            attached: None,
+            last_short: None,
+            finished: false,
+        }
+    }
+
+    /// Get the next token.
+    ///
+    /// Returns `None` when all arguments have been consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the last long option had an attached value that was not read with
+    /// [`Lexer::value`], like `--verbose=yes` for a flag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::lexer::{Arg, Lexer};
+    ///
+    /// let mut lexer = Lexer::new(vec!["--width=3".into(), "--verbose=yes".into()]);
+    ///
+    /// assert_eq!(lexer.next_arg()?, Some(Arg::Long("width")));
+    /// assert_eq!(lexer.value()?, "3");
+    /// assert_eq!(lexer.next_arg()?, Some(Arg::Long("verbose")));
+    /// assert!(lexer.next_arg().is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    pub fn next_arg(&mut self) -> Result<Option<Arg<'_>>, CliError> {
+        if let Some(value) = self.attached.take() {
+            return Err(CliError::custom(
+                self.last(),
+                format!(
+                    "unexpected value `{}`, this argument does not take a value",
+                    value.to_string_lossy(),
+                ),
+            ));
+        }
+
+        if let Some((cluster, offset)) = self.shorts.as_mut() {
+            if let Some(ch) = cluster[*offset..].chars().next() {
+                *offset += ch.len_utf8();
+                self.last_short = Some(ch);
+
+                return Ok(Some(Arg::Short(ch)));
+            }
+            self.shorts = None;
+        }
+
+        loop {
+            let arg = match self.args.next() {
+                Some(arg) if !self.finished => arg,
+                arg => return Ok(arg.map(Arg::Value)),
+            };
+
+            return Ok(Some(match arg.into_string() {
+                Ok(arg) if arg == "--" => {
+                    self.finished = true;
+                    continue;
+                }
+                Ok(arg) if arg.starts_with("--") => {
+                    let end = arg.find('=').unwrap_or(arg.len());
+                    self.attached = arg.get(end + 1..).map(OsString::from);
+                    self.long = (arg, end);
+                    self.last_short = None;
+
+                    Arg::Long(&self.long.0[2..end])
+                }
+                Ok(arg) if arg.starts_with('-') && arg.len() > 1 => {
+                    let ch = arg[1..].chars().next().unwrap_or_default();
+                    self.shorts = Some((arg, 1 + ch.len_utf8()));
+                    self.last_short = Some(ch);
+
+                    Arg::Short(ch)
+                }
+                Ok(arg) => Arg::Value(arg.into()),
+                Err(arg) => Arg::Value(arg),
+            }));
+        }
+    }
+
+    /// Get the value for the last option.
+    ///
+    /// This is the value attached to the option, or the next argument.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if there are no more arguments.
+    pub fn value(&mut self) -> Result<OsString, CliError> {
+        if let Some(value) = self.attached.take() {
+            return Ok(value);
+        }
+
+        if let Some((cluster, offset)) = self.shorts.take() {
+            let rest = &cluster[offset..];
+            if !rest.is_empty() {
+                return Ok(rest.strip_prefix('=').unwrap_or(rest).into());
+            }
+        }
+
+        self.args.next().ok_or_else(|| CliError::MissingValue {
+            argument: self.last(),
+        })
+    }
+
+    // The name of the last option, for error messages.
+    fn last(&self) -> String {
+        match self.last_short {
+            Some(ch) => format!("-{ch}"),
+            None => self.long.0[..self.long.1].to_string(),
+        }
+    }
+}
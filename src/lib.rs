@@ -13,134 +13,2124 @@
 #![deny(clippy::all)]
 #![deny(clippy::pedantic)]
 
+use crate::alias::Aliases;
+use crate::source::{Env, Layers};
 use std::env;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::fmt::Display;
+use std::io;
+use std::num::{ParseFloatError, ParseIntError};
+use std::path::PathBuf;
+use std::process::{ExitCode, Termination};
+use std::str::FromStr;
+use std::sync::Arc;
 
+pub mod alias;
+#[doc(hidden)]
+pub mod args_macro;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "color")]
+pub mod color;
+pub mod completion;
+#[cfg(any(feature = "json", feature = "toml"))]
+pub mod config;
+#[cfg(feature = "dotenv")]
+pub mod dotenv;
+pub mod generate;
+pub mod help;
+pub mod lexer;
+pub mod logging;
+pub mod runtime;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod source;
+pub mod suggest;
 pub mod traits;
+pub mod types;
 
-/// Argument parsing errors.
+/// The outcome of [`OnlyArgs::try_parse`].
 #[derive(Debug)]
+pub enum ParseResult<T> {
+    /// The arguments were parsed.
+    Args(T),
+
+    /// The `--help` argument was provided.
+    Help,
+
+    /// The `-h` argument was provided.
+    ShortHelp,
+
+    /// The `--version` argument was provided.
+    Version,
+
+    /// The `-V` argument was provided.
+    ShortVersion,
+}
+
+impl<T: OnlyArgs> ParseResult<T> {
+    /// Get the parsed arguments, or print the help or version message and exit the process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::{OnlyArgs, ParseResult};
+    /// # #[derive(Debug)]
+    /// # struct Args;
+    /// # impl OnlyArgs for Args {
+    /// #     fn parse(_args: Vec<std::ffi::OsString>) -> Result<Self, onlyargs::CliError> {
+    /// #         Ok(Args)
+    /// #     }
+    /// # }
+    ///
+    /// match Args::try_parse(vec![])? {
+    ///     ParseResult::Help => println!("{}", Args::HELP),
+    ///     result => {
+    ///         let args = result.or_exit();
+    ///         // Do something with `args`...
+    ///     }
+    /// }
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    pub fn or_exit(self) -> T {
+        match self {
+            Self::Args(args) => args,
+            Self::Help => T::help(),
+            Self::ShortHelp => T::short_help(),
+            Self::Version => T::version(),
+            Self::ShortVersion => T::short_version(),
+        }
+    }
+}
+
+/// The exit code for command line usage errors from `sysexits.h`.
+///
+/// See [`OnlyArgs::error_exit_code`].
+pub const EX_USAGE: i32 = 64;
+
+/// Argument parsing errors.
+///
+/// Errors for an argument name the argument in the `argument` field, which is the command line
+/// argument (like `--width`), or the environment variable or config key that the value came from.
+/// The value that failed to parse, if any, is in the `value` field.
+///
+/// New variants may be added in future releases. Use [`CliError::kind`] to categorize errors
+/// without matching on every variant.
+///
+/// Errors can be compared with `==`, which makes them easy to check in tests. Wrapped I/O errors
+/// are compared by their [`io::ErrorKind`], and [custom errors](CliError::Custom) by their
+/// messages. [Positions](CliError::At) are ignored, so errors from a parser compare equal to the
+/// same error without a position. Match on [`CliError::inner`] to ignore the position with
+/// `matches!`.
+///
+/// ```
+/// use onlyargs::CliError;
+///
+/// let err = CliError::MissingValue {
+///     argument: "--width".into(),
+/// };
+///
+/// assert_eq!(err.clone(), err);
+/// assert_eq!(err.clone().at(1, "--width"), err);
+/// assert!(matches!(
+///     err.at(1, "--width").inner(),
+///     CliError::MissingValue { argument } if argument == "--width",
+/// ));
+/// ```
+///
+/// Values are shown as plain text in error messages. On Unix, bytes that are not valid UTF-8 are
+/// shown as `\xNN` escapes:
+///
+/// ```
+/// # #[cfg(unix)] {
+/// use onlyargs::CliError;
+/// use std::ffi::OsString;
+/// use std::os::unix::ffi::OsStringExt as _;
+///
+/// let err = CliError::Unknown {
+///     argument: OsString::from_vec(b"--caf\xE9".to_vec()),
+/// };
+///
+/// assert_eq!(err.to_string(), r"Unknown argument `--caf\xE9`");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum CliError {
     /// An argument requires a value, but one was not provided.
-    MissingValue(String),
+    MissingValue { argument: String },
 
     /// A required argument was not provided.
-    MissingRequired(String),
+    MissingRequired { argument: String },
 
     /// An argument requires a value, but parsing it as a `bool` failed.
-    ParseBoolError(String, OsString, std::str::ParseBoolError),
+    ParseBoolError {
+        argument: String,
+        value: OsString,
+        source: std::str::ParseBoolError,
+    },
 
     /// An argument requires a value, but parsing it as a `char` failed.
-    ParseCharError(String, OsString, std::char::ParseCharError),
+    ParseCharError {
+        argument: String,
+        value: OsString,
+        source: std::char::ParseCharError,
+    },
 
     /// An argument requires a value, but parsing it as a floating-point number failed.
-    ParseFloatError(String, OsString, std::num::ParseFloatError),
+    ParseFloatError {
+        argument: String,
+        value: OsString,
+        source: std::num::ParseFloatError,
+    },
 
     /// An argument requires a value, but parsing it as an integer failed.
-    ParseIntError(String, OsString, std::num::ParseIntError),
+    ParseIntError {
+        argument: String,
+        value: OsString,
+        source: std::num::ParseIntError,
+    },
 
     /// An argument requires a value, but parsing it as a `String` failed.
-    ParseStrError(String, OsString),
+    ParseStrError { argument: String, value: OsString },
+
+    /// An argument requires a value, but parsing it as a URL failed.
+    #[cfg(feature = "url")]
+    ParseUrlError {
+        argument: String,
+        value: OsString,
+        source: url::ParseError,
+    },
+
+    /// An argument requires a value, but parsing it as a UUID failed.
+    #[cfg(feature = "uuid")]
+    ParseUuidError {
+        argument: String,
+        value: OsString,
+        source: uuid::Error,
+    },
+
+    /// An argument requires a value, but parsing it as a semantic version failed.
+    #[cfg(feature = "semver")]
+    ParseVersionError {
+        argument: String,
+        value: OsString,
+        source: Arc<semver::Error>,
+    },
+
+    /// An argument value is not one of the possible values.
+    InvalidChoice {
+        argument: String,
+        value: OsString,
+        choices: Vec<&'static str>,
+    },
+
+    /// Two arguments that cannot be used together were both provided.
+    Conflict { argument: String, other: String },
+
+    /// A path argument does not exist.
+    PathNotFound { argument: String, path: PathBuf },
+
+    /// A path argument exists, but it is not a file.
+    NotAFile { argument: String, path: PathBuf },
+
+    /// A path argument exists, but it is not a directory.
+    NotADirectory { argument: String, path: PathBuf },
+
+    /// An unknown argument was provided.
+    Unknown { argument: OsString },
+
+    /// An unknown argument was provided, and it looks like a typo of a known argument.
+    UnknownSuggestion {
+        argument: OsString,
+        suggestion: String,
+    },
+
+    /// A configuration file exists, but reading it failed.
+    ConfigIoError {
+        path: PathBuf,
+        source: Arc<io::Error>,
+    },
+
+    /// Parsing a configuration file failed. The `path` is `None` for text that was not read from a
+    /// file.
+    ParseConfigError {
+        path: Option<PathBuf>,
+        message: String,
+    },
+
+    /// An alias expands to itself, directly or through other aliases.
+    AliasRecursion { alias: String },
+
+    /// An alias expansion cannot be split into words.
+    ParseAliasError { alias: String, message: String },
+
+    /// Several arguments failed to parse.
+    ///
+    /// Returned by parsers that keep going after the first error, so all of the problems can be
+    /// reported at once. The errors are in the order they were found.
+    Multiple { errors: Vec<CliError> },
+
+    /// An argument was rejected by an application-defined parser or validator.
+    ///
+    /// See [`CliError::custom`].
+    Custom {
+        argument: String,
+        source: Arc<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// An error caused by the argument `token` at `index`.
+    ///
+    /// The index is zero-based and counts the arguments given to the parser, which do not include
+    /// the program name. See [`CliError::position`] and [`CliError::token`].
+    ///
+    /// The position does not change what the error is. It compares equal to the underlying error,
+    /// which [`CliError::inner`] returns for use with `matches!`.
+    At {
+        index: usize,
+        token: OsString,
+        error: Box<CliError>,
+    },
+}
+
+/// The category of a [`CliError`], returned by [`CliError::kind`].
+///
+/// Each kind corresponds to a [`CliError`] variant. Errors with a position report the kind of the
+/// underlying error, and [`CliError::UnknownSuggestion`] is reported as [`ErrorKind::Unknown`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// See [`CliError::MissingValue`].
+    MissingValue,
+
+    /// See [`CliError::MissingRequired`].
+    MissingRequired,
+
+    /// See [`CliError::ParseBoolError`].
+    ParseBoolError,
+
+    /// See [`CliError::ParseCharError`].
+    ParseCharError,
+
+    /// See [`CliError::ParseFloatError`].
+    ParseFloatError,
+
+    /// See [`CliError::ParseIntError`].
+    ParseIntError,
+
+    /// See [`CliError::ParseStrError`].
+    ParseStrError,
+
+    /// See [`CliError::ParseUrlError`].
+    #[cfg(feature = "url")]
+    ParseUrlError,
+
+    /// See [`CliError::ParseUuidError`].
+    #[cfg(feature = "uuid")]
+    ParseUuidError,
+
+    /// See [`CliError::ParseVersionError`].
+    #[cfg(feature = "semver")]
+    ParseVersionError,
+
+    /// See [`CliError::InvalidChoice`].
+    InvalidChoice,
+
+    /// See [`CliError::Conflict`].
+    Conflict,
+
+    /// See [`CliError::PathNotFound`].
+    PathNotFound,
+
+    /// See [`CliError::NotAFile`].
+    NotAFile,
+
+    /// See [`CliError::NotADirectory`].
+    NotADirectory,
+
+    /// See [`CliError::Unknown`] and [`CliError::UnknownSuggestion`].
+    Unknown,
+
+    /// See [`CliError::ConfigIoError`].
+    ConfigIoError,
+
+    /// See [`CliError::ParseConfigError`].
+    ParseConfigError,
+
+    /// See [`CliError::AliasRecursion`].
+    AliasRecursion,
+
+    /// See [`CliError::ParseAliasError`].
+    ParseAliasError,
+
+    /// See [`CliError::Multiple`].
+    Multiple,
+
+    /// See [`CliError::Custom`].
+    Custom,
+}
+
+/// The primary argument parser trait.
+///
+/// This trait can be derived with the [`onlyargs_derive`](https://docs.rs/onlyargs_derive) crate.
+///
+/// See the [`parse`] function for more information.
+pub trait OnlyArgs {
+    /// The application help string.
+    ///
+    /// The `{bin_name}` placeholder is replaced with [`bin_name`] when the help string is printed,
+    /// e.g. in the usage line: `"Usage: {bin_name} [flags]"`.
+    const HELP: &'static str = concat!(
+        env!("CARGO_PKG_NAME"),
+        " v",
+        env!("CARGO_PKG_VERSION"),
+        "\n",
+        env!("CARGO_PKG_DESCRIPTION"),
+        "\n",
+    );
+
+    /// A condensed application help string, shown for `-h`.
+    ///
+    /// Defaults to [`OnlyArgs::HELP`]. The `{bin_name}` placeholder is replaced like in
+    /// [`OnlyArgs::HELP`].
+    const SHORT_HELP: &'static str = Self::HELP;
+
+    /// The application name and version.
+    const VERSION: &'static str = concat!(
+        env!("CARGO_PKG_NAME"),
+        " v",
+        env!("CARGO_PKG_VERSION"),
+        "\n",
+    );
+
+    /// A condensed application name and version, shown for `-V`.
+    ///
+    /// Defaults to [`OnlyArgs::VERSION`].
+    const SHORT_VERSION: &'static str = Self::VERSION;
+
+    /// A description of every argument, for tools that generate shell completions or
+    /// documentation, and for tests that inspect the command line interface.
+    ///
+    /// Hidden arguments are not included. The default is empty, and `onlyargs_derive` fills it in.
+    /// See [`completion`].
+    const SPEC: &'static [ArgSpec] = &[];
+
+    /// The usage line, shown with argument parsing errors by [`OnlyArgs::format_error`].
+    ///
+    /// Multiple lines should be indented by two spaces, like in the help text. The `{bin_name}`
+    /// placeholder is replaced like in [`OnlyArgs::HELP`]. Defaults to an empty string, which omits
+    /// the usage from error messages.
+    const USAGE: &'static str = "";
+
+    /// Construct a type that implements this trait.
+    ///
+    /// Each argument is provided as an [`OsString`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command line arguments cannot be parsed to `Self`.
+    fn parse(args: Vec<OsString>) -> Result<Self, CliError>
+    where
+        Self: Sized;
+
+    /// Construct a type that implements this trait from an iterator of arguments.
+    ///
+    /// This avoids collecting the arguments into a `Vec` first, e.g. when they come from
+    /// [`std::env::args_os`]. The default implementation collects them and calls
+    /// [`OnlyArgs::parse`]. Derived parsers stream the arguments, unless they are preprocessed or
+    /// scanned for a config path.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command line arguments cannot be parsed to `Self`.
+    fn parse_iter<I>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator<Item = OsString>,
+        Self: Sized,
+    {
+        Self::parse(args.into_iter().collect())
+    }
+
+    /// Create the [`Layers`] that provide values for arguments missing from the command line.
+    ///
+    /// The default implementation only reads environment variables with [`Env`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a source cannot be created, e.g. when a config file cannot be parsed.
+    fn layers() -> Result<Layers, CliError> {
+        Ok(Layers::new().push(Env))
+    }
+
+    /// Construct a type that implements this trait, using `layers` for arguments that are missing
+    /// from the command line.
+    ///
+    /// The default implementation ignores `layers` and calls [`OnlyArgs::parse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command line arguments or layered values cannot be parsed to `Self`.
+    fn parse_with_layers(args: Vec<OsString>, layers: &Layers) -> Result<Self, CliError>
+    where
+        Self: Sized,
+    {
+        let _ = layers;
+
+        Self::parse(args)
+    }
+
+    /// Construct a type that implements this trait, without exiting the process for the help or
+    /// version arguments.
+    ///
+    /// This is useful for libraries, tests, and interactive applications that need to decide how
+    /// to handle these arguments. The default implementation calls [`OnlyArgs::parse`], so only
+    /// implementations that override this method return anything other than
+    /// [`ParseResult::Args`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command line arguments cannot be parsed to `Self`.
+    fn try_parse(args: Vec<OsString>) -> Result<ParseResult<Self>, CliError>
+    where
+        Self: Sized,
+    {
+        Self::parse(args).map(ParseResult::Args)
+    }
+
+    /// Like [`OnlyArgs::try_parse`], using `layers` for arguments that are missing from the command
+    /// line.
+    ///
+    /// The default implementation calls [`OnlyArgs::parse_with_layers`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command line arguments or layered values cannot be parsed to `Self`.
+    fn try_parse_with_layers(
+        args: Vec<OsString>,
+        layers: &Layers,
+    ) -> Result<ParseResult<Self>, CliError>
+    where
+        Self: Sized,
+    {
+        Self::parse_with_layers(args, layers).map(ParseResult::Args)
+    }
+
+    /// Construct a type that implements this trait, and return any [warnings](Warning) instead of
+    /// printing them.
+    ///
+    /// The default implementation calls [`OnlyArgs::parse`] and returns no warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command line arguments cannot be parsed to `Self`.
+    fn parse_with_warnings(args: Vec<OsString>) -> Result<(Self, Vec<Warning>), CliError>
+    where
+        Self: Sized,
+    {
+        Self::parse(args).map(|args| (args, vec![]))
+    }
+
+    /// Construct a type that implements this trait from the leading arguments, and return the rest.
+    ///
+    /// Parsing stops at the first argument that is not recognized. That argument and all arguments
+    /// after it are returned, so another parser can finish the job. Arguments after `--` are also
+    /// returned, without the `--`.
+    ///
+    /// The default implementation calls [`OnlyArgs::parse`] and returns no arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the recognized arguments cannot be parsed to `Self`.
+    fn parse_partial(args: Vec<OsString>) -> Result<(Self, Vec<OsString>), CliError>
+    where
+        Self: Sized,
+    {
+        Self::parse(args).map(|args| (args, vec![]))
+    }
+
+    /// Like [`OnlyArgs::try_parse_with_layers`], returning any [warnings](Warning) instead of
+    /// printing them.
+    ///
+    /// The default implementation calls [`OnlyArgs::try_parse_with_layers`] and returns no
+    /// warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the command line arguments or layered values cannot be parsed to `Self`.
+    fn try_parse_with_warnings(
+        args: Vec<OsString>,
+        layers: &Layers,
+    ) -> Result<(ParseResult<Self>, Vec<Warning>), CliError>
+    where
+        Self: Sized,
+    {
+        Self::try_parse_with_layers(args, layers).map(|result| (result, vec![]))
+    }
+
+    /// Report a warning found while parsing arguments.
+    ///
+    /// Parse methods that do not return warnings call this for each warning. The default
+    /// implementation prints the warning to `stderr`.
+    fn warn(warning: &Warning) {
+        eprintln!("Warning: {warning}");
+    }
+
+    /// Rewrite the command line arguments before they are parsed.
+    ///
+    /// This is an extension point for features that transform the arguments, like expanding
+    /// [aliases](alias), inserting extra arguments from the environment, or replacing renamed
+    /// arguments. Derived parsers call it before matching any arguments. Implementations of
+    /// [`OnlyArgs::parse`] should do the same. The default implementation returns `args` unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the arguments cannot be rewritten.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::ffi::OsString;
+    /// use onlyargs::{CliError, OnlyArgs};
+    ///
+    /// struct Args {
+    ///     verbose: bool,
+    /// }
+    ///
+    /// impl OnlyArgs for Args {
+    ///     fn parse(args: Vec<OsString>) -> Result<Self, CliError> {
+    ///         let args = Self::preprocess(args)?;
+    ///
+    ///         Ok(Self {
+    ///             verbose: args.iter().any(|arg| arg == "--verbose"),
+    ///         })
+    ///     }
+    ///
+    ///     fn preprocess(args: Vec<OsString>) -> Result<Vec<OsString>, CliError> {
+    ///         // `--debug` was renamed to `--verbose`.
+    ///         Ok(args
+    ///             .into_iter()
+    ///             .map(|arg| if arg == "--debug" { "--verbose".into() } else { arg })
+    ///             .collect())
+    ///     }
+    /// }
+    ///
+    /// assert!(Args::parse(vec!["--debug".into()])?.verbose);
+    /// # Ok::<_, CliError>(())
+    /// ```
+    fn preprocess(args: Vec<OsString>) -> Result<Vec<OsString>, CliError> {
+        Ok(args)
+    }
+
+    /// Check the parsed arguments as a whole.
+    ///
+    /// This is the place for checks that involve more than one argument, like ranges that depend on
+    /// other options or options that must be used together. Derived parsers call it before
+    /// returning. Implementations of [`OnlyArgs::parse`] should do the same. The default
+    /// implementation accepts all arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the arguments are not valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::ffi::OsString;
+    /// use onlyargs::{CliError, OnlyArgs};
+    ///
+    /// struct Args {
+    ///     min: i32,
+    ///     max: i32,
+    /// }
+    ///
+    /// impl OnlyArgs for Args {
+    ///     fn parse(args: Vec<OsString>) -> Result<Self, CliError> {
+    ///         // Parse the arguments...
+    ///         # let _ = args;
+    ///         let args = Self { min: 10, max: 0 };
+    ///
+    ///         args.validate()?;
+    ///
+    ///         Ok(args)
+    ///     }
+    ///
+    ///     fn validate(&self) -> Result<(), CliError> {
+    ///         if self.min > self.max {
+    ///             return Err(CliError::custom("--min", "must not be greater than `--max`"));
+    ///         }
+    ///
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// assert!(Args::parse(vec![]).is_err());
+    /// ```
+    fn validate(&self) -> Result<(), CliError> {
+        Ok(())
+    }
+
+    /// The width that help text is wrapped to.
+    ///
+    /// The default implementation detects the terminal width with [`help::terminal_width`].
+    #[must_use]
+    fn help_width() -> usize {
+        help::terminal_width().unwrap_or(help::DEFAULT_WIDTH)
+    }
+
+    /// Whether help text is printed in the plain format. See [`help::plain`].
+    ///
+    /// The default implementation checks the `ONLYARGS_PLAIN_HELP` environment variable with
+    /// [`help::plain_requested`].
+    #[must_use]
+    fn plain_help() -> bool {
+        help::plain_requested()
+    }
+
+    /// Whether long help text is shown in a pager. See `help::page()`.
+    ///
+    /// This has no effect unless the `pager` feature is enabled. The default implementation returns
+    /// `false`.
+    #[must_use]
+    fn pager() -> bool {
+        false
+    }
+
+    /// Whether [`parse`] handles the hidden `--generate` argument. See [`generate`].
+    ///
+    /// The default implementation returns `false`.
+    #[must_use]
+    fn generate_arg() -> bool {
+        false
+    }
+
+    /// The exit code used after printing the help or version message.
+    ///
+    /// The default implementation returns `0`.
+    #[must_use]
+    fn help_exit_code() -> i32 {
+        0
+    }
+
+    /// The exit code used by [`OnlyArgs::exit_with_error`].
+    ///
+    /// The default implementation returns `2`, the conventional exit code for usage errors. Use
+    /// [`EX_USAGE`] to follow `sysexits.h` instead.
+    #[must_use]
+    fn error_exit_code() -> i32 {
+        2
+    }
+
+    /// Print an argument parsing error and its causes to `stderr`, and exit the process with
+    /// [`OnlyArgs::error_exit_code`].
+    ///
+    /// With the `color` feature, the message is colored when `stderr` is a terminal. See the
+    /// `color` module.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[derive(Debug)]
+    /// # struct Args;
+    /// # impl onlyargs::OnlyArgs for Args {
+    /// #     fn parse(_args: Vec<std::ffi::OsString>) -> Result<Self, onlyargs::CliError> {
+    /// #         Ok(Args)
+    /// #     }
+    /// # }
+    /// use onlyargs::OnlyArgs as _;
+    ///
+    /// let args: Args = onlyargs::parse().unwrap_or_else(|err| Args::exit_with_error(&err));
+    /// ```
+    fn exit_with_error(err: &CliError) -> ! {
+        eprint_error(&Self::format_error(err));
+
+        std::process::exit(Self::error_exit_code());
+    }
+
+    /// Generate a shell completion script from [`OnlyArgs::SPEC`].
+    ///
+    /// The script completes the name of the running binary. See [`completion::generate`].
+    #[must_use]
+    fn completions(shell: completion::Shell) -> String {
+        completion::generate(shell, &short_bin_name(), Self::SPEC)
+    }
+
+    /// Generate a man page from [`OnlyArgs::SPEC`]. See [`generate::man`].
+    #[must_use]
+    fn man_page() -> String {
+        let bin_name = short_bin_name();
+        let usage = Self::USAGE.replace("{bin_name}", &bin_name);
+
+        generate::man(&bin_name, Self::SHORT_VERSION, &usage, Self::SPEC)
+    }
+
+    /// Generate a Markdown reference from [`OnlyArgs::SPEC`]. See [`generate::markdown`].
+    #[must_use]
+    fn markdown() -> String {
+        let bin_name = short_bin_name();
+        let usage = Self::USAGE.replace("{bin_name}", &bin_name);
+
+        generate::markdown(&bin_name, Self::SHORT_VERSION, &usage, Self::SPEC)
+    }
+
+    /// Construct an equivalent [`clap::Command`](::clap::Command) from [`OnlyArgs::SPEC`]. See
+    /// [`clap::command`](self::clap::command).
+    ///
+    /// The version number is the last word of [`OnlyArgs::SHORT_VERSION`], without a leading `v`.
+    #[cfg(feature = "clap")]
+    #[must_use]
+    fn clap_command() -> ::clap::Command {
+        let version = Self::SHORT_VERSION
+            .split_whitespace()
+            .last()
+            .unwrap_or_default();
+        let version = version.strip_prefix('v').unwrap_or(version);
+
+        self::clap::command(&short_bin_name(), version, Self::SPEC)
+    }
+
+    /// Generate a JSON spec from [`OnlyArgs::SPEC`]. See [`generate::json`].
+    #[must_use]
+    fn spec_json() -> String {
+        let bin_name = short_bin_name();
+        let usage = Self::USAGE.replace("{bin_name}", &bin_name);
+
+        generate::json(&bin_name, Self::SHORT_VERSION, &usage, Self::SPEC)
+    }
+
+    /// The completion candidates for the value of the argument named `name`, which starts with
+    /// `word`.
+    ///
+    /// Completion scripts call this for arguments with [`Complete::Callback`]. The default
+    /// implementation returns no candidates. See [`completion::respond`].
+    #[must_use]
+    fn complete(name: &str, word: &str) -> Vec<String> {
+        let _ = (name, word);
+
+        Vec::new()
+    }
+
+    /// Format an argument parsing error with the [usage line](OnlyArgs::USAGE).
+    ///
+    /// Arguments in [`OnlyArgs::SPEC`] are shown with their short name and value placeholder, like
+    /// `-w/--width <INTEGER>`. See the [`format_error`] function.
+    #[must_use]
+    fn format_error(err: &CliError) -> String {
+        format_error(
+            &err.labeled(Self::SPEC),
+            &Self::USAGE.replace("{bin_name}", &bin_name()),
+        )
+    }
+
+    /// The stream that help and version messages are printed to.
+    ///
+    /// The default implementation returns [`help::Output::Stdout`].
+    #[must_use]
+    fn help_output() -> help::Output {
+        help::Output::Stdout
+    }
+
+    /// Format help text for printing.
+    ///
+    /// The text is converted with [`help::plain`] if [`OnlyArgs::plain_help`] is `true`. Otherwise
+    /// it is wrapped to [`OnlyArgs::help_width`].
+    #[must_use]
+    fn render_help(text: &str) -> String {
+        if Self::plain_help() {
+            help::plain(text)
+        } else {
+            help::wrap(text, Self::help_width())
+        }
+    }
+
+    /// The application help string, with `{bin_name}` replaced by the name of the binary.
+    ///
+    /// Unlike [`OnlyArgs::help`], the text is not formatted for the terminal. This is useful for
+    /// sending the help text to logs, GUIs, or test snapshots.
+    #[must_use]
+    fn help_string() -> String {
+        Self::HELP.replace("{bin_name}", &bin_name())
+    }
+
+    /// The condensed application help string, with `{bin_name}` replaced by the name of the
+    /// binary.
+    #[must_use]
+    fn short_help_string() -> String {
+        Self::SHORT_HELP.replace("{bin_name}", &bin_name())
+    }
+
+    /// Write the application help string from [`OnlyArgs::help_string`] to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if writing fails.
+    fn write_help(writer: &mut impl io::Write) -> io::Result<()> {
+        writer.write_all(Self::help_string().as_bytes())
+    }
+
+    /// Write the application name and version to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if writing fails.
+    fn write_version(writer: &mut impl io::Write) -> io::Result<()> {
+        writer.write_all(Self::VERSION.as_bytes())
+    }
+
+    /// Print the application help string and exit the process.
+    ///
+    /// The help string from [`OnlyArgs::help_string`] is formatted with
+    /// [`OnlyArgs::render_help`] and printed with [`help::print`].
+    fn help() -> ! {
+        help::print(
+            &Self::render_help(&Self::help_string()),
+            Self::help_output(),
+            Self::pager(),
+        );
+        std::process::exit(Self::help_exit_code());
+    }
+
+    /// Print the condensed application help string and exit the process.
+    ///
+    /// The help string from [`OnlyArgs::short_help_string`] is formatted with
+    /// [`OnlyArgs::render_help`] and printed with [`help::print`].
+    fn short_help() -> ! {
+        help::print(
+            &Self::render_help(&Self::short_help_string()),
+            Self::help_output(),
+            Self::pager(),
+        );
+        std::process::exit(Self::help_exit_code());
+    }
+
+    /// Print the application name and version and exit the process.
+    ///
+    /// The version is printed with [`help::print`].
+    fn version() -> ! {
+        help::print(Self::VERSION, Self::help_output(), false);
+        std::process::exit(Self::help_exit_code());
+    }
+
+    /// Print the condensed application name and version and exit the process.
+    ///
+    /// The version is printed with [`help::print`].
+    fn short_version() -> ! {
+        help::print(Self::SHORT_VERSION, Self::help_output(), false);
+        std::process::exit(Self::help_exit_code());
+    }
+}
+
+impl CliError {
+    /// Create an error for an unknown argument, suggesting the closest of the `known` argument
+    /// names.
+    ///
+    /// Returns [`CliError::UnknownSuggestion`] if one of the names is a likely match, or
+    /// [`CliError::Unknown`] otherwise. See [`suggest::did_you_mean`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::CliError;
+    ///
+    /// let err = CliError::unknown("--outpt".into(), &["--help", "--output"]);
+    ///
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Unknown argument `--outpt`. Did you mean `--output`?",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn unknown(arg: OsString, known: &[&str]) -> Self {
+        let suggestion = arg
+            .to_str()
+            .and_then(|name| suggest::did_you_mean(name, known));
+
+        match suggestion {
+            Some(suggestion) => Self::UnknownSuggestion {
+                argument: arg,
+                suggestion: suggestion.to_string(),
+            },
+            None => Self::Unknown { argument: arg },
+        }
+    }
+
+    /// Create an error for an argument that was rejected by the application.
+    ///
+    /// Custom parsers, validators, and checks that run after parsing can use this to report
+    /// problems the same way as the built-in errors. The `error` can be any error type, or a
+    /// message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::CliError;
+    ///
+    /// fn check_width(width: i32) -> Result<i32, CliError> {
+    ///     if width > 0 {
+    ///         Ok(width)
+    ///     } else {
+    ///         Err(CliError::custom("--width", "must be greater than zero"))
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     check_width(0).unwrap_err().to_string(),
+    ///     "Invalid argument `--width`: must be greater than zero",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn custom<N, E>(arg: N, error: E) -> Self
+    where
+        N: Into<String>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self::Custom {
+            argument: arg.into(),
+            source: Arc::from(error.into()),
+        }
+    }
+
+    /// Attach the path of the file that failed to parse to a `ParseConfigError`.
+    #[cfg(any(feature = "json", feature = "toml", feature = "dotenv"))]
+    pub(crate) fn in_file(self, path: &std::path::Path) -> Self {
+        match self {
+            Self::ParseConfigError { message, .. } => Self::ParseConfigError {
+                path: Some(path.to_path_buf()),
+                message,
+            },
+            err => err,
+        }
+    }
+
+    /// Replace the long argument names in this error with labels from `spec`, like
+    /// `-w/--width <INTEGER>`. Other names, like environment variables, are unchanged.
+    pub(crate) fn labeled(&self, spec: &[ArgSpec]) -> Self {
+        let label = |argument: &mut String| {
+            let arg = spec.iter().find(|arg| {
+                !matches!(arg.kind, ArgKind::Positional(_))
+                    && argument.strip_prefix("--") == Some(arg.name)
+            });
+            if let Some(arg) = arg {
+                if let Some(ch) = arg.short {
+                    *argument = format!("-{ch}/{argument}");
+                }
+                if let Some(value_name) = arg.value_name {
+                    *argument = format!("{argument} <{value_name}>");
+                }
+            }
+        };
+
+        let mut err = self.clone();
+        match &mut err {
+            Self::MissingValue { argument }
+            | Self::MissingRequired { argument }
+            | Self::ParseBoolError { argument, .. }
+            | Self::ParseCharError { argument, .. }
+            | Self::ParseFloatError { argument, .. }
+            | Self::ParseIntError { argument, .. }
+            | Self::ParseStrError { argument, .. }
+            | Self::InvalidChoice { argument, .. }
+            | Self::PathNotFound { argument, .. }
+            | Self::NotAFile { argument, .. }
+            | Self::NotADirectory { argument, .. }
+            | Self::Custom { argument, .. } => label(argument),
+            #[cfg(feature = "url")]
+            Self::ParseUrlError { argument, .. } => label(argument),
+            #[cfg(feature = "uuid")]
+            Self::ParseUuidError { argument, .. } => label(argument),
+            #[cfg(feature = "semver")]
+            Self::ParseVersionError { argument, .. } => label(argument),
+            Self::Conflict { argument, other } => {
+                label(argument);
+                label(other);
+            }
+            Self::Multiple { errors } => {
+                for err in errors {
+                    *err = err.labeled(spec);
+                }
+            }
+            Self::At { error, .. } => **error = error.labeled(spec),
+            _ => (),
+        }
+
+        err
+    }
+
+    /// Attach the index and raw text of the argument that caused this error.
+    ///
+    /// Errors that already have a position are returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::CliError;
+    ///
+    /// let err = CliError::MissingValue {
+    ///     argument: "--width".to_string(),
+    /// };
+    /// let err = err.at(2, "-w");
+    ///
+    /// assert_eq!(err.position(), Some(2));
+    /// assert_eq!(err.token(), Some("-w".as_ref()));
+    /// assert_eq!(err.to_string(), "Missing value for `--width` (argument 3)");
+    /// ```
+    #[must_use]
+    pub fn at<T>(self, index: usize, token: T) -> Self
+    where
+        T: Into<OsString>,
+    {
+        match self {
+            Self::At { .. } => self,
+            error => Self::At {
+                index,
+                token: token.into(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Get the index of the argument that caused this error, if it is known.
+    ///
+    /// Errors from parsers generated by `onlyargs_derive` have a position when they are caused by
+    /// a command line argument. Errors from other sources, like environment variables and missing
+    /// required arguments, do not.
+    #[must_use]
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Self::At { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Get the raw command line argument that caused this error, if it is known.
+    ///
+    /// This is the argument at [`CliError::position`], like the value that failed to parse, or
+    /// the option that is missing its value.
+    #[must_use]
+    pub fn token(&self) -> Option<&OsStr> {
+        match self {
+            Self::At { token, .. } => Some(token),
+            _ => None,
+        }
+    }
+
+    /// Get the underlying error without its position.
+    #[must_use]
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::At { error, .. } => error,
+            error => error,
+        }
+    }
+
+    /// Get the category of this error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::{CliError, ErrorKind};
+    ///
+    /// let err = CliError::unknown("--outpt".into(), &["--output"]).at(0, "--outpt");
+    ///
+    /// assert_eq!(err.kind(), ErrorKind::Unknown);
+    /// ```
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::MissingValue { .. } => ErrorKind::MissingValue,
+            Self::MissingRequired { .. } => ErrorKind::MissingRequired,
+            Self::ParseBoolError { .. } => ErrorKind::ParseBoolError,
+            Self::ParseCharError { .. } => ErrorKind::ParseCharError,
+            Self::ParseFloatError { .. } => ErrorKind::ParseFloatError,
+            Self::ParseIntError { .. } => ErrorKind::ParseIntError,
+            Self::ParseStrError { .. } => ErrorKind::ParseStrError,
+            #[cfg(feature = "url")]
+            Self::ParseUrlError { .. } => ErrorKind::ParseUrlError,
+            #[cfg(feature = "uuid")]
+            Self::ParseUuidError { .. } => ErrorKind::ParseUuidError,
+            #[cfg(feature = "semver")]
+            Self::ParseVersionError { .. } => ErrorKind::ParseVersionError,
+            Self::InvalidChoice { .. } => ErrorKind::InvalidChoice,
+            Self::Conflict { .. } => ErrorKind::Conflict,
+            Self::PathNotFound { .. } => ErrorKind::PathNotFound,
+            Self::NotAFile { .. } => ErrorKind::NotAFile,
+            Self::NotADirectory { .. } => ErrorKind::NotADirectory,
+            Self::Unknown { .. } | Self::UnknownSuggestion { .. } => ErrorKind::Unknown,
+            Self::ConfigIoError { .. } => ErrorKind::ConfigIoError,
+            Self::ParseConfigError { .. } => ErrorKind::ParseConfigError,
+            Self::AliasRecursion { .. } => ErrorKind::AliasRecursion,
+            Self::ParseAliasError { .. } => ErrorKind::ParseAliasError,
+            Self::Multiple { .. } => ErrorKind::Multiple,
+            Self::Custom { .. } => ErrorKind::Custom,
+            Self::At { error, .. } => error.kind(),
+        }
+    }
+}
+
+impl Display for CliError {
+    #[allow(clippy::too_many_lines)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingValue { argument } => {
+                write!(f, "Missing value for `{argument}`")
+            }
+            Self::MissingRequired { argument } => {
+                write!(f, "Missing required argument `{argument}`")
+            }
+            Self::ParseBoolError {
+                argument, value, ..
+            } => write!(
+                f,
+                "Bool parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            Self::ParseCharError {
+                argument, value, ..
+            } => write!(
+                f,
+                "Char parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            Self::ParseFloatError {
+                argument, value, ..
+            } => write!(
+                f,
+                "Float parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            Self::ParseIntError {
+                argument, value, ..
+            } => write!(
+                f,
+                "Int parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            Self::ParseStrError { argument, value } => write!(
+                f,
+                "String parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            #[cfg(feature = "url")]
+            Self::ParseUrlError {
+                argument, value, ..
+            } => write!(
+                f,
+                "URL parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            #[cfg(feature = "uuid")]
+            Self::ParseUuidError {
+                argument, value, ..
+            } => write!(
+                f,
+                "UUID parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            #[cfg(feature = "semver")]
+            Self::ParseVersionError {
+                argument, value, ..
+            } => write!(
+                f,
+                "Version parsing error for argument `{argument}`: value `{value}`",
+                value = Lossy(value),
+            ),
+            Self::InvalidChoice {
+                argument,
+                value,
+                choices,
+            } => write!(
+                f,
+                "Invalid value for argument `{argument}`: value `{value}`, possible values: {}",
+                choices.join(", "),
+                value = Lossy(value),
+            ),
+            Self::Conflict { argument, other } => {
+                write!(f, "Argument `{argument}` cannot be used with `{other}`")
+            }
+            Self::PathNotFound { argument, path } => write!(
+                f,
+                "Path not found for argument `{argument}`: `{}`",
+                path.display(),
+            ),
+            Self::NotAFile { argument, path } => write!(
+                f,
+                "Path for argument `{argument}` is not a file: `{}`",
+                path.display(),
+            ),
+            Self::NotADirectory { argument, path } => write!(
+                f,
+                "Path for argument `{argument}` is not a directory: `{}`",
+                path.display(),
+            ),
+            Self::Unknown { argument } => write!(
+                f,
+                "Unknown argument `{argument}`",
+                argument = Lossy(argument)
+            ),
+            Self::UnknownSuggestion {
+                argument,
+                suggestion,
+            } => write!(
+                f,
+                "Unknown argument `{argument}`. Did you mean `{suggestion}`?",
+                argument = Lossy(argument),
+            ),
+            Self::ConfigIoError { path, .. } => {
+                write!(f, "Config file read error for path `{}`", path.display())
+            }
+            Self::ParseConfigError {
+                path: Some(path),
+                message,
+            } => write!(
+                f,
+                "Config file parsing error for path `{}`: {message}",
+                path.display(),
+            ),
+            Self::ParseConfigError {
+                path: None,
+                message,
+            } => {
+                write!(f, "Config file parsing error: {message}")
+            }
+            Self::AliasRecursion { alias } => write!(f, "Alias `{alias}` expands to itself"),
+            Self::ParseAliasError { alias, message } => {
+                write!(f, "Alias parsing error for `{alias}`: {message}")
+            }
+            Self::Multiple { errors } => {
+                write!(f, "{} argument errors:", errors.len())?;
+                for err in errors {
+                    write!(f, "\n  {err}")?;
+                }
+
+                Ok(())
+            }
+            Self::Custom { argument, source } => {
+                write!(f, "Invalid argument `{argument}`: {source}")
+            }
+            Self::At { index, error, .. } => write!(f, "{error} (argument {})", index + 1),
+        }
+    }
+}
+
+/// Displays an `OsStr` without quotes or escapes.
+///
+/// Invalid UTF-8 bytes are shown as `\xNN` escapes on Unix, and as `U+FFFD` elsewhere.
+pub(crate) struct Lossy<'a>(pub(crate) &'a OsStr);
+
+impl Display for Lossy<'_> {
+    #[cfg(unix)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let mut bytes = self.0.as_bytes();
+        loop {
+            match std::str::from_utf8(bytes) {
+                Ok(text) => return f.write_str(text),
+                Err(err) => {
+                    let (valid, rest) = bytes.split_at(err.valid_up_to());
+                    let invalid = err.error_len().unwrap_or(rest.len());
+
+                    f.write_str(std::str::from_utf8(valid).unwrap_or_default())?;
+                    for byte in &rest[..invalid] {
+                        write!(f, "\\x{byte:02X}")?;
+                    }
+                    bytes = &rest[invalid..];
+                }
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.to_string_lossy())
+    }
+}
+
+impl PartialEq for CliError {
+    #[allow(clippy::too_many_lines)]
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::MissingValue { argument: a }, Self::MissingValue { argument: b })
+            | (Self::MissingRequired { argument: a }, Self::MissingRequired { argument: b })
+            | (Self::AliasRecursion { alias: a }, Self::AliasRecursion { alias: b }) => a == b,
+            (
+                Self::ParseConfigError {
+                    path: a_path,
+                    message: a,
+                },
+                Self::ParseConfigError {
+                    path: b_path,
+                    message: b,
+                },
+            ) => a_path == b_path && a == b,
+            (
+                Self::ParseBoolError {
+                    argument: a,
+                    value: a_value,
+                    source: a_source,
+                },
+                Self::ParseBoolError {
+                    argument: b,
+                    value: b_value,
+                    source: b_source,
+                },
+            ) => a == b && a_value == b_value && a_source == b_source,
+            (
+                Self::ParseCharError {
+                    argument: a,
+                    value: a_value,
+                    source: a_source,
+                },
+                Self::ParseCharError {
+                    argument: b,
+                    value: b_value,
+                    source: b_source,
+                },
+            ) => a == b && a_value == b_value && a_source == b_source,
+            (
+                Self::ParseFloatError {
+                    argument: a,
+                    value: a_value,
+                    source: a_source,
+                },
+                Self::ParseFloatError {
+                    argument: b,
+                    value: b_value,
+                    source: b_source,
+                },
+            ) => a == b && a_value == b_value && a_source == b_source,
+            (
+                Self::ParseIntError {
+                    argument: a,
+                    value: a_value,
+                    source: a_source,
+                },
+                Self::ParseIntError {
+                    argument: b,
+                    value: b_value,
+                    source: b_source,
+                },
+            ) => a == b && a_value == b_value && a_source == b_source,
+            (
+                Self::ParseStrError {
+                    argument: a,
+                    value: a_value,
+                },
+                Self::ParseStrError {
+                    argument: b,
+                    value: b_value,
+                },
+            ) => a == b && a_value == b_value,
+            #[cfg(feature = "url")]
+            (
+                Self::ParseUrlError {
+                    argument: a,
+                    value: a_value,
+                    source: a_source,
+                },
+                Self::ParseUrlError {
+                    argument: b,
+                    value: b_value,
+                    source: b_source,
+                },
+            ) => a == b && a_value == b_value && a_source == b_source,
+            #[cfg(feature = "uuid")]
+            (
+                Self::ParseUuidError {
+                    argument: a,
+                    value: a_value,
+                    source: a_source,
+                },
+                Self::ParseUuidError {
+                    argument: b,
+                    value: b_value,
+                    source: b_source,
+                },
+            ) => a == b && a_value == b_value && a_source == b_source,
+            #[cfg(feature = "semver")]
+            (
+                Self::ParseVersionError {
+                    argument: a,
+                    value: a_value,
+                    source: a_source,
+                },
+                Self::ParseVersionError {
+                    argument: b,
+                    value: b_value,
+                    source: b_source,
+                },
+            ) => a == b && a_value == b_value && a_source.to_string() == b_source.to_string(),
+            (
+                Self::InvalidChoice {
+                    argument: a,
+                    value: a_value,
+                    choices: a_choices,
+                },
+                Self::InvalidChoice {
+                    argument: b,
+                    value: b_value,
+                    choices: b_choices,
+                },
+            ) => a == b && a_value == b_value && a_choices == b_choices,
+            (
+                Self::Conflict {
+                    argument: a,
+                    other: a_other,
+                },
+                Self::Conflict {
+                    argument: b,
+                    other: b_other,
+                },
+            ) => a == b && a_other == b_other,
+            (
+                Self::PathNotFound {
+                    argument: a,
+                    path: a_path,
+                },
+                Self::PathNotFound {
+                    argument: b,
+                    path: b_path,
+                },
+            )
+            | (
+                Self::NotAFile {
+                    argument: a,
+                    path: a_path,
+                },
+                Self::NotAFile {
+                    argument: b,
+                    path: b_path,
+                },
+            )
+            | (
+                Self::NotADirectory {
+                    argument: a,
+                    path: a_path,
+                },
+                Self::NotADirectory {
+                    argument: b,
+                    path: b_path,
+                },
+            ) => a == b && a_path == b_path,
+            (Self::Unknown { argument: a }, Self::Unknown { argument: b }) => a == b,
+            (
+                Self::UnknownSuggestion {
+                    argument: a,
+                    suggestion: a_suggestion,
+                },
+                Self::UnknownSuggestion {
+                    argument: b,
+                    suggestion: b_suggestion,
+                },
+            ) => a == b && a_suggestion == b_suggestion,
+            (
+                Self::ConfigIoError {
+                    path: a,
+                    source: a_source,
+                },
+                Self::ConfigIoError {
+                    path: b,
+                    source: b_source,
+                },
+            ) => a == b && a_source.kind() == b_source.kind(),
+            (
+                Self::ParseAliasError {
+                    alias: a,
+                    message: a_message,
+                },
+                Self::ParseAliasError {
+                    alias: b,
+                    message: b_message,
+                },
+            ) => a == b && a_message == b_message,
+            (Self::Multiple { errors: a }, Self::Multiple { errors: b }) => a == b,
+            (
+                Self::Custom {
+                    argument: a,
+                    source: a_source,
+                },
+                Self::Custom {
+                    argument: b,
+                    source: b_source,
+                },
+            ) => a == b && a_source.to_string() == b_source.to_string(),
+            (Self::At { error, .. }, other) | (other, Self::At { error, .. }) => **error == *other,
+            _ => false,
+        }
+    }
+}
+
+impl std::error::Error for CliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ParseBoolError { source, .. } => Some(source),
+            Self::ParseCharError { source, .. } => Some(source),
+            Self::ParseFloatError { source, .. } => Some(source),
+            Self::ParseIntError { source, .. } => Some(source),
+            #[cfg(feature = "url")]
+            Self::ParseUrlError { source, .. } => Some(source),
+            #[cfg(feature = "uuid")]
+            Self::ParseUuidError { source, .. } => Some(source),
+            #[cfg(feature = "semver")]
+            Self::ParseVersionError { source, .. } => Some(&**source),
+            Self::ConfigIoError { source, .. } => Some(&**source),
+            Self::Custom { source, .. } => source.source(),
+            Self::At { error, .. } => error.source(),
+            _ => None,
+        }
+    }
+}
+
+/// Non-fatal problems found while parsing arguments.
+///
+/// Warnings do not stop the parser. They are returned by [`OnlyArgs::parse_with_warnings`], or
+/// printed to `stderr` with [`OnlyArgs::warn`] by the other parse methods.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// A deprecated argument was used on the command line.
+    Deprecated {
+        argument: String,
+        note: Option<String>,
+    },
+
+    /// A custom warning, e.g. when a value was clamped to a valid range.
+    Custom { argument: String, message: String },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deprecated {
+                argument,
+                note: Some(note),
+            } => write!(f, "`{argument}` is deprecated: {note}"),
+            Self::Deprecated { argument, .. } => write!(f, "`{argument}` is deprecated"),
+            Self::Custom { argument, message } => write!(f, "`{argument}`: {message}"),
+        }
+    }
+}
+
+/// A description of an argument, from [`OnlyArgs::SPEC`].
+///
+/// New fields may be added, so descriptions are built with [`ArgSpec::new`] and the `with_*`
+/// methods.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::{ArgKind, ArgSpec, ValueType};
+///
+/// const WIDTH: ArgSpec = ArgSpec::new("width", ArgKind::Option(ValueType::Integer), "Set the width.")
+///     .with_short('w')
+///     .with_value_name("INTEGER")
+///     .with_default("80");
+///
+/// assert_eq!(WIDTH.short, Some('w'));
+/// assert!(!WIDTH.required);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ArgSpec {
+    /// The long name without the leading hyphens, like `width`. Positional arguments use the
+    /// field name, or the lowercase value name for the fields of a tuple struct.
+    pub name: &'static str,
+
+    /// The short name without the leading hyphen.
+    pub short: Option<char>,
+
+    /// Whether the argument is a flag, an option, or positional.
+    pub kind: ArgKind,
+
+    /// The value placeholder shown in the help text, like `INTEGER`. This is `None` for flags.
+    pub value_name: Option<&'static str>,
+
+    /// Whether the argument can be given multiple times.
+    pub multiple: bool,
+
+    /// Whether the argument must be given.
+    pub required: bool,
+
+    /// The default value, like `80`. Flags that are set by default have the value `true`.
+    pub default: Option<&'static str>,
+
+    /// The description from the help text.
+    pub help: &'static str,
+
+    /// What the shell completes for the value.
+    pub complete: Complete,
+}
+
+impl ArgSpec {
+    /// Describe an argument with its long name, kind, and help text.
+    ///
+    /// The argument has no short name, value name, or default, can be given once, is not
+    /// required, and completes with [`Complete::Default`].
+    #[must_use]
+    pub const fn new(name: &'static str, kind: ArgKind, help: &'static str) -> Self {
+        Self {
+            name,
+            short: None,
+            kind,
+            value_name: None,
+            multiple: false,
+            required: false,
+            default: None,
+            help,
+            complete: Complete::Default,
+        }
+    }
+
+    /// Set the short name.
+    #[must_use]
+    pub const fn with_short(mut self, short: char) -> Self {
+        self.short = Some(short);
+        self
+    }
+
+    /// Set the value placeholder shown in the help text.
+    #[must_use]
+    pub const fn with_value_name(mut self, value_name: &'static str) -> Self {
+        self.value_name = Some(value_name);
+        self
+    }
+
+    /// Set whether the argument can be given multiple times.
+    #[must_use]
+    pub const fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Set whether the argument must be given.
+    #[must_use]
+    pub const fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Set the default value.
+    #[must_use]
+    pub const fn with_default(mut self, default: &'static str) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Set what the shell completes for the value.
+    #[must_use]
+    pub const fn with_complete(mut self, complete: Complete) -> Self {
+        self.complete = complete;
+        self
+    }
+}
+
+/// The kind of argument described by an [`ArgSpec`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ArgKind {
+    /// A flag that does not take a value.
+    Flag,
+
+    /// An option that takes a value of the given type.
+    Option(ValueType),
+
+    /// A positional argument of the given type.
+    Positional(ValueType),
+}
+
+/// The type of value that an option or positional argument takes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ValueType {
+    /// A floating point number.
+    Float,
+
+    /// An integer.
+    Integer,
+
+    /// A string with platform-specific encoding.
+    OsString,
+
+    /// A file system path.
+    Path,
+
+    /// A UTF-8 string.
+    String,
+}
+
+/// What a shell completion script completes for the value of an [`ArgSpec`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Complete {
+    /// Complete file names for [`ValueType::Path`], and nothing for other value types.
+    Default,
+
+    /// Complete file names.
+    File,
+
+    /// Complete directory names.
+    Dir,
+
+    /// Complete host names.
+    Hostname,
+
+    /// Ask the application for candidates with [`OnlyArgs::complete`].
+    Callback,
+}
+
+/// The values of an option that can be given multiple times, in the order they were given.
+///
+/// This is like a `Vec<T>`, with accessors for tools whose behavior depends on how many times an
+/// option was repeated, or only on its last value. `onlyargs_derive` accepts `Occurrences<T>` for
+/// any `T` that it accepts in `Vec<T>`.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::Occurrences;
+///
+/// let includes = Occurrences::from(vec!["src", "tests"]);
+///
+/// assert_eq!(includes.count(), 2);
+/// assert_eq!(includes.first(), Some(&"src"));
+/// assert_eq!(includes.last(), Some(&"tests"));
+/// assert_eq!(includes.values(), ["src", "tests"]);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Occurrences<T> {
+    values: Vec<T>,
+}
+
+impl<T> Occurrences<T> {
+    /// Create an empty list of occurrences.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    /// Record another occurrence of the option.
+    pub fn push(&mut self, value: T) {
+        self.values.push(value);
+    }
+
+    /// The number of times the option was given.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether the option was not given.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The value of the first occurrence.
+    #[must_use]
+    pub fn first(&self) -> Option<&T> {
+        self.values.first()
+    }
+
+    /// The value of the last occurrence.
+    #[must_use]
+    pub fn last(&self) -> Option<&T> {
+        self.values.last()
+    }
+
+    /// The values of all occurrences.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Iterate over the values of all occurrences.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    /// Convert into the values of all occurrences.
+    #[must_use]
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<T> Default for Occurrences<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<T>> for Occurrences<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self { values }
+    }
+}
+
+impl<T> From<Occurrences<T>> for Vec<T> {
+    fn from(occurrences: Occurrences<T>) -> Self {
+        occurrences.values
+    }
+}
+
+impl<T> FromIterator<T> for Occurrences<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> IntoIterator for Occurrences<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Occurrences<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+/// Converts an argument parsing error to the conventional exit code for usage errors, `2`.
+///
+/// Use [`Report`] to exit with a different code.
+impl From<CliError> for ExitCode {
+    fn from(_: CliError) -> Self {
+        Self::from(2)
+    }
+}
+
+/// Format an argument parsing error for the user.
+///
+/// This produces the conventional error block: the error and its causes, the `usage` line, and a
+/// hint to run with `--help`. The usage section is omitted if `usage` is empty.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::CliError;
+///
+/// let err = CliError::MissingRequired {
+///     argument: "--name".into(),
+/// };
+///
+/// assert_eq!(
+///     onlyargs::format_error(&err, "myapp --name <STRING> [flags]"),
+///     "Error: Missing required argument `--name`\n\
+///     \n\
+///     Usage:\n  \
+///       myapp --name <STRING> [flags]\n\
+///     \n\
+///     For more information, try `--help`.\n",
+/// );
+/// ```
+#[must_use]
+pub fn format_error(err: &CliError, usage: &str) -> String {
+    use std::error::Error as _;
+    use std::fmt::Write as _;
+
+    let mut output = format!("Error: {err}\n");
+    let mut source = err.source();
+    while let Some(err) = source {
+        writeln!(output, "  Caused by: {err}").unwrap();
+        source = err.source();
+    }
+    if !usage.is_empty() {
+        write!(output, "\nUsage:\n  {usage}\n").unwrap();
+    }
+    output.push_str("\nFor more information, try `--help`.\n");
 
-    /// An unknown argument was provided.
-    Unknown(OsString),
+    output
 }
 
-/// The primary argument parser trait.
+/// An argument parsing error that is reported when returned from `main`.
 ///
-/// This trait can be derived with the [`onlyargs_derive`](https://docs.rs/onlyargs_derive) crate.
+/// `Report` implements [`Debug`](std::fmt::Debug) with the message from [`format_error`], so it
+/// can be returned from `main` in a `Result`. The standard library prints the message after an
+/// `Error:` label, and exits with status `1`:
 ///
-/// See the [`parse`] function for more information.
-pub trait OnlyArgs {
-    /// The application help string.
-    const HELP: &'static str = concat!(
-        env!("CARGO_PKG_NAME"),
-        " v",
-        env!("CARGO_PKG_VERSION"),
-        "\n",
-        env!("CARGO_PKG_DESCRIPTION"),
-        "\n",
-    );
+/// ```no_run
+/// # use onlyargs::OnlyArgs;
+/// # #[derive(Debug)]
+/// # struct Args;
+/// # impl OnlyArgs for Args {
+/// #     fn parse(_: Vec<std::ffi::OsString>) -> Result<Self, onlyargs::CliError> { Ok(Self) }
+/// # }
+/// fn main() -> Result<(), onlyargs::Report> {
+///     let args: Args = onlyargs::parse().map_err(onlyargs::Report::new::<Args>)?;
+///
+///     // ...
+///     # drop(args);
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// `Report` also implements [`Termination`], which prints the message and returns the configured
+/// exit code. This is useful when `main` returns an [`ExitCode`]:
+///
+/// ```no_run
+/// # use onlyargs::OnlyArgs;
+/// # #[derive(Debug)]
+/// # struct Args;
+/// # impl OnlyArgs for Args {
+/// #     fn parse(_: Vec<std::ffi::OsString>) -> Result<Self, onlyargs::CliError> { Ok(Self) }
+/// # }
+/// use onlyargs::Report;
+/// use std::process::{ExitCode, Termination as _};
+///
+/// fn run() -> Result<(), Report> {
+///     let args: Args = onlyargs::parse()?;
+///
+///     // ...
+///     # drop(args);
+///
+///     Ok(())
+/// }
+///
+/// fn main() -> ExitCode {
+///     match run() {
+///         Ok(()) => ExitCode::SUCCESS,
+///         Err(report) => report.with_exit_code(onlyargs::EX_USAGE as u8).report(),
+///     }
+/// }
+/// ```
+pub struct Report {
+    error: CliError,
+    spec: &'static [ArgSpec],
+    usage: String,
+    exit_code: u8,
+}
 
-    /// The application name and version.
-    const VERSION: &'static str = concat!(
-        env!("CARGO_PKG_NAME"),
-        " v",
-        env!("CARGO_PKG_VERSION"),
-        "\n",
-    );
+impl Report {
+    /// Create a report with the [argument labels](OnlyArgs::format_error),
+    /// [usage line](OnlyArgs::USAGE), and [exit code](OnlyArgs::error_exit_code) from `T`.
+    #[must_use]
+    pub fn new<T: OnlyArgs>(error: CliError) -> Self {
+        Self {
+            error,
+            spec: T::SPEC,
+            usage: T::USAGE.replace("{bin_name}", &bin_name()),
+            exit_code: u8::try_from(T::error_exit_code()).unwrap_or(1),
+        }
+    }
 
-    /// Construct a type that implements this trait.
-    ///
-    /// Each argument is provided as an [`OsString`].
-    ///
-    /// # Errors
-    ///
-    /// Returns `Err` if the command line arguments cannot be parsed to `Self`.
-    fn parse(args: Vec<OsString>) -> Result<Self, CliError>
-    where
-        Self: Sized;
+    /// Replace the usage line shown with the error.
+    #[must_use]
+    pub fn with_usage(mut self, usage: impl Into<String>) -> Self {
+        self.usage = usage.into();
+        self
+    }
 
-    /// Print the application help string and exit the process.
-    fn help() -> ! {
-        eprintln!("{}", Self::HELP);
-        std::process::exit(0);
+    /// Replace the exit code returned by [`Termination::report`].
+    #[must_use]
+    pub fn with_exit_code(mut self, exit_code: u8) -> Self {
+        self.exit_code = exit_code;
+        self
     }
 
-    /// Print the application name and version and exit the process.
-    fn version() -> ! {
-        eprintln!("{}", Self::VERSION);
-        std::process::exit(0);
+    /// The argument parsing error.
+    #[must_use]
+    pub fn error(&self) -> &CliError {
+        &self.error
+    }
+
+    /// Consume the report and return the argument parsing error.
+    #[must_use]
+    pub fn into_error(self) -> CliError {
+        self.error
     }
 }
 
-impl Display for CliError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::MissingValue(arg) => write!(f, "Missing value for argument `{arg}`"),
-            Self::MissingRequired(arg) => write!(f, "Missing required argument `{arg}`"),
-            Self::ParseBoolError(arg, value, _) => write!(
-                f,
-                "Bool parsing error for argument `{arg}`: value={value:?}"
-            ),
-            Self::ParseCharError(arg, value, _) => write!(
-                f,
-                "Char parsing error for argument `{arg}`: value={value:?}"
-            ),
-            Self::ParseFloatError(arg, value, _) => write!(
-                f,
-                "Float parsing error for argument `{arg}`: value={value:?}"
-            ),
-            Self::ParseIntError(arg, value, _) => {
-                write!(f, "Int parsing error for argument `{arg}`: value={value:?}")
-            }
-            Self::ParseStrError(arg, value) => write!(
-                f,
-                "String parsing error for argument `{arg}`: value={value:?}"
-            ),
-            Self::Unknown(arg) => write!(f, "Unknown argument: {arg:?}"),
+/// Creates a report without a usage line, which exits with code `2`.
+impl From<CliError> for Report {
+    fn from(error: CliError) -> Self {
+        Self {
+            error,
+            spec: &[],
+            usage: String::new(),
+            exit_code: 2,
         }
     }
 }
 
-impl std::error::Error for CliError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            Self::ParseBoolError(_, _, err) => Some(err),
-            Self::ParseCharError(_, _, err) => Some(err),
-            Self::ParseFloatError(_, _, err) => Some(err),
-            Self::ParseIntError(_, _, err) => Some(err),
-            _ => None,
-        }
+impl std::fmt::Debug for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The standard library adds its own `Error:` label and newline.
+        let message = format_error(&self.error.labeled(self.spec), &self.usage);
+        let message = message.strip_prefix("Error: ").unwrap_or(&message);
+
+        f.write_str(message.trim_end())
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.labeled(self.spec).fmt(f)
     }
 }
 
+impl Termination for Report {
+    fn report(self) -> ExitCode {
+        eprint_error(&format_error(&self.error.labeled(self.spec), &self.usage));
+
+        ExitCode::from(self.exit_code)
+    }
+}
+
+/// Print a formatted error message to `stderr`, with colors if they are enabled.
+fn eprint_error(message: &str) {
+    #[cfg(feature = "color")]
+    if color::enabled() {
+        eprint!("{}", color::style(message));
+        return;
+    }
+
+    eprint!("{message}");
+}
+
+/// The name of the binary, from the first command line argument.
+///
+/// This is what the `{bin_name}` placeholder in [`OnlyArgs::HELP`] is replaced with. It is empty if
+/// the first argument is not available.
+///
+/// # Example
+///
+/// ```
+/// let usage = "Usage: {bin_name} [flags]".replace("{bin_name}", &onlyargs::bin_name());
+/// ```
+#[must_use]
+pub fn bin_name() -> String {
+    env::args_os()
+        .next()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+// The file name of the running binary, without its directory.
+fn short_bin_name() -> String {
+    let bin_name = bin_name();
+
+    std::path::Path::new(&bin_name).file_name().map_or_else(
+        || bin_name.clone(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
 /// Type constructor for argument parser.
 ///
 /// Given a type that implements [`OnlyArgs`], this function will construct the type from the
 /// current environment.
 ///
+/// When a completion script asks for candidates, they are printed and the application exits. See
+/// [`completion::respond`]. The hidden `--generate` argument is handled the same way, see
+/// [`generate::respond`].
+///
 /// # Errors
 ///
 /// Returns `Err` if arguments from the environment cannot be parsed to `T`.
@@ -173,7 +2163,7 @@ impl std::error::Error for CliError {
 ///                     verbose = true;
 ///                 }
 ///                 Some("--") => break,
-///                 _ => return Err(CliError::Unknown(arg)),
+///                 _ => return Err(CliError::Unknown { argument: arg }),
 ///             }
 ///         }
 ///
@@ -192,7 +2182,322 @@ impl std::error::Error for CliError {
 /// # Ok::<(), CliError>(())
 /// ```
 pub fn parse<T: OnlyArgs>() -> Result<T, CliError> {
-    T::parse(env::args_os().skip(1).collect())
+    completion::respond::<T>();
+    generate::respond::<T>();
+
+    T::parse_iter(env::args_os().skip(1))
+}
+
+/// Type constructor for argument parser with the given arguments.
+///
+/// This is like [`parse`], but the arguments are taken from `args` instead of the environment.
+/// They can be any type that converts to [`OsString`], which is convenient for tests. The first
+/// item is an argument, not the program name.
+///
+/// # Errors
+///
+/// Returns `Err` if `args` cannot be parsed to `T`.
+///
+/// # Example
+///
+/// ```
+/// # use onlyargs::{CliError, OnlyArgs};
+/// # #[derive(Debug)]
+/// # struct Args {
+/// #     width: i32,
+/// # }
+/// # impl OnlyArgs for Args {
+/// #     fn parse(args: Vec<std::ffi::OsString>) -> Result<Self, CliError> {
+/// #         let width = args.get(1).and_then(|arg| arg.to_str()?.parse().ok()).unwrap_or_default();
+/// #         Ok(Self { width })
+/// #     }
+/// # }
+/// let args: Args = onlyargs::parse_from(["--width", "3"])?;
+///
+/// assert_eq!(args.width, 3);
+/// # Ok::<_, CliError>(())
+/// ```
+pub fn parse_from<T, I>(args: I) -> Result<T, CliError>
+where
+    T: OnlyArgs,
+    I: IntoIterator,
+    I::Item: Into<OsString>,
+{
+    T::parse_iter(args.into_iter().map(Into::into))
+}
+
+/// Type constructor for argument parser that exits on errors.
+///
+/// This is like [`parse`], but when the arguments cannot be parsed, the error is printed to
+/// `stderr` with the usage line and the process exits. See [`OnlyArgs::exit_with_error`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use onlyargs::OnlyArgs;
+/// # #[derive(Debug)]
+/// # struct Args {
+/// #     verbose: bool,
+/// # }
+/// # impl OnlyArgs for Args {
+/// #     fn parse(_: Vec<std::ffi::OsString>) -> Result<Self, onlyargs::CliError> {
+/// #         Ok(Self { verbose: false })
+/// #     }
+/// # }
+/// let args: Args = onlyargs::parse_or_exit();
+///
+/// if args.verbose {
+///     println!("Verbose output is enabled");
+/// }
+/// ```
+#[must_use]
+pub fn parse_or_exit<T: OnlyArgs>() -> T {
+    parse().unwrap_or_else(|err| T::exit_with_error(&err))
+}
+
+/// Type constructor for argument parser with custom [`Layers`].
+///
+/// This is like [`parse`], but arguments that are missing from the command line are looked up in
+/// `layers` instead of the default layers provided by [`OnlyArgs::layers`]. See the [`source`]
+/// module for more information.
+///
+/// # Errors
+///
+/// Returns `Err` if arguments from the environment cannot be parsed to `T`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use onlyargs::OnlyArgs;
+/// # struct Args;
+/// # impl OnlyArgs for Args {
+/// #     fn parse(_: Vec<std::ffi::OsString>) -> Result<Self, onlyargs::CliError> { Ok(Self) }
+/// # }
+/// use onlyargs::source::{Key, Source};
+/// use std::ffi::OsString;
+///
+/// struct Remote;
+///
+/// impl Source for Remote {
+///     fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+///         // Fetch the value from a remote configuration service...
+///         # vec![]
+///     }
+/// }
+///
+/// // Remote values have the lowest precedence.
+/// let layers = Args::layers()?.push(Remote);
+/// let args: Args = onlyargs::parse_with_layers(&layers)?;
+/// # Ok::<_, onlyargs::CliError>(())
+/// ```
+pub fn parse_with_layers<T: OnlyArgs>(layers: &Layers) -> Result<T, CliError> {
+    completion::respond::<T>();
+    generate::respond::<T>();
+
+    T::parse_with_layers(env::args_os().skip(1).collect(), layers)
+}
+
+/// Type constructor for argument parser with command aliases.
+///
+/// This is like [`parse`], but aliases in the first argument are expanded before parsing. See the
+/// [`alias`] module for more information.
+///
+/// # Errors
+///
+/// Returns `Err` if an alias cannot be expanded or arguments from the environment cannot be parsed
+/// to `T`.
+pub fn parse_with_aliases<T: OnlyArgs>(aliases: &Aliases) -> Result<T, CliError> {
+    completion::respond::<T>();
+    generate::respond::<T>();
+
+    T::parse(aliases.expand(env::args_os().skip(1).collect())?)
+}
+
+/// Split arguments at the first `--`.
+///
+/// Returns the arguments before the `--`, and the arguments after it. The `--` itself is not
+/// included in either list. If there is no `--`, all arguments are in the first list.
+///
+/// Wrapper programs can parse the first list and forward the second list verbatim to the program
+/// they run.
+///
+/// # Example
+///
+/// ```
+/// # use onlyargs::{CliError, OnlyArgs};
+/// # #[derive(Debug)]
+/// # struct Args;
+/// # impl OnlyArgs for Args {
+/// #     fn parse(_args: Vec<std::ffi::OsString>) -> Result<Self, CliError> {
+/// #         Ok(Self)
+/// #     }
+/// # }
+/// let (head, tail) = onlyargs::split_escape(["--verbose", "--", "cargo", "--", "test"].map(Into::into));
+///
+/// let args = Args::parse(head)?;
+/// assert_eq!(tail, ["cargo", "--", "test"]);
+/// # Ok::<_, CliError>(())
+/// ```
+pub fn split_escape<I>(args: I) -> (Vec<OsString>, Vec<OsString>)
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut args = args.into_iter();
+    let head = args.by_ref().take_while(|arg| arg != "--").collect();
+
+    (head, args.collect())
+}
+
+/// Parse an argument into a `String`.
+///
+/// This is the free function form of [`ArgExt::parse_str`](traits::ArgExt::parse_str), for
+/// implementations that prefer function calls over extension traits.
+///
+/// # Errors
+///
+/// Returns `Err` if the argument is `None` or not valid UTF-8.
+///
+/// # Example
+///
+/// ```
+/// # use std::ffi::OsString;
+/// let mut args = vec![OsString::from("--name"), OsString::from("Jay")].into_iter();
+///
+/// while let Some(arg) = args.next() {
+///     if let Some(name @ "--name") = arg.to_str() {
+///         assert_eq!(onlyargs::parse_str(name, args.next())?, "Jay");
+///     }
+/// }
+/// # Ok::<_, onlyargs::CliError>(())
+/// ```
+pub fn parse_str<N>(name: N, value: Option<OsString>) -> Result<String, CliError>
+where
+    N: Into<String>,
+{
+    traits::ArgExt::parse_str(value, name)
+}
+
+/// Parse an argument into a `PathBuf`.
+///
+/// This is the free function form of [`ArgExt::parse_path`](traits::ArgExt::parse_path).
+///
+/// # Errors
+///
+/// Returns `Err` if the argument is `None`.
+pub fn parse_path<N>(name: N, value: Option<OsString>) -> Result<PathBuf, CliError>
+where
+    N: Into<String>,
+{
+    traits::ArgExt::parse_path(value, name)
+}
+
+/// Parse an argument into an `OsString`.
+///
+/// This is the free function form of [`ArgExt::parse_osstr`](traits::ArgExt::parse_osstr).
+///
+/// # Errors
+///
+/// Returns `Err` if the argument is `None`.
+pub fn parse_osstr<N>(name: N, value: Option<OsString>) -> Result<OsString, CliError>
+where
+    N: Into<String>,
+{
+    traits::ArgExt::parse_osstr(value, name)
+}
+
+/// Parse an argument into a primitive integer.
+///
+/// This is the free function form of [`ArgExt::parse_int`](traits::ArgExt::parse_int).
+///
+/// # Errors
+///
+/// Returns `Err` if the argument is `None` or not a valid integer.
+pub fn parse_int<T, N>(name: N, value: Option<OsString>) -> Result<T, CliError>
+where
+    N: Into<String>,
+    T: FromStr<Err = ParseIntError>,
+{
+    traits::ArgExt::parse_int(value, name)
+}
+
+/// Parse an argument into a primitive floating point number.
+///
+/// This is the free function form of [`ArgExt::parse_float`](traits::ArgExt::parse_float).
+///
+/// # Errors
+///
+/// Returns `Err` if the argument is `None` or not a valid floating point number.
+pub fn parse_float<T, N>(name: N, value: Option<OsString>) -> Result<T, CliError>
+where
+    N: Into<String>,
+    T: FromStr<Err = ParseFloatError>,
+{
+    traits::ArgExt::parse_float(value, name)
+}
+
+/// Parse an argument into a `bool`.
+///
+/// This is the free function form of [`ArgExt::parse_bool`](traits::ArgExt::parse_bool).
+///
+/// # Errors
+///
+/// Returns `Err` if the argument is `None` or not a valid boolean.
+pub fn parse_bool<N>(name: N, value: Option<OsString>) -> Result<bool, CliError>
+where
+    N: Into<String>,
+{
+    traits::ArgExt::parse_bool(value, name)
+}
+
+/// Parse an argument into a single `char`.
+///
+/// This is the free function form of [`ArgExt::parse_char`](traits::ArgExt::parse_char).
+///
+/// # Errors
+///
+/// Returns `Err` if the argument is `None` or not exactly one character.
+pub fn parse_char<N>(name: N, value: Option<OsString>) -> Result<char, CliError>
+where
+    N: Into<String>,
+{
+    traits::ArgExt::parse_char(value, name)
+}
+
+/// Unwrap a required argument, unless `skip` is `true`.
+///
+/// Implementations that parse `--help` and `--version` into flags, instead of exiting right away,
+/// can pass those flags as `skip`. Missing required arguments are then replaced with their default
+/// values, so the help message can be shown without an error.
+///
+/// # Errors
+///
+/// Returns `Err` if `value` is `None` and `skip` is `false`.
+///
+/// # Example
+///
+/// ```
+/// # use std::ffi::OsString;
+/// let args = vec![OsString::from("--help")];
+/// let help = args.iter().any(|arg| arg == "--help");
+/// let username: Option<String> = None;
+///
+/// let username = onlyargs::unwrap_required(help, "--username", username)?;
+///
+/// assert_eq!(username, "");
+/// # Ok::<_, onlyargs::CliError>(())
+/// ```
+pub fn unwrap_required<T, N>(skip: bool, name: N, value: Option<T>) -> Result<T, CliError>
+where
+    T: Default,
+    N: Into<String>,
+{
+    match value {
+        Some(value) => Ok(value),
+        None if skip => Ok(T::default()),
+        None => Err(CliError::MissingRequired {
+            argument: name.into(),
+        }),
+    }
 }
 
 mod macros {
@@ -240,4 +2545,170 @@ mod macros {
             );
         };
     }
+
+    /// Declares an argument struct and implements [`OnlyArgs`] for it, without a proc-macro.
+    ///
+    /// This is a lightweight alternative to the `onlyargs_derive` crate, for applications that do
+    /// not want a proc-macro dependency. It supports a subset of the derive macro:
+    ///
+    /// - `bool` fields are flags.
+    /// - `String`, `OsString`, `PathBuf`, `char`, integer, and float fields are options that take a
+    ///   value. They are required unless they have a default value, given with `= expr` after the
+    ///   type.
+    /// - `Option<T>` fields are optional options, and `Vec<T>` fields can be given multiple times.
+    ///
+    /// Each field is an argument with the long name `--{field}`, where underscores are replaced by
+    /// hyphens. The short name is the first character of the field name, unless an earlier field or
+    /// `-h` for `--help` or `-V` for `--version` already uses it. Doc comments on the fields are used
+    /// in the help text, which is aligned like the derive macro's.
+    ///
+    /// Positional arguments and the derive macro's attributes are not supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::OnlyArgs as _;
+    /// use std::path::PathBuf;
+    ///
+    /// onlyargs::args! {
+    ///     #[derive(Debug)]
+    ///     struct Args {
+    ///         /// Enable verbose output.
+    ///         verbose: bool,
+    ///         /// Set the width.
+    ///         width: i32 = 42,
+    ///         /// Set the user name.
+    ///         username: String = "root",
+    ///         /// Write output to a file.
+    ///         output: Option<PathBuf>,
+    ///         /// Exclude files.
+    ///         exclude: Vec<String>,
+    ///     }
+    /// }
+    ///
+    /// let args = Args::parse(vec!["-vw".into(), "3".into(), "--exclude=target".into()])?;
+    ///
+    /// assert!(args.verbose);
+    /// assert_eq!(args.width, 3);
+    /// assert_eq!(args.username, "root");
+    /// assert_eq!(args.output, None);
+    /// assert_eq!(args.exclude, ["target"]);
+    ///
+    /// assert!(Args::help_string().contains(
+    ///     "\nOptions:\n  \
+    ///     -w --width INTEGER    Set the width. [default: 42]\n  \
+    ///     -u --username STRING  "
+    /// ));
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    ///
+    /// [`OnlyArgs`]: crate::OnlyArgs
+    #[macro_export]
+    macro_rules! args {
+        (
+            $(#[$meta:meta])*
+            $vis:vis struct $name:ident {
+                $(
+                    $(#[doc = $doc:literal])*
+                    $field_vis:vis $field:ident: $ty:ty $(= $default:expr)?
+                ),* $(,)?
+            }
+        ) => {
+            $(#[$meta])*
+            $vis struct $name {
+                $(
+                    $(#[doc = $doc])*
+                    $field_vis $field: $ty,
+                )*
+            }
+
+            const _: () = {
+                impl $name {
+                    fn onlyargs_fields_() -> $crate::args_macro::Fields {
+                        $crate::args_macro::Fields::new(::std::vec![$(
+                            $crate::args_macro::FieldInfo {
+                                name: ::std::stringify!($field),
+                                value_name: <$ty as $crate::args_macro::Field>::VALUE_NAME,
+                                required: <$ty as $crate::args_macro::Field>::REQUIRED,
+                                doc: &[$($doc),*],
+                                default: ::std::option::Option::None
+                                    $(.or(::std::option::Option::Some(::std::stringify!($default))))?,
+                            },
+                        )*])
+                    }
+                }
+
+                impl $crate::OnlyArgs for $name {
+                    const HELP: &'static str = $crate::impl_help!();
+
+                    const VERSION: &'static str = ::std::concat!(
+                        ::std::env!("CARGO_PKG_NAME"),
+                        " v",
+                        ::std::env!("CARGO_PKG_VERSION"),
+                        "\n",
+                    );
+
+                    const USAGE: &'static str = "{bin_name} [flags] [options]";
+
+                    fn help_string() -> ::std::string::String {
+                        Self::onlyargs_fields_()
+                            .help(Self::HELP)
+                            .replace("{bin_name}", &$crate::bin_name())
+                    }
+
+                    fn short_help_string() -> ::std::string::String {
+                        <Self as $crate::OnlyArgs>::help_string()
+                    }
+
+                    fn parse(
+                        args: ::std::vec::Vec<::std::ffi::OsString>,
+                    ) -> ::std::result::Result<Self, $crate::CliError> {
+                        let fields_ = Self::onlyargs_fields_();
+                        $(
+                            let mut $field = <<$ty as $crate::args_macro::Field>::State
+                                as ::std::default::Default>::default();
+                        )*
+
+                        let mut lexer_ = $crate::lexer::Lexer::new(args);
+                        while let ::std::option::Option::Some(arg_) = lexer_.next_arg()? {
+                            match arg_ {
+                                $crate::lexer::Arg::Short('h') | $crate::lexer::Arg::Long("help") => {
+                                    <Self as $crate::OnlyArgs>::help()
+                                }
+                                $crate::lexer::Arg::Short('V')
+                                | $crate::lexer::Arg::Long("version") => {
+                                    <Self as $crate::OnlyArgs>::version()
+                                }
+                                arg_ => {
+                                    let field_ = fields_.find(arg_)?;
+                                    $(
+                                        if field_ == ::std::stringify!($field) {
+                                            <$ty as $crate::args_macro::Field>::push(
+                                                &mut $field,
+                                                &mut lexer_,
+                                                &fields_.label(field_),
+                                            )?;
+                                        }
+                                    )*
+                                }
+                            }
+                        }
+
+                        ::std::result::Result::Ok(Self {
+                            $(
+                                $field: <$ty as $crate::args_macro::Field>::finish(
+                                    $field,
+                                    ::std::option::Option::None
+                                        $(.or(::std::option::Option::Some(
+                                            ::std::convert::Into::into($default),
+                                        )))?,
+                                    &fields_.label(::std::stringify!($field)),
+                                )?,
+                            )*
+                        })
+                    }
+                }
+            };
+        };
+    }
 }
@@ -0,0 +1,55 @@
+//! Install a logger configured from [`Verbosity`].
+//!
+//! Most applications map `--verbose` and `--quiet` to a log level by hand. [`init`] does it in one
+//! call for the logging crates enabled as features:
+//!
+//! - `env_logger`: installs an [`env_logger`] logger for the `log` crate. The `RUST_LOG`
+//!   environment variable takes precedence over the verbosity.
+//! - `tracing-subscriber`: installs a [`tracing_subscriber::fmt`] subscriber for the `tracing`
+//!   crate.
+//!
+//! `onlyargs_derive` generates an `init_logging(&self)` method for argument structs with a
+//! `Verbosity` field, which calls [`init`].
+//!
+//! These features have a higher MSRV than the rest of the crate.
+//!
+//! [`env_logger`]: https://docs.rs/env_logger
+//! [`tracing_subscriber::fmt`]: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/fmt/index.html
+
+use crate::types::Verbosity;
+
+/// Install the loggers enabled by features, at the level given by `verbosity`.
+///
+/// The default level is `WARN`; see `Verbosity::log_level_filter()` for the mapping. Nothing is
+/// installed for a crate that already has a global logger, and nothing happens at all without a
+/// logging feature.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::types::Verbosity;
+///
+/// onlyargs::logging::init(Verbosity::new(1, 0));
+///
+/// // A second call leaves the first logger in place.
+/// onlyargs::logging::init(Verbosity::new(2, 0));
+/// ```
+pub fn init(verbosity: Verbosity) {
+    #[cfg(feature = "env_logger")]
+    {
+        let _ = env_logger::Builder::new()
+            .filter_level(verbosity.log_level_filter())
+            .parse_default_env()
+            .try_init();
+    }
+
+    #[cfg(feature = "tracing-subscriber")]
+    {
+        let _ = tracing_subscriber::fmt()
+            .with_max_level(verbosity.tracing_level_filter())
+            .try_init();
+    }
+
+    #[cfg(not(any(feature = "env_logger", feature = "tracing-subscriber")))]
+    let _ = verbosity;
+}
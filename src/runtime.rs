@@ -0,0 +1,283 @@
+//! Parsers for arguments that are only known at runtime.
+//!
+//! The [`OnlyArgs`](crate::OnlyArgs) trait and its derive macro describe arguments with a type.
+//! This module builds a [`Parser`] from a list of arguments instead, which is useful for plugins
+//! and scripting hosts that discover their arguments while running.
+//!
+//! Parsed values are returned in [`Matches`], and looked up by name with the type they were
+//! declared with.
+//!
+//! # Example
+//!
+//! ```
+//! use onlyargs::runtime::Parser;
+//! use std::path::PathBuf;
+//!
+//! let parser = Parser::new()
+//!     .flag("verbose", 'v')
+//!     .option::<u32>("width", 'w')
+//!     .positional::<PathBuf>();
+//!
+//! let matches = parser.parse(vec![
+//!     "-v".into(),
+//!     "--width".into(),
+//!     "42".into(),
+//!     "input.txt".into(),
+//! ])?;
+//!
+//! assert!(matches.flag("verbose"));
+//! assert_eq!(matches.get::<u32>("width"), Some(&42));
+//! assert_eq!(matches.positional::<PathBuf>(), [&PathBuf::from("input.txt")]);
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+
+use crate::traits::ArgExt as _;
+use crate::CliError;
+use std::any::Any;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Types that can be parsed from a single argument value.
+pub trait FromArg: Sized + 'static {
+    /// Parse an argument value. `name` is the argument that the value belongs to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the value cannot be parsed.
+    fn from_arg(value: OsString, name: &str) -> Result<Self, CliError>;
+}
+
+impl FromArg for String {
+    fn from_arg(value: OsString, name: &str) -> Result<Self, CliError> {
+        value.parse_str(name)
+    }
+}
+
+impl FromArg for OsString {
+    fn from_arg(value: OsString, name: &str) -> Result<Self, CliError> {
+        value.parse_osstr(name)
+    }
+}
+
+impl FromArg for PathBuf {
+    fn from_arg(value: OsString, name: &str) -> Result<Self, CliError> {
+        value.parse_path(name)
+    }
+}
+
+impl FromArg for bool {
+    fn from_arg(value: OsString, name: &str) -> Result<Self, CliError> {
+        value.parse_bool(name)
+    }
+}
+
+impl FromArg for char {
+    fn from_arg(value: OsString, name: &str) -> Result<Self, CliError> {
+        value.parse_char(name)
+    }
+}
+
+macro_rules! impl_from_arg {
+    ($parser:ident => $($ty:ty),*) => {
+        $(
+            impl FromArg for $ty {
+                fn from_arg(value: OsString, name: &str) -> Result<Self, CliError> {
+                    value.$parser(name)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_arg!(parse_int => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_from_arg!(parse_float => f32, f64);
+
+type ValueParser = fn(OsString, &str) -> Result<Box<dyn Any>, CliError>;
+
+fn parse_value<T: FromArg>(value: OsString, name: &str) -> Result<Box<dyn Any>, CliError> {
+    T::from_arg(value, name).map(|value| Box::new(value) as Box<dyn Any>)
+}
+
+#[derive(Debug)]
+struct Arg {
+    name: String,
+    short: Option<char>,
+    // Flags do not have a value parser.
+    parser: Option<ValueParser>,
+}
+
+impl Arg {
+    fn matches(&self, arg: &str) -> bool {
+        if let Some(name) = arg.strip_prefix("--") {
+            return name == self.name;
+        }
+
+        let mut chars = arg.chars();
+        self.short.is_some()
+            && chars.next() == Some('-')
+            && chars.next() == self.short
+            && chars.next().is_none()
+    }
+}
+
+/// A parser for a list of arguments that is built at runtime.
+///
+/// Flags and options have a long name like `--width` and an optional short name like `-w`. Options
+/// take one value, and the last one on the command line wins. Arguments that do not start with `-`
+/// are positional.
+#[derive(Debug, Default)]
+pub struct Parser {
+    args: Vec<Arg>,
+    positional: Option<ValueParser>,
+}
+
+impl Parser {
+    /// Create a parser without any arguments.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a flag named `--{name}`, with the short name `-{short}`.
+    #[must_use]
+    pub fn flag(self, name: impl Into<String>, short: impl Into<Option<char>>) -> Self {
+        self.push(name.into(), short.into(), None)
+    }
+
+    /// Add an option named `--{name}` that takes a value of type `T`, with the short name
+    /// `-{short}`.
+    #[must_use]
+    pub fn option<T: FromArg>(
+        self,
+        name: impl Into<String>,
+        short: impl Into<Option<char>>,
+    ) -> Self {
+        self.push(name.into(), short.into(), Some(parse_value::<T>))
+    }
+
+    /// Accept positional arguments of type `T`.
+    ///
+    /// Without positional arguments, any argument that is not a flag or option is an error.
+    #[must_use]
+    pub fn positional<T: FromArg>(mut self) -> Self {
+        self.positional = Some(parse_value::<T>);
+        self
+    }
+
+    fn push(mut self, name: String, short: Option<char>, parser: Option<ValueParser>) -> Self {
+        self.args.push(Arg {
+            name,
+            short,
+            parser,
+        });
+        self
+    }
+
+    /// Parse the arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an argument is not known, or a value cannot be parsed.
+    pub fn parse(&self, args: Vec<OsString>) -> Result<Matches, CliError> {
+        let mut matches = Matches::default();
+
+        let mut args = args.into_iter();
+        let total = args.len();
+        while let Some(arg) = args.next() {
+            let index = total - args.len() - 1;
+            let found = arg
+                .to_str()
+                .and_then(|arg| self.args.iter().find(|known| known.matches(arg)));
+
+            match (found, &self.positional) {
+                (Some(known), _) => {
+                    let label = format!("--{}", known.name);
+
+                    if let Some(parser) = known.parser {
+                        let value = args
+                            .next()
+                            .parse_osstr(&label)
+                            .map_err(|err| err.at(index, &arg))?;
+                        let token = value.clone();
+                        let value =
+                            parser(value, &label).map_err(|err| err.at(index + 1, token))?;
+                        matches.values.insert(known.name.clone(), value);
+                    } else {
+                        matches.values.insert(known.name.clone(), Box::new(true));
+                    }
+                }
+                (None, Some(parser)) if arg == "--" => {
+                    for (index, arg) in (index + 1..).zip(args.by_ref()) {
+                        let token = arg.clone();
+                        let value =
+                            parser(arg, "<POSITIONAL>").map_err(|err| err.at(index, token))?;
+                        matches.positional.push(value);
+                    }
+                }
+                (None, None) if arg == "--" => break,
+                (None, Some(parser)) if !arg.to_str().map_or(false, |arg| arg.starts_with('-')) => {
+                    let token = arg.clone();
+                    let value = parser(arg, "<POSITIONAL>").map_err(|err| err.at(index, token))?;
+                    matches.positional.push(value);
+                }
+                (None, _) => {
+                    let known = self
+                        .args
+                        .iter()
+                        .map(|known| format!("--{}", known.name))
+                        .collect::<Vec<_>>();
+                    let known = known.iter().map(String::as_str).collect::<Vec<_>>();
+
+                    return Err(CliError::unknown(arg.clone(), &known).at(index, arg));
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+}
+
+/// Arguments parsed by a [`Parser`].
+#[derive(Default)]
+pub struct Matches {
+    values: HashMap<String, Box<dyn Any>>,
+    positional: Vec<Box<dyn Any>>,
+}
+
+impl Matches {
+    /// Check whether the flag named `--{name}` was given.
+    #[must_use]
+    pub fn flag(&self, name: &str) -> bool {
+        self.get::<bool>(name).copied().unwrap_or_default()
+    }
+
+    /// Get the value of the option named `--{name}`.
+    ///
+    /// Returns `None` if the option was not given, or if `T` is not the type the option was added
+    /// with.
+    #[must_use]
+    pub fn get<T: Any>(&self, name: &str) -> Option<&T> {
+        self.values.get(name)?.downcast_ref()
+    }
+
+    /// Get the positional arguments.
+    ///
+    /// Returns an empty list if `T` is not the type the positional arguments were added with.
+    #[must_use]
+    pub fn positional<T: Any>(&self) -> Vec<&T> {
+        self.positional
+            .iter()
+            .filter_map(|value| value.downcast_ref())
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for Matches {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Matches")
+            .field("names", &self.values.keys().collect::<Vec<_>>())
+            .field("positional", &self.positional.len())
+            .finish()
+    }
+}
@@ -0,0 +1,697 @@
+//! Deserialize [`serde`](https://docs.rs/serde) types from command line arguments and other
+//! sources.
+//!
+//! This module lets applications reuse existing `Deserialize` structs (e.g. a config struct) as
+//! their argument type. Each struct field is looked up by name in a [`Source`]:
+//!
+//! - [`from_args`] reads long arguments from the command line. See [`Tokens`] for the syntax.
+//! - [`from_source`] reads from any [`Source`], including the [`Layers`] returned by
+//!   `OnlyArgs::layers()`. This treats the environment variables, `.env` file, and config file of
+//!   an `OnlyArgs` struct as the source for another struct.
+//!
+//! Field values are parsed with the same rules as `#[derive(OnlyArgs)]`: primitives use the last
+//! value provided, `Vec<T>` uses all values, and missing `Option<T>` fields are `None`. Nested
+//! structs and maps are not supported.
+//!
+//! # Example
+//!
+//! ```
+//! use serde::Deserialize;
+//! use std::ffi::OsString;
+//!
+//! #[derive(Debug, Deserialize)]
+//! struct Args {
+//!     username: String,
+//!     names: Vec<String>,
+//!     width: Option<i32>,
+//!     verbose: bool,
+//! }
+//!
+//! let args = ["--username", "Alice", "--names", "Bob", "--names", "Carol", "--verbose"];
+//! let args: Args = onlyargs::serde::from_args(args.into_iter().map(OsString::from).collect())?;
+//!
+//! assert_eq!(args.username, "Alice");
+//! assert_eq!(args.names, ["Bob", "Carol"]);
+//! assert_eq!(args.width, None);
+//! assert!(args.verbose);
+//! # Ok::<_, onlyargs::serde::Error>(())
+//! ```
+//!
+//! # Serializing errors
+//!
+//! [`CliError`] implements `Serialize`, so tools that wrap a CLI can read parse failures in a
+//! machine-readable format like JSON. Errors are serialized as a map with these entries:
+//!
+//! - `kind`: The error category in `snake_case`, like `"missing_value"` or `"unknown"`. See
+//!   [`ErrorKind`](crate::ErrorKind).
+//! - `message`: The human-readable error message.
+//! - `position`: The index of the command line argument that caused the error, if it is known.
+//! - `token`: The command line argument at `position`.
+//! - `argument`: The argument name, if the error is about an argument.
+//! - `value`: The argument value, if the error is about a value.
+//! - `choices`: The possible values, for `invalid_choice` errors.
+//! - `other`: The argument that was already given, for `conflict` errors.
+//! - `suggestion`: The suggested argument name, for `unknown` errors with a suggestion.
+//! - `path`: The path, for `path_not_found`, `not_a_file`, `not_a_directory`, and
+//!   `config_io_error` errors, and for `parse_config_error` errors from a file.
+//! - `alias`: The alias name, for `alias_recursion` and `parse_alias_error` errors.
+//! - `errors`: The list of errors, for `multiple` errors.
+//!
+//! Entries that do not apply to an error are omitted. Values that are not valid UTF-8 are
+//! serialized as lossy strings.
+//!
+//! ```
+//! use onlyargs::CliError;
+//!
+//! let err = CliError::unknown("--outpt".into(), &["--output"]).at(2, "--outpt");
+//!
+//! assert_eq!(
+//!     serde_json::to_string(&err).unwrap(),
+//!     r#"{"kind":"unknown","message":"Unknown argument `--outpt`. Did you mean `--output`? (argument 3)","position":2,"token":"--outpt","argument":"--outpt","suggestion":"--output"}"#,
+//! );
+//! ```
+//!
+//! [`Layers`]: crate::source::Layers
+
+use crate::source::{Key, Origin, Source};
+use crate::traits::ArgExt as _;
+use crate::{CliError, ErrorKind, Lossy};
+use ::serde::de::value::{SeqDeserializer, StrDeserializer, StringDeserializer};
+use ::serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use ::serde::ser::SerializeMap as _;
+use std::ffi::OsString;
+use std::fmt::Display;
+
+/// Errors produced while deserializing.
+#[derive(Debug)]
+pub enum Error {
+    /// An argument could not be parsed.
+    Cli(CliError),
+
+    /// A custom error from the `Deserialize` implementation, e.g. a missing field.
+    Custom(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cli(err) => write!(f, "{err}"),
+            Self::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cli(err) => Some(err),
+            Self::Custom(_) => None,
+        }
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl From<CliError> for Error {
+    fn from(err: CliError) -> Self {
+        Self::Cli(err)
+    }
+}
+
+impl ::serde::Serialize for CliError {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", kind_name(self.kind()))?;
+        map.serialize_entry("message", &self.to_string())?;
+        if let Some(position) = self.position() {
+            map.serialize_entry("position", &position)?;
+        }
+        if let Some(token) = self.token() {
+            map.serialize_entry("token", &Lossy(token).to_string())?;
+        }
+
+        match self.inner() {
+            CliError::MissingValue { argument }
+            | CliError::MissingRequired { argument }
+            | CliError::Custom { argument, .. } => map.serialize_entry("argument", argument)?,
+            CliError::ParseBoolError {
+                argument, value, ..
+            }
+            | CliError::ParseCharError {
+                argument, value, ..
+            }
+            | CliError::ParseFloatError {
+                argument, value, ..
+            }
+            | CliError::ParseIntError {
+                argument, value, ..
+            }
+            | CliError::ParseStrError { argument, value } => {
+                map.serialize_entry("argument", argument)?;
+                map.serialize_entry("value", &Lossy(value).to_string())?;
+            }
+            #[cfg(feature = "url")]
+            CliError::ParseUrlError {
+                argument, value, ..
+            } => {
+                map.serialize_entry("argument", argument)?;
+                map.serialize_entry("value", &Lossy(value).to_string())?;
+            }
+            #[cfg(feature = "uuid")]
+            CliError::ParseUuidError {
+                argument, value, ..
+            } => {
+                map.serialize_entry("argument", argument)?;
+                map.serialize_entry("value", &Lossy(value).to_string())?;
+            }
+            #[cfg(feature = "semver")]
+            CliError::ParseVersionError {
+                argument, value, ..
+            } => {
+                map.serialize_entry("argument", argument)?;
+                map.serialize_entry("value", &Lossy(value).to_string())?;
+            }
+            CliError::InvalidChoice {
+                argument,
+                value,
+                choices,
+            } => {
+                map.serialize_entry("argument", argument)?;
+                map.serialize_entry("value", &Lossy(value).to_string())?;
+                map.serialize_entry("choices", choices)?;
+            }
+            CliError::Conflict { argument, other } => {
+                map.serialize_entry("argument", argument)?;
+                map.serialize_entry("other", other)?;
+            }
+            CliError::PathNotFound { argument, path }
+            | CliError::NotAFile { argument, path }
+            | CliError::NotADirectory { argument, path } => {
+                map.serialize_entry("argument", argument)?;
+                map.serialize_entry("path", &path.to_string_lossy())?;
+            }
+            CliError::Unknown { argument } => {
+                map.serialize_entry("argument", &Lossy(argument).to_string())?;
+            }
+            CliError::UnknownSuggestion {
+                argument,
+                suggestion,
+            } => {
+                map.serialize_entry("argument", &Lossy(argument).to_string())?;
+                map.serialize_entry("suggestion", suggestion)?;
+            }
+            CliError::ConfigIoError { path, .. }
+            | CliError::ParseConfigError {
+                path: Some(path), ..
+            } => {
+                map.serialize_entry("path", &path.to_string_lossy())?;
+            }
+            CliError::AliasRecursion { alias } | CliError::ParseAliasError { alias, .. } => {
+                map.serialize_entry("alias", alias)?;
+            }
+            CliError::Multiple { errors } => map.serialize_entry("errors", errors)?,
+            CliError::ParseConfigError { .. } | CliError::At { .. } => (),
+        }
+
+        map.end()
+    }
+}
+
+/// The stable name of an error kind in serialized errors.
+fn kind_name(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::MissingValue => "missing_value",
+        ErrorKind::MissingRequired => "missing_required",
+        ErrorKind::ParseBoolError => "parse_bool_error",
+        ErrorKind::ParseCharError => "parse_char_error",
+        ErrorKind::ParseFloatError => "parse_float_error",
+        ErrorKind::ParseIntError => "parse_int_error",
+        ErrorKind::ParseStrError => "parse_str_error",
+        #[cfg(feature = "url")]
+        ErrorKind::ParseUrlError => "parse_url_error",
+        #[cfg(feature = "uuid")]
+        ErrorKind::ParseUuidError => "parse_uuid_error",
+        #[cfg(feature = "semver")]
+        ErrorKind::ParseVersionError => "parse_version_error",
+        ErrorKind::InvalidChoice => "invalid_choice",
+        ErrorKind::Conflict => "conflict",
+        ErrorKind::PathNotFound => "path_not_found",
+        ErrorKind::NotAFile => "not_a_file",
+        ErrorKind::NotADirectory => "not_a_directory",
+        ErrorKind::Unknown => "unknown",
+        ErrorKind::ConfigIoError => "config_io_error",
+        ErrorKind::ParseConfigError => "parse_config_error",
+        ErrorKind::AliasRecursion => "alias_recursion",
+        ErrorKind::ParseAliasError => "parse_alias_error",
+        ErrorKind::Multiple => "multiple",
+        ErrorKind::Custom => "custom",
+    }
+}
+
+/// Long arguments from the command line, keyed by field name.
+///
+/// - Arguments must use the long form, e.g. `--log-level debug` provides the `log_level` field.
+/// - An argument that is followed by another `--` argument (or nothing) is a flag with the value
+///   `true`.
+/// - Arguments can be repeated to provide multiple values.
+///
+/// Short and positional arguments are not supported.
+#[derive(Debug, Default)]
+pub struct Tokens {
+    values: Vec<(String, Vec<OsString>)>,
+}
+
+impl Tokens {
+    /// Split command line arguments into tokens.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an argument is not a long argument.
+    pub fn parse(args: Vec<OsString>) -> Result<Self, CliError> {
+        let mut values: Vec<(String, Vec<OsString>)> = vec![];
+
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let name = match arg.to_str().and_then(|arg| arg.strip_prefix("--")) {
+                Some(name) if !name.is_empty() => name.replace('-', "_"),
+                _ => return Err(CliError::Unknown { argument: arg }),
+            };
+            let value = args
+                .next_if(|next| !next.to_str().map_or(false, |next| next.starts_with("--")))
+                .unwrap_or_else(|| "true".into());
+
+            match values.iter_mut().find(|(other, _)| *other == name) {
+                Some((_, other)) => other.push(value),
+                None => values.push((name, vec![value])),
+            }
+        }
+
+        Ok(Self { values })
+    }
+}
+
+impl Source for Tokens {
+    fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+        self.values
+            .iter()
+            .find(|(name, _)| name == key.name)
+            .map(|(_, values)| values.clone())
+            .unwrap_or_default()
+    }
+
+    fn describe(&self, key: &Key<'_>) -> String {
+        format!("--{}", key.name.replace('_', "-"))
+    }
+
+    fn origin(&self, _key: &Key<'_>) -> Origin {
+        Origin::Cli
+    }
+}
+
+/// Deserialize a struct from long command line arguments.
+///
+/// The `--help` and `--version` arguments are not handled.
+///
+/// # Errors
+///
+/// Returns `Err` if the arguments cannot be deserialized to `T`.
+pub fn from_args<T: DeserializeOwned>(args: Vec<OsString>) -> Result<T, Error> {
+    from_source(&Tokens::parse(args)?)
+}
+
+/// Deserialize a struct from a [`Source`].
+///
+/// # Errors
+///
+/// Returns `Err` if the values provided by `source` cannot be deserialized to `T`.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::serde::{from_source, Tokens};
+/// use onlyargs::source::{Key, Layers, Source};
+/// use serde::Deserialize;
+/// use std::ffi::OsString;
+///
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     username: String,
+///     width: i32,
+/// }
+///
+/// struct Defaults;
+///
+/// impl Source for Defaults {
+///     fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+///         match key.name {
+///             "username" => vec!["Alice".into()],
+///             "width" => vec!["42".into()],
+///             _ => vec![],
+///         }
+///     }
+/// }
+///
+/// // The command line takes precedence over the defaults.
+/// let args = vec!["--width".into(), "80".into()];
+/// let layers = Layers::new().push(Tokens::parse(args)?).push(Defaults);
+/// let settings: Settings = from_source(&layers)?;
+///
+/// assert_eq!(settings.username, "Alice");
+/// assert_eq!(settings.width, 80);
+/// # Ok::<_, onlyargs::serde::Error>(())
+/// ```
+pub fn from_source<T: DeserializeOwned>(source: &dyn Source) -> Result<T, Error> {
+    T::deserialize(Deserializer::new(source))
+}
+
+/// A [`serde::Deserializer`](::serde::Deserializer) for structs, backed by a [`Source`].
+#[derive(Clone)]
+pub struct Deserializer<'a> {
+    source: &'a dyn Source,
+    env_prefix: Option<String>,
+}
+
+impl<'a> Deserializer<'a> {
+    /// Create a deserializer that reads fields from `source`.
+    pub fn new(source: &'a dyn Source) -> Self {
+        Self {
+            source,
+            env_prefix: None,
+        }
+    }
+
+    /// Give every field an environment variable name, like `#[env_prefix(...)]` in
+    /// `#[derive(OnlyArgs)]`. The `log_level` field is read from `{prefix}LOG_LEVEL`.
+    #[must_use]
+    pub fn env_prefix<P>(mut self, prefix: P) -> Self
+    where
+        P: Into<String>,
+    {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+}
+
+impl std::fmt::Debug for Deserializer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Deserializer")
+            .field("env_prefix", &self.env_prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(de::Error::custom("only structs can be deserialized"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(Fields {
+            de: &self,
+            names: fields.iter(),
+            value: None,
+        })
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// Visits each struct field that the source provides.
+struct Fields<'a, 'b> {
+    de: &'b Deserializer<'a>,
+    names: std::slice::Iter<'static, &'static str>,
+    value: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for Fields<'_, '_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        for &field in self.names.by_ref() {
+            let env = self.de.env_prefix.as_ref().map(|prefix| {
+                let mut name = field.to_string();
+                name.make_ascii_uppercase();
+
+                format!("{prefix}{name}")
+            });
+            let key = Key {
+                name: field,
+                env: env.as_deref(),
+            };
+
+            let values = self.de.source.get(&key);
+            if !values.is_empty() {
+                self.value = Some(Value {
+                    name: self.de.source.describe(&key),
+                    values,
+                });
+                let field: StrDeserializer<'_, Error> = field.into_deserializer();
+
+                return seed.deserialize(field).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::custom("value requested before key")),
+        }
+    }
+}
+
+/// The values provided for a single field.
+struct Value {
+    name: String,
+    values: Vec<OsString>,
+}
+
+impl Value {
+    fn last(mut self) -> (String, OsString) {
+        let value = self.values.pop().unwrap_or_default();
+
+        (self.name, value)
+    }
+
+    fn parse<T, E, F>(self, error: F) -> Result<T, Error>
+    where
+        T: std::str::FromStr<Err = E>,
+        F: FnOnce(String, OsString, E) -> CliError,
+    {
+        let (name, value) = self.last();
+        let string = value.clone().parse_str(name.as_str())?;
+
+        string.parse().map_err(|err| error(name, value, err).into())
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_int {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let (name, value) = self.last();
+
+                visitor.$visit(value.parse_int(name)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.values.len() > 1 {
+            self.deserialize_seq(visitor)
+        } else {
+            self.deserialize_string(visitor)
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(
+            self.parse(|argument, value, source| CliError::ParseBoolError {
+                argument,
+                value,
+                source,
+            })?,
+        )
+    }
+
+    deserialize_int! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let (name, value) = self.last();
+
+        visitor.visit_f32(value.parse_float(name)?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let (name, value) = self.last();
+
+        visitor.visit_f64(value.parse_float(name)?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_char(
+            self.parse(|argument, value, source| CliError::ParseCharError {
+                argument,
+                value,
+                source,
+            })?,
+        )
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let (name, value) = self.last();
+
+        visitor.visit_string(value.parse_str(name)?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let (name, value) = self.last();
+
+        visitor.visit_byte_buf(value.parse_str(name)?.into_bytes())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let name = self.name;
+        let values = self.values.into_iter().map(|value| Value {
+            name: name.clone(),
+            values: vec![value],
+        });
+
+        let mut seq = SeqDeserializer::new(values);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(de::Error::custom(format!(
+            "maps are not supported for argument `{}`",
+            self.name,
+        )))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error> {
+        Err(de::Error::custom(format!(
+            "nested structs are not supported for argument `{}`",
+            self.name,
+        )))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let (name, value) = self.last();
+        let variant: StringDeserializer<Error> = value.parse_str(name)?.into_deserializer();
+
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
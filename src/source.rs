@@ -0,0 +1,360 @@
+//! Layered sources for argument values.
+//!
+//! Arguments that are not provided on the command line can be filled in by other sources, like
+//! environment variables and configuration files. The precedence chain is always:
+//!
+//! 1. The command line.
+//! 2. Each [`Source`] in a [`Layers`] chain, in the order they were added.
+//! 3. The argument's default value.
+//!
+//! The command line and defaults are handled by the [`OnlyArgs`] implementation. The layers in
+//! between are pluggable: applications can implement [`Source`] for their own types (e.g. a remote
+//! configuration service) and insert them anywhere in the chain.
+//!
+//! The default chain created by `#[derive(OnlyArgs)]` is: [`Env`], then `dotenv::DotEnv` (with the
+//! `#[dotenv]` attribute), then `config::Config` (with the `#[config_file(...)]` attribute).
+//!
+//! # Example
+//!
+//! ```
+//! use onlyargs::source::{Env, Key, Layers, Source};
+//! use onlyargs::traits::*;
+//! use std::ffi::OsString;
+//!
+//! struct Remote;
+//!
+//! impl Source for Remote {
+//!     fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+//!         match key.name {
+//!             "width" => vec!["80".into()],
+//!             _ => vec![],
+//!         }
+//!     }
+//! }
+//!
+//! let layers = Layers::new().push(Env).push(Remote);
+//! let key = Key {
+//!     name: "width",
+//!     env: Some("MYAPP_WIDTH"),
+//! };
+//! let width: i32 = layers
+//!     .parse(&key, |value, name| value.parse_int(name))?
+//!     .unwrap_or(42);
+//!
+//! assert_eq!(width, 80);
+//! # Ok::<_, onlyargs::CliError>(())
+//! ```
+//!
+//! # Provenance
+//!
+//! Each source reports an [`Origin`] so applications can tell where a value came from. The derive
+//! macro records origins for every argument in an [`Origins`] field marked with `#[origins]`.
+//!
+//! [`OnlyArgs`]: crate::OnlyArgs
+
+use crate::CliError;
+use std::ffi::OsString;
+
+/// Identifies an argument when looking up values in a [`Source`].
+#[derive(Copy, Clone, Debug)]
+pub struct Key<'a> {
+    /// The argument's field name, e.g. `log_level`.
+    ///
+    /// Configuration files use this as the key.
+    pub name: &'a str,
+
+    /// The argument's environment variable name, if it has one.
+    pub env: Option<&'a str>,
+}
+
+/// A source of argument values.
+pub trait Source {
+    /// Get all values for an argument.
+    ///
+    /// Returns an empty `Vec` if this source does not provide the argument. Single-value arguments
+    /// use the last value.
+    fn get(&self, key: &Key<'_>) -> Vec<OsString>;
+
+    /// The argument name reported in errors for values provided by this source.
+    ///
+    /// Defaults to the field name.
+    fn describe(&self, key: &Key<'_>) -> String {
+        key.name.to_string()
+    }
+
+    /// Where values provided by this source come from.
+    ///
+    /// Defaults to [`Origin::Config`].
+    fn origin(&self, key: &Key<'_>) -> Origin {
+        let _ = key;
+
+        Origin::Config
+    }
+}
+
+/// Where an argument's value came from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Origin {
+    /// The command line.
+    Cli,
+
+    /// An environment variable (including `.env` files).
+    Env,
+
+    /// A configuration file or other custom [`Source`].
+    Config,
+
+    /// The argument's default value. Arguments that were not provided anywhere have this origin.
+    Default,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Cli => "command line",
+            Self::Env => "environment",
+            Self::Config => "config",
+            Self::Default => "default",
+        })
+    }
+}
+
+/// The [`Origin`] of each argument, in declaration order.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::source::{Origin, Origins};
+///
+/// let mut origins = Origins::default();
+/// origins.set("width", Origin::Env);
+///
+/// assert_eq!(origins.source_of("width"), Some(Origin::Env));
+/// assert_eq!(origins.source_of("height"), None);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Origins {
+    origins: Vec<(&'static str, Origin)>,
+}
+
+impl Origins {
+    /// Record the origin of an argument by field name.
+    pub fn set(&mut self, name: &'static str, origin: Origin) {
+        match self.origins.iter_mut().find(|(other, _)| *other == name) {
+            Some((_, other)) => *other = origin,
+            None => self.origins.push((name, origin)),
+        }
+    }
+
+    /// Get the origin of an argument by field name.
+    ///
+    /// Returns `None` if the argument is unknown.
+    #[must_use]
+    pub fn source_of(&self, name: &str) -> Option<Origin> {
+        self.origins
+            .iter()
+            .find_map(|&(other, origin)| (other == name).then_some(origin))
+    }
+
+    /// Iterate over all field names and their origins.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, Origin)> + '_ {
+        self.origins.iter().copied()
+    }
+}
+
+/// Format the effective value of each argument in aligned columns, like for a `--dry-run` view.
+///
+/// Each row is a field name, its value, and its origin if it is known. `#[derive(OnlyArgs)]`
+/// generates a `show_config()` method that calls this with the origins from its `#[origins]`
+/// field.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::source::{format_config, Origin};
+///
+/// let config = format_config(&[
+///     ("verbose", "true".to_string(), Some(Origin::Cli)),
+///     ("width", "80".to_string(), Some(Origin::Default)),
+/// ]);
+///
+/// assert_eq!(config, "verbose  true  command line\nwidth    80    default\n");
+/// ```
+#[must_use]
+pub fn format_config(rows: &[(&str, String, Option<Origin>)]) -> String {
+    let name_width = rows
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let value_width = rows
+        .iter()
+        .map(|(_, value, _)| value.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut config = String::new();
+    for (name, value, origin) in rows {
+        let row = match origin {
+            Some(origin) => format!("{name:<name_width$}  {value:<value_width$}  {origin}"),
+            None => format!("{name:<name_width$}  {value}"),
+        };
+        config.push_str(row.trim_end());
+        config.push('\n');
+    }
+
+    config
+}
+
+/// Environment variables.
+///
+/// Provides values for arguments with an environment variable name. Errors are reported with the
+/// variable name.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Env;
+
+impl Source for Env {
+    fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+        key.env.and_then(std::env::var_os).into_iter().collect()
+    }
+
+    fn describe(&self, key: &Key<'_>) -> String {
+        key.env.unwrap_or(key.name).to_string()
+    }
+
+    fn origin(&self, _key: &Key<'_>) -> Origin {
+        Origin::Env
+    }
+}
+
+/// An ordered chain of [`Source`]s.
+///
+/// Sources added first take precedence over sources added later.
+#[derive(Default)]
+pub struct Layers {
+    sources: Vec<Box<dyn Source>>,
+}
+
+impl std::fmt::Debug for Layers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Layers")
+            .field("len", &self.sources.len())
+            .finish()
+    }
+}
+
+impl Layers {
+    /// Create an empty chain.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source with lower precedence than all existing sources.
+    #[must_use]
+    pub fn push<S>(mut self, source: S) -> Self
+    where
+        S: Source + 'static,
+    {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Insert a source at the given position in the chain.
+    ///
+    /// Index `0` takes precedence over all existing sources.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[must_use]
+    pub fn insert<S>(mut self, index: usize, source: S) -> Self
+    where
+        S: Source + 'static,
+    {
+        self.sources.insert(index, Box::new(source));
+        self
+    }
+
+    /// The number of sources in the chain.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Returns `true` if there are no sources in the chain.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Find the first source that provides the argument.
+    fn find(&self, key: &Key<'_>) -> Option<(&dyn Source, Vec<OsString>)> {
+        self.sources.iter().find_map(|source| {
+            let values = source.get(key);
+
+            (!values.is_empty()).then(|| (source.as_ref(), values))
+        })
+    }
+
+    /// Get the origin of the first source that provides the argument.
+    ///
+    /// Returns `None` if no source provides the argument.
+    #[must_use]
+    pub fn origin(&self, key: &Key<'_>) -> Option<Origin> {
+        self.find(key).map(|(source, _)| source.origin(key))
+    }
+
+    /// Parse the value for a single-value argument from the first source that provides it.
+    ///
+    /// The `parse` function is called with the raw value and the argument name to report in
+    /// errors. Returns `Ok(None)` if no source provides the argument.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the `parse` function fails.
+    pub fn parse<T, F>(&self, key: &Key<'_>, parse: F) -> Result<Option<T>, CliError>
+    where
+        F: FnOnce(OsString, String) -> Result<T, CliError>,
+    {
+        self.find(key)
+            .and_then(|(source, mut values)| values.pop().map(|value| (source, value)))
+            .map(|(source, value)| parse(value, source.describe(key)))
+            .transpose()
+    }
+
+    /// Parse all values for a multivalue argument from the first source that provides it.
+    ///
+    /// Returns an empty `Vec` if no source provides the argument.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the `parse` function fails.
+    pub fn parse_all<T, F>(&self, key: &Key<'_>, mut parse: F) -> Result<Vec<T>, CliError>
+    where
+        F: FnMut(OsString, String) -> Result<T, CliError>,
+    {
+        match self.find(key) {
+            Some((source, values)) => values
+                .into_iter()
+                .map(|value| parse(value, source.describe(key)))
+                .collect(),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+impl Source for Layers {
+    fn get(&self, key: &Key<'_>) -> Vec<OsString> {
+        self.find(key).map(|(_, values)| values).unwrap_or_default()
+    }
+
+    fn describe(&self, key: &Key<'_>) -> String {
+        self.find(key)
+            .map_or_else(|| key.name.to_string(), |(source, _)| source.describe(key))
+    }
+
+    fn origin(&self, key: &Key<'_>) -> Origin {
+        Layers::origin(self, key).unwrap_or(Origin::Default)
+    }
+}
@@ -0,0 +1,99 @@
+//! "Did you mean" suggestions for mistyped arguments.
+//!
+//! Suggestions are chosen by [edit distance] between the unknown argument and each known argument
+//! name. Only close matches are suggested, so an argument that is not similar to anything gets no
+//! suggestion at all.
+//!
+//! [edit distance]: https://en.wikipedia.org/wiki/Levenshtein_distance
+
+/// Compute the Levenshtein edit distance between two strings.
+///
+/// This is the number of single character insertions, deletions, and substitutions required to
+/// change `a` into `b`.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::suggest::edit_distance;
+///
+/// assert_eq!(edit_distance("--outpt", "--output"), 1);
+/// assert_eq!(edit_distance("kitten", "sitting"), 3);
+/// ```
+#[must_use]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Find the known argument name that is closest to `arg`.
+///
+/// Returns `None` if no candidate is close enough to be a likely typo. The allowed distance grows
+/// with the length of `arg`, about one edit for every three characters.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::suggest::did_you_mean;
+///
+/// let known = ["--help", "--output", "--verbose"];
+///
+/// assert_eq!(did_you_mean("--outpt", &known), Some("--output"));
+/// assert_eq!(did_you_mean("--frobnicate", &known), None);
+/// ```
+#[must_use]
+pub fn did_you_mean<'a>(arg: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    similar(arg, candidates).into_iter().next()
+}
+
+/// Find all candidates that are close to `arg`, closest first.
+///
+/// This uses the same distance limit as [`did_you_mean`]. It is useful when more than one
+/// suggestion should be shown, e.g. when an application dispatches commands from the first
+/// positional argument and wants to list the likely commands along with the full set:
+///
+/// ```
+/// use onlyargs::suggest::similar;
+///
+/// let commands = ["build", "bench", "check", "clean"];
+/// let arg = "bnch";
+///
+/// let message = format!(
+///     "Unknown command: {arg:?}. Similar commands: {}. Valid commands: {}",
+///     similar(arg, &commands).join(", "),
+///     commands.join(", "),
+/// );
+///
+/// assert_eq!(
+///     message,
+///     r#"Unknown command: "bnch". Similar commands: bench. Valid commands: build, bench, check, clean"#,
+/// );
+/// ```
+#[must_use]
+pub fn similar<'a>(arg: &str, candidates: &[&'a str]) -> Vec<&'a str> {
+    let max_distance = (arg.chars().count() / 3).max(1);
+
+    let mut similar = candidates
+        .iter()
+        .map(|&candidate| (edit_distance(arg, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect::<Vec<_>>();
+    similar.sort_by_key(|&(distance, _)| distance);
+
+    similar
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
@@ -1,10 +1,33 @@
-use crate::CliError;
-use std::ffi::OsString;
+use crate::{CliError, Occurrences};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env;
+use std::error::Error;
+use std::ffi::{CString, OsStr, OsString};
 use std::num::{ParseFloatError, ParseIntError};
 use std::path::PathBuf;
 use std::str::FromStr;
+#[cfg(feature = "semver")]
+use std::sync::Arc;
+use std::time::Duration;
 
 /// An extension trait for `Option<OsString>` that provides some parsers that are useful for CLIs.
+///
+/// The trait is also implemented for `OsString`, and for the borrowed `&OsStr` and
+/// `Option<&OsStr>`. The borrowed implementations only copy the argument when the parsed value
+/// owns it, like a `String`, or when reporting an error.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::traits::*;
+/// use std::ffi::OsStr;
+///
+/// let args = ["--width", "42"].map(OsStr::new);
+///
+/// assert_eq!(args.get(1).copied().parse_int::<u32, _>("--width")?, 42);
+/// assert!(args.get(2).copied().parse_int::<u32, _>("--width").is_err());
+/// # Ok::<_, onlyargs::CliError>(())
+/// ```
 pub trait ArgExt {
     /// Parse an argument into a `String`.
     ///
@@ -15,6 +38,47 @@ pub trait ArgExt {
     where
         N: Into<String>;
 
+    /// Parse an argument into a `String` that is not empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or empty or only whitespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// assert_eq!(OsString::from(" Ferris ").parse_non_empty_str("--name")?, " Ferris ");
+    /// assert!(OsString::from("  ").parse_non_empty_str("--name").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_non_empty_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a `String` without leading and trailing whitespace, that is not
+    /// empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or empty or only whitespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// assert_eq!(OsString::from(" Ferris ").parse_trimmed_str("--name")?, "Ferris");
+    /// assert!(OsString::from("").parse_trimmed_str("--name").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_trimmed_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>;
+
     /// Parse an argument into a `PathBuf`.
     ///
     /// # Errors
@@ -24,6 +88,83 @@ pub trait ArgExt {
     where
         N: Into<String>;
 
+    /// Parse an argument into a `PathBuf` that exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, or the path does not exist.
+    fn parse_existing_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a `PathBuf` that is an existing file.
+    ///
+    /// Symbolic links are followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, the path does not exist, or it is not a file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use onlyargs::CliError;
+    /// use std::ffi::OsString;
+    ///
+    /// assert!(OsString::from("Cargo.toml").parse_existing_file("--config").is_ok());
+    /// assert!(matches!(
+    ///     OsString::from("src").parse_existing_file("--config"),
+    ///     Err(CliError::NotAFile { .. }),
+    /// ));
+    /// assert!(matches!(
+    ///     OsString::from("missing.toml").parse_existing_file("--config"),
+    ///     Err(CliError::PathNotFound { .. }),
+    /// ));
+    /// ```
+    fn parse_existing_file<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a `PathBuf` that is an existing directory.
+    ///
+    /// Symbolic links are followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, the path does not exist, or it is not a directory.
+    fn parse_existing_dir<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a `PathBuf`, replacing a leading `~` with the home directory.
+    ///
+    /// Shells do not expand `~` in quoted values, like `--config "~/app.toml"`. Only `~` alone or
+    /// followed by a separator is expanded; `~user` paths are left as they are. The home directory
+    /// is `HOME`, or `USERPROFILE` on Windows. The path is not changed if it is not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    /// use std::path::Path;
+    ///
+    /// let path = OsString::from("~/app.toml").parse_expanded_path("--config")?;
+    /// assert!(path.ends_with("app.toml"));
+    ///
+    /// let path = OsString::from("~ferris/app.toml").parse_expanded_path("--config")?;
+    /// assert_eq!(path, Path::new("~ferris/app.toml"));
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_expanded_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>;
+
     /// Parse an argument into an `OsString`.
     ///
     /// # Errors
@@ -33,6 +174,28 @@ pub trait ArgExt {
     where
         N: Into<String>;
 
+    /// Parse an argument into a `CString`, for passing to C functions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or contains a NUL byte.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// let name = OsString::from("eth0").parse_cstring("--interface")?;
+    ///
+    /// assert_eq!(name.as_bytes(), b"eth0");
+    /// assert!(OsString::from("eth\0").parse_cstring("--interface").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_cstring<N>(self, name: N) -> Result<CString, CliError>
+    where
+        N: Into<String>;
+
     /// Parse an argument into a primitive integer.
     ///
     /// # Errors
@@ -52,6 +215,272 @@ pub trait ArgExt {
     where
         N: Into<String>,
         T: FromStr<Err = ParseFloatError>;
+
+    /// Parse an argument into a `bool`.
+    ///
+    /// Accepts `true`, `yes`, and `1` for `true`, and `false`, `no`, and `0` for `false`, ignoring
+    /// ASCII case.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None` or not a valid boolean.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// assert_eq!(OsString::from("yes").parse_bool("--color")?, true);
+    /// assert_eq!(OsString::from("FALSE").parse_bool("--color")?, false);
+    /// assert!(OsString::from("maybe").parse_bool("--color").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_bool<N>(self, name: N) -> Result<bool, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a single `char`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None` or not exactly one character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// assert_eq!(OsString::from(",").parse_char("--delimiter")?, ',');
+    /// assert!(OsString::from("::").parse_char("--delimiter").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_char<N>(self, name: N) -> Result<char, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a `Duration`.
+    ///
+    /// The value is a whole number followed by a unit: `ns`, `us`, `ms`, `s`, `m`, `h`, or `d`. A
+    /// number without a unit is in seconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or not a valid duration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    /// use std::time::Duration;
+    ///
+    /// assert_eq!(OsString::from("30s").parse_duration("--timeout")?, Duration::from_secs(30));
+    /// assert_eq!(OsString::from("5m").parse_duration("--timeout")?, Duration::from_secs(300));
+    /// assert_eq!(OsString::from("250ms").parse_duration("--timeout")?, Duration::from_millis(250));
+    /// assert!(OsString::from("soon").parse_duration("--timeout").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_duration<N>(self, name: N) -> Result<Duration, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a size in bytes.
+    ///
+    /// The value is a whole number followed by an optional unit, ignoring ASCII case:
+    ///
+    /// - `b` or no unit for bytes.
+    /// - `k`, `m`, `g`, and `t`, or `kib`, `mib`, `gib`, and `tib`, for powers of 1024.
+    /// - `kb`, `mb`, `gb`, and `tb` for powers of 1000.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, not a valid size, or larger than
+    /// `u64::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// assert_eq!(OsString::from("4k").parse_size("--buffer")?, 4096);
+    /// assert_eq!(OsString::from("10MiB").parse_size("--buffer")?, 10 * 1024 * 1024);
+    /// assert_eq!(OsString::from("2kB").parse_size("--buffer")?, 2000);
+    /// assert!(OsString::from("lots").parse_size("--buffer").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_size<N>(self, name: N) -> Result<u64, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a `Url`.
+    ///
+    /// Requires the `url` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or not a valid absolute URL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// let url = OsString::from("https://example.com/api").parse_url("--endpoint")?;
+    ///
+    /// assert_eq!(url.host_str(), Some("example.com"));
+    /// assert!(OsString::from("example.com").parse_url("--endpoint").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    #[cfg(feature = "url")]
+    fn parse_url<N>(self, name: N) -> Result<url::Url, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a `Uuid`.
+    ///
+    /// Requires the `uuid` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or not a valid UUID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// let id = OsString::from("67e55044-10b1-426f-9247-bb680e5fe0c8").parse_uuid("--id")?;
+    ///
+    /// assert_eq!(id.get_version_num(), 4);
+    /// assert!(OsString::from("42").parse_uuid("--id").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    #[cfg(feature = "uuid")]
+    fn parse_uuid<N>(self, name: N) -> Result<uuid::Uuid, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument into a semantic `Version`.
+    ///
+    /// Requires the `semver` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or not a valid semantic version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// let version = OsString::from("1.2.3-beta.1").parse_version("--min-version")?;
+    ///
+    /// assert_eq!((version.major, version.minor, version.patch), (1, 2, 3));
+    /// assert!(OsString::from("1.2").parse_version("--min-version").is_err());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    #[cfg(feature = "semver")]
+    fn parse_version<N>(self, name: N) -> Result<semver::Version, CliError>
+    where
+        N: Into<String>;
+
+    /// Parse an argument with a custom parser.
+    ///
+    /// The parser receives the argument as a `&str`. Its errors are reported as
+    /// [`CliError::Custom`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or rejected by the parser.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    ///
+    /// let size = OsString::from("3x4").parse_with("--size", |value| {
+    ///     let (width, height) = value.split_once('x').ok_or("expected WIDTHxHEIGHT")?;
+    ///
+    ///     Ok::<_, Box<dyn std::error::Error + Send + Sync>>((width.parse()?, height.parse()?))
+    /// })?;
+    ///
+    /// assert_eq!(size, (3_u32, 4_u32));
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_with<T, E, N, F>(self, name: N, parser: F) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Into<Box<dyn Error + Send + Sync>>;
+
+    /// Parse an argument into one of several values, looked up by name in a table.
+    ///
+    /// Names are matched ignoring ASCII case. When nothing matches, the error lists every name in
+    /// the table as a possible value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or not a name in the table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use onlyargs::CliError;
+    /// use std::ffi::OsString;
+    ///
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Format {
+    ///     Json,
+    ///     Toml,
+    /// }
+    ///
+    /// let formats = [("json", Format::Json), ("toml", Format::Toml)];
+    ///
+    /// assert_eq!(OsString::from("JSON").parse_enum("--format", &formats)?, Format::Json);
+    /// assert_eq!(
+    ///     OsString::from("xml").parse_enum("--format", &formats).unwrap_err().to_string(),
+    ///     "Invalid value for argument `--format`: value `xml`, possible values: json, toml",
+    /// );
+    /// # Ok::<_, CliError>(())
+    /// ```
+    fn parse_enum<T, N>(self, name: N, values: &[(&'static str, T)]) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: Clone;
+
+    /// Parse an argument into any type that implements [`FromStr`].
+    ///
+    /// Errors from [`FromStr`] are reported as [`CliError::Custom`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the argument is `None`, not valid UTF-8, or cannot be parsed to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::ffi::OsString;
+    /// use std::net::IpAddr;
+    ///
+    /// let addr = OsString::from("127.0.0.1").parse_from_str::<IpAddr, _>("--addr")?;
+    ///
+    /// assert!(addr.is_loopback());
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
+    fn parse_from_str<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>;
 }
 
 /// An extension trait for required arguments.
@@ -63,9 +492,25 @@ pub trait RequiredArgExt {
 
     /// Unwrap an argument that is required by the CLI.
     ///
+    /// Collections like `Vec`, `HashMap`, and `BTreeSet` are required to have at least one item.
+    ///
     /// # Errors
     ///
-    /// Returns `Err` if the argument is `None`.
+    /// Returns `Err` if the argument is `None`, or the collection is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::traits::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut defines = HashMap::new();
+    /// assert!(defines.clone().required("--define").is_err());
+    ///
+    /// defines.insert("DEBUG", "1");
+    /// assert_eq!(defines.required("--define")?.len(), 1);
+    /// # Ok::<_, onlyargs::CliError>(())
+    /// ```
     fn required<N>(self, name: N) -> Result<Self::Inner, CliError>
     where
         N: Into<String>;
@@ -77,9 +522,34 @@ impl ArgExt for Option<OsString> {
         N: Into<String>,
     {
         let name = name.into();
-        self.ok_or_else(|| CliError::MissingValue(name.clone()))?
-            .into_string()
-            .map_err(|err| CliError::ParseStrError(name, err))
+        self.ok_or_else(|| CliError::MissingValue {
+            argument: name.clone(),
+        })?
+        .into_string()
+        .map_err(|value| CliError::ParseStrError {
+            argument: name,
+            value,
+        })
+    }
+
+    fn parse_non_empty_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string, name))
+    }
+
+    fn parse_trimmed_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string.trim().to_string(), name))
     }
 
     fn parse_path<N>(self, name: N) -> Result<PathBuf, CliError>
@@ -87,68 +557,66 @@ impl ArgExt for Option<OsString> {
         N: Into<String>,
     {
         Ok(self
-            .ok_or_else(|| CliError::MissingValue(name.into()))?
+            .ok_or_else(|| CliError::MissingValue {
+                argument: name.into(),
+            })?
             .into())
     }
 
-    fn parse_osstr<N>(self, name: N) -> Result<OsString, CliError>
+    fn parse_expanded_path<N>(self, name: N) -> Result<PathBuf, CliError>
     where
         N: Into<String>,
     {
-        self.ok_or_else(|| CliError::MissingValue(name.into()))
+        self.parse_path(name).map(expand_tilde)
     }
 
-    fn parse_int<T, N>(self, name: N) -> Result<T, CliError>
+    fn parse_existing_path<N>(self, name: N) -> Result<PathBuf, CliError>
     where
         N: Into<String>,
-        T: FromStr<Err = ParseIntError>,
     {
         let name = name.into();
 
-        self.clone().parse_str(&name).and_then(|string| {
-            string
-                .parse::<T>()
-                .map_err(|err| CliError::ParseIntError(name, self.unwrap(), err))
-        })
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Any))
     }
 
-    fn parse_float<T, N>(self, name: N) -> Result<T, CliError>
+    fn parse_existing_file<N>(self, name: N) -> Result<PathBuf, CliError>
     where
         N: Into<String>,
-        T: FromStr<Err = ParseFloatError>,
     {
         let name = name.into();
 
-        self.clone().parse_str(&name).and_then(|string| {
-            string
-                .parse::<T>()
-                .map_err(|err| CliError::ParseFloatError(name, self.unwrap(), err))
-        })
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::File))
     }
-}
 
-impl ArgExt for OsString {
-    fn parse_str<N>(self, name: N) -> Result<String, CliError>
+    fn parse_existing_dir<N>(self, name: N) -> Result<PathBuf, CliError>
     where
         N: Into<String>,
     {
         let name = name.into();
-        self.into_string()
-            .map_err(|err| CliError::ParseStrError(name, err))
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Dir))
     }
 
-    fn parse_path<N>(self, _name: N) -> Result<PathBuf, CliError>
+    fn parse_osstr<N>(self, name: N) -> Result<OsString, CliError>
     where
         N: Into<String>,
     {
-        Ok(self.into())
+        self.ok_or_else(|| CliError::MissingValue {
+            argument: name.into(),
+        })
     }
 
-    fn parse_osstr<N>(self, _name: N) -> Result<OsString, CliError>
+    fn parse_cstring<N>(self, name: N) -> Result<CString, CliError>
     where
         N: Into<String>,
     {
-        Ok(self)
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| c_string(string, name))
     }
 
     fn parse_int<T, N>(self, name: N) -> Result<T, CliError>
@@ -161,7 +629,11 @@ impl ArgExt for OsString {
         self.clone().parse_str(&name).and_then(|string| {
             string
                 .parse::<T>()
-                .map_err(|err| CliError::ParseIntError(name, self, err))
+                .map_err(|source| CliError::ParseIntError {
+                    argument: name,
+                    value: self.unwrap(),
+                    source,
+                })
         })
     }
 
@@ -175,33 +647,1015 @@ impl ArgExt for OsString {
         self.clone().parse_str(&name).and_then(|string| {
             string
                 .parse::<T>()
-                .map_err(|err| CliError::ParseFloatError(name, self, err))
+                .map_err(|source| CliError::ParseFloatError {
+                    argument: name,
+                    value: self.unwrap(),
+                    source,
+                })
         })
     }
-}
 
-impl<T> RequiredArgExt for Option<T> {
-    type Inner = T;
+    fn parse_bool<N>(self, name: N) -> Result<bool, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
 
-    fn required<N>(self, name: N) -> Result<Self::Inner, CliError>
+        self.parse_osstr(&name)
+            .and_then(|value| value.parse_bool(name))
+    }
+
+    fn parse_char<N>(self, name: N) -> Result<char, CliError>
     where
         N: Into<String>,
     {
-        self.ok_or_else(|| CliError::MissingRequired(name.into()))
+        let name = name.into();
+
+        self.parse_osstr(&name)
+            .and_then(|value| value.parse_char(name))
     }
-}
 
-impl<T> RequiredArgExt for Vec<T> {
-    type Inner = Vec<T>;
+    fn parse_duration<N>(self, name: N) -> Result<Duration, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_with(name, duration)
+    }
 
-    fn required<N>(self, name: N) -> Result<Self::Inner, CliError>
+    fn parse_size<N>(self, name: N) -> Result<u64, CliError>
     where
         N: Into<String>,
     {
-        if self.is_empty() {
-            Err(CliError::MissingRequired(name.into()))
-        } else {
-            Ok(self)
-        }
+        self.parse_with(name, size)
     }
-}
+
+    #[cfg(feature = "url")]
+    fn parse_url<N>(self, name: N) -> Result<url::Url, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_osstr(&name)
+            .and_then(|value| value.parse_url(name))
+    }
+
+    #[cfg(feature = "uuid")]
+    fn parse_uuid<N>(self, name: N) -> Result<uuid::Uuid, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_osstr(&name)
+            .and_then(|value| value.parse_uuid(name))
+    }
+
+    #[cfg(feature = "semver")]
+    fn parse_version<N>(self, name: N) -> Result<semver::Version, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_osstr(&name)
+            .and_then(|value| value.parse_version(name))
+    }
+
+    fn parse_with<T, E, N, F>(self, name: N, parser: F) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let name = name.into();
+
+        self.parse_osstr(&name)
+            .and_then(|value| value.parse_with(name, parser))
+    }
+
+    fn parse_enum<T, N>(self, name: N, values: &[(&'static str, T)]) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: Clone,
+    {
+        let name = name.into();
+
+        self.parse_osstr(&name)
+            .and_then(|value| value.parse_enum(name, values))
+    }
+
+    fn parse_from_str<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.parse_with(name, str::parse)
+    }
+}
+
+impl ArgExt for OsString {
+    fn parse_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        self.into_string().map_err(|value| CliError::ParseStrError {
+            argument: name,
+            value,
+        })
+    }
+
+    fn parse_non_empty_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string, name))
+    }
+
+    fn parse_trimmed_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string.trim().to_string(), name))
+    }
+
+    fn parse_path<N>(self, _name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        Ok(self.into())
+    }
+
+    fn parse_expanded_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_path(name).map(expand_tilde)
+    }
+
+    fn parse_existing_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Any))
+    }
+
+    fn parse_existing_file<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::File))
+    }
+
+    fn parse_existing_dir<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Dir))
+    }
+
+    fn parse_osstr<N>(self, _name: N) -> Result<OsString, CliError>
+    where
+        N: Into<String>,
+    {
+        Ok(self)
+    }
+
+    fn parse_cstring<N>(self, name: N) -> Result<CString, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| c_string(string, name))
+    }
+
+    fn parse_int<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr<Err = ParseIntError>,
+    {
+        let name = name.into();
+
+        self.clone().parse_str(&name).and_then(|string| {
+            string
+                .parse::<T>()
+                .map_err(|source| CliError::ParseIntError {
+                    argument: name,
+                    value: self,
+                    source,
+                })
+        })
+    }
+
+    fn parse_float<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr<Err = ParseFloatError>,
+    {
+        let name = name.into();
+
+        self.clone().parse_str(&name).and_then(|string| {
+            string
+                .parse::<T>()
+                .map_err(|source| CliError::ParseFloatError {
+                    argument: name,
+                    value: self,
+                    source,
+                })
+        })
+    }
+
+    fn parse_bool<N>(self, name: N) -> Result<bool, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.clone().parse_str(&name).and_then(|string| {
+            let string = match string.to_ascii_lowercase().as_str() {
+                "yes" | "1" => "true".to_string(),
+                "no" | "0" => "false".to_string(),
+                lower => lower.to_string(),
+            };
+
+            string
+                .parse::<bool>()
+                .map_err(|source| CliError::ParseBoolError {
+                    argument: name,
+                    value: self,
+                    source,
+                })
+        })
+    }
+
+    fn parse_char<N>(self, name: N) -> Result<char, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.clone().parse_str(&name).and_then(|string| {
+            string
+                .parse::<char>()
+                .map_err(|source| CliError::ParseCharError {
+                    argument: name,
+                    value: self,
+                    source,
+                })
+        })
+    }
+
+    fn parse_duration<N>(self, name: N) -> Result<Duration, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_with(name, duration)
+    }
+
+    fn parse_size<N>(self, name: N) -> Result<u64, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_with(name, size)
+    }
+
+    #[cfg(feature = "url")]
+    fn parse_url<N>(self, name: N) -> Result<url::Url, CliError>
+    where
+        N: Into<String>,
+    {
+        self.as_os_str().parse_url(name)
+    }
+
+    #[cfg(feature = "uuid")]
+    fn parse_uuid<N>(self, name: N) -> Result<uuid::Uuid, CliError>
+    where
+        N: Into<String>,
+    {
+        self.as_os_str().parse_uuid(name)
+    }
+
+    #[cfg(feature = "semver")]
+    fn parse_version<N>(self, name: N) -> Result<semver::Version, CliError>
+    where
+        N: Into<String>,
+    {
+        self.as_os_str().parse_version(name)
+    }
+
+    fn parse_with<T, E, N, F>(self, name: N, parser: F) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| parser(&string).map_err(|err| CliError::custom(name, err)))
+    }
+
+    fn parse_enum<T, N>(self, name: N, values: &[(&'static str, T)]) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: Clone,
+    {
+        self.as_os_str().parse_enum(name, values)
+    }
+
+    fn parse_from_str<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.parse_with(name, str::parse)
+    }
+}
+
+impl ArgExt for Option<&OsStr> {
+    fn parse_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_str(name))
+    }
+
+    fn parse_non_empty_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string, name))
+    }
+
+    fn parse_trimmed_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string.trim().to_string(), name))
+    }
+
+    fn parse_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        value_of(self, name).map(PathBuf::from)
+    }
+
+    fn parse_expanded_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_path(name).map(expand_tilde)
+    }
+
+    fn parse_existing_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Any))
+    }
+
+    fn parse_existing_file<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::File))
+    }
+
+    fn parse_existing_dir<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Dir))
+    }
+
+    fn parse_osstr<N>(self, name: N) -> Result<OsString, CliError>
+    where
+        N: Into<String>,
+    {
+        value_of(self, name).map(OsStr::to_os_string)
+    }
+
+    fn parse_cstring<N>(self, name: N) -> Result<CString, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| c_string(string, name))
+    }
+
+    fn parse_int<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr<Err = ParseIntError>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_int(name))
+    }
+
+    fn parse_float<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr<Err = ParseFloatError>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_float(name))
+    }
+
+    fn parse_bool<N>(self, name: N) -> Result<bool, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_bool(name))
+    }
+
+    fn parse_char<N>(self, name: N) -> Result<char, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_char(name))
+    }
+
+    fn parse_duration<N>(self, name: N) -> Result<Duration, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_with(name, duration)
+    }
+
+    fn parse_size<N>(self, name: N) -> Result<u64, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_with(name, size)
+    }
+
+    #[cfg(feature = "url")]
+    fn parse_url<N>(self, name: N) -> Result<url::Url, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_url(name))
+    }
+
+    #[cfg(feature = "uuid")]
+    fn parse_uuid<N>(self, name: N) -> Result<uuid::Uuid, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_uuid(name))
+    }
+
+    #[cfg(feature = "semver")]
+    fn parse_version<N>(self, name: N) -> Result<semver::Version, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_version(name))
+    }
+
+    fn parse_with<T, E, N, F>(self, name: N, parser: F) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_with(name, parser))
+    }
+
+    fn parse_enum<T, N>(self, name: N, values: &[(&'static str, T)]) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: Clone,
+    {
+        let name = name.into();
+
+        value_of(self, &name).and_then(|value| value.parse_enum(name, values))
+    }
+
+    fn parse_from_str<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.parse_with(name, str::parse)
+    }
+}
+
+// Reject values that are empty or only whitespace.
+fn non_empty(string: String, name: String) -> Result<String, CliError> {
+    if string.trim().is_empty() {
+        Err(CliError::custom(name, "value must not be empty"))
+    } else {
+        Ok(string)
+    }
+}
+
+fn c_string(string: String, name: String) -> Result<CString, CliError> {
+    CString::new(string).map_err(|_| CliError::custom(name, "value must not contain a NUL byte"))
+}
+
+// Replace a leading `~` component with the home directory.
+fn expand_tilde(path: PathBuf) -> PathBuf {
+    let home = env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" })
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from);
+
+    match (home, path.strip_prefix("~")) {
+        (Some(home), Ok(rest)) if rest.as_os_str().is_empty() => home,
+        (Some(home), Ok(rest)) => home.join(rest),
+        _ => path,
+    }
+}
+
+// The kind of path that `existing` accepts.
+#[derive(Clone, Copy)]
+enum PathKind {
+    Any,
+    File,
+    Dir,
+}
+
+// Check that a path exists, and that it is the expected kind.
+fn existing(path: PathBuf, name: String, kind: PathKind) -> Result<PathBuf, CliError> {
+    match kind {
+        _ if !path.exists() => Err(CliError::PathNotFound {
+            argument: name,
+            path,
+        }),
+        PathKind::File if !path.is_file() => Err(CliError::NotAFile {
+            argument: name,
+            path,
+        }),
+        PathKind::Dir if !path.is_dir() => Err(CliError::NotADirectory {
+            argument: name,
+            path,
+        }),
+        _ => Ok(path),
+    }
+}
+
+// Split a value like `250ms` into its number and unit.
+fn split_unit(value: &str) -> (&str, &str) {
+    value.split_at(
+        value
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(value.len()),
+    )
+}
+
+// Parse a duration for `ArgExt::parse_duration`.
+fn duration(value: &str) -> Result<Duration, String> {
+    let error = || {
+        format!("invalid duration `{value}`, expected a number with a unit like `30s`, `5m`, or `250ms`")
+    };
+    let (number, unit) = split_unit(value);
+    let number = number.parse::<u64>().map_err(|_| error())?;
+
+    let seconds = |scale: u64| {
+        number
+            .checked_mul(scale)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("duration `{value}` is too long"))
+    };
+
+    match unit {
+        "ns" => Ok(Duration::from_nanos(number)),
+        "us" => Ok(Duration::from_micros(number)),
+        "ms" => Ok(Duration::from_millis(number)),
+        "" | "s" => seconds(1),
+        "m" => seconds(60),
+        "h" => seconds(60 * 60),
+        "d" => seconds(24 * 60 * 60),
+        _ => Err(error()),
+    }
+}
+
+// Parse a size in bytes for `ArgExt::parse_size`.
+fn size(value: &str) -> Result<u64, String> {
+    let error =
+        || format!("invalid size `{value}`, expected a number with a unit like `4k` or `10MiB`");
+    let (number, unit) = split_unit(value);
+    let number = number.parse::<u64>().map_err(|_| error())?;
+
+    let scale: u64 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        _ => return Err(error()),
+    };
+
+    number
+        .checked_mul(scale)
+        .ok_or_else(|| format!("size `{value}` is too large"))
+}
+
+// Get a borrowed argument, or report a missing value.
+fn value_of<N>(value: Option<&OsStr>, name: N) -> Result<&OsStr, CliError>
+where
+    N: Into<String>,
+{
+    value.ok_or_else(|| CliError::MissingValue {
+        argument: name.into(),
+    })
+}
+
+// Borrow a UTF-8 argument as `&str`, copying it only to report an error.
+fn str_of<'a>(value: &'a OsStr, name: &str) -> Result<&'a str, CliError> {
+    value.to_str().ok_or_else(|| CliError::ParseStrError {
+        argument: name.to_string(),
+        value: value.to_os_string(),
+    })
+}
+
+impl ArgExt for &OsStr {
+    fn parse_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        str_of(self, &name.into()).map(String::from)
+    }
+
+    fn parse_non_empty_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string, name))
+    }
+
+    fn parse_trimmed_str<N>(self, name: N) -> Result<String, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| non_empty(string.trim().to_string(), name))
+    }
+
+    fn parse_path<N>(self, _name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        Ok(self.into())
+    }
+
+    fn parse_expanded_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_path(name).map(expand_tilde)
+    }
+
+    fn parse_existing_path<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Any))
+    }
+
+    fn parse_existing_file<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::File))
+    }
+
+    fn parse_existing_dir<N>(self, name: N) -> Result<PathBuf, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_path(&name)
+            .and_then(|path| existing(path, name, PathKind::Dir))
+    }
+
+    fn parse_osstr<N>(self, _name: N) -> Result<OsString, CliError>
+    where
+        N: Into<String>,
+    {
+        Ok(self.to_os_string())
+    }
+
+    fn parse_cstring<N>(self, name: N) -> Result<CString, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        self.parse_str(&name)
+            .and_then(|string| c_string(string, name))
+    }
+
+    fn parse_int<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr<Err = ParseIntError>,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            string
+                .parse::<T>()
+                .map_err(|source| CliError::ParseIntError {
+                    argument: name,
+                    value: self.to_os_string(),
+                    source,
+                })
+        })
+    }
+
+    fn parse_float<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr<Err = ParseFloatError>,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            string
+                .parse::<T>()
+                .map_err(|source| CliError::ParseFloatError {
+                    argument: name,
+                    value: self.to_os_string(),
+                    source,
+                })
+        })
+    }
+
+    fn parse_bool<N>(self, name: N) -> Result<bool, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            let string = match string.to_ascii_lowercase().as_str() {
+                "yes" | "1" => "true".to_string(),
+                "no" | "0" => "false".to_string(),
+                lower => lower.to_string(),
+            };
+
+            string
+                .parse::<bool>()
+                .map_err(|source| CliError::ParseBoolError {
+                    argument: name,
+                    value: self.to_os_string(),
+                    source,
+                })
+        })
+    }
+
+    fn parse_char<N>(self, name: N) -> Result<char, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            string
+                .parse::<char>()
+                .map_err(|source| CliError::ParseCharError {
+                    argument: name,
+                    value: self.to_os_string(),
+                    source,
+                })
+        })
+    }
+
+    fn parse_duration<N>(self, name: N) -> Result<Duration, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_with(name, duration)
+    }
+
+    fn parse_size<N>(self, name: N) -> Result<u64, CliError>
+    where
+        N: Into<String>,
+    {
+        self.parse_with(name, size)
+    }
+
+    #[cfg(feature = "url")]
+    fn parse_url<N>(self, name: N) -> Result<url::Url, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            string.parse().map_err(|source| CliError::ParseUrlError {
+                argument: name,
+                value: self.to_os_string(),
+                source,
+            })
+        })
+    }
+
+    #[cfg(feature = "uuid")]
+    fn parse_uuid<N>(self, name: N) -> Result<uuid::Uuid, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            string.parse().map_err(|source| CliError::ParseUuidError {
+                argument: name,
+                value: self.to_os_string(),
+                source,
+            })
+        })
+    }
+
+    #[cfg(feature = "semver")]
+    fn parse_version<N>(self, name: N) -> Result<semver::Version, CliError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            string
+                .parse()
+                .map_err(|source| CliError::ParseVersionError {
+                    argument: name,
+                    value: self.to_os_string(),
+                    source: Arc::new(source),
+                })
+        })
+    }
+
+    fn parse_with<T, E, N, F>(self, name: N, parser: F) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let name = name.into();
+
+        str_of(self, &name)
+            .and_then(|string| parser(string).map_err(|err| CliError::custom(name, err)))
+    }
+
+    fn parse_enum<T, N>(self, name: N, values: &[(&'static str, T)]) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: Clone,
+    {
+        let name = name.into();
+
+        str_of(self, &name).and_then(|string| {
+            values
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(string))
+                .map(|(_, value)| value.clone())
+                .ok_or_else(|| CliError::InvalidChoice {
+                    argument: name,
+                    value: self.to_os_string(),
+                    choices: values.iter().map(|(key, _)| *key).collect(),
+                })
+        })
+    }
+
+    fn parse_from_str<T, N>(self, name: N) -> Result<T, CliError>
+    where
+        N: Into<String>,
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.parse_with(name, str::parse)
+    }
+}
+
+impl<T> RequiredArgExt for Option<T> {
+    type Inner = T;
+
+    fn required<N>(self, name: N) -> Result<Self::Inner, CliError>
+    where
+        N: Into<String>,
+    {
+        self.ok_or_else(|| CliError::MissingRequired {
+            argument: name.into(),
+        })
+    }
+}
+
+impl<T> RequiredArgExt for Vec<T> {
+    type Inner = Vec<T>;
+
+    fn required<N>(self, name: N) -> Result<Self::Inner, CliError>
+    where
+        N: Into<String>,
+    {
+        if self.is_empty() {
+            Err(CliError::MissingRequired {
+                argument: name.into(),
+            })
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+macro_rules! impl_required_collection {
+    ($($ty:ident<$($param:ident),*>),*) => {
+        $(
+            impl<$($param),*> RequiredArgExt for $ty<$($param),*> {
+                type Inner = Self;
+
+                fn required<N>(self, name: N) -> Result<Self::Inner, CliError>
+                where
+                    N: Into<String>,
+                {
+                    if self.is_empty() {
+                        Err(CliError::MissingRequired {
+                            argument: name.into(),
+                        })
+                    } else {
+                        Ok(self)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_required_collection!(
+    HashMap<K, V, S>,
+    BTreeMap<K, V>,
+    HashSet<T, S>,
+    BTreeSet<T>,
+    Occurrences<T>
+);
@@ -0,0 +1,237 @@
+//! Ready-made argument types for common command line conventions.
+//!
+//! `onlyargs_derive` recognizes these types as fields of the argument struct and generates the
+//! arguments they stand for.
+
+use crate::help::Output;
+use std::env;
+use std::fmt::{self, Display};
+
+/// How chatty the application should be, from repeated `-v/--verbose` and `-q/--quiet` flags.
+///
+/// A field of this type adds both flags to the argument struct. Each flag can be repeated, and short
+/// flags can be grouped, like `-vv`. The [`level()`](Self::level) is the number of `--verbose`
+/// flags minus the number of `--quiet` flags.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::types::Verbosity;
+///
+/// let verbosity = Verbosity::new(2, 0);
+/// assert_eq!(verbosity.level(), 2);
+///
+/// let verbosity = Verbosity::new(0, 1);
+/// assert_eq!(verbosity.level(), -1);
+/// assert!(verbosity.is_quiet());
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Verbosity {
+    /// The number of times `-v/--verbose` was given.
+    pub verbose: u8,
+
+    /// The number of times `-q/--quiet` was given.
+    pub quiet: u8,
+}
+
+impl Verbosity {
+    /// Create a verbosity from the number of `--verbose` and `--quiet` flags.
+    #[must_use]
+    pub fn new(verbose: u8, quiet: u8) -> Self {
+        Self { verbose, quiet }
+    }
+
+    /// The verbosity level. Zero is the default, positive levels are more verbose, and negative
+    /// levels are more quiet.
+    #[must_use]
+    pub fn level(&self) -> i16 {
+        i16::from(self.verbose) - i16::from(self.quiet)
+    }
+
+    /// Check whether the level is below the default.
+    #[must_use]
+    pub fn is_quiet(&self) -> bool {
+        self.level() < 0
+    }
+
+    /// The `log` level filter for this verbosity.
+    ///
+    /// The default level is `Warn`. Each `--verbose` raises it through `Info`, `Debug`, and
+    /// `Trace`, and each `--quiet` lowers it through `Error` and `Off`.
+    ///
+    /// Requires the `log` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    /// use onlyargs::types::Verbosity;
+    ///
+    /// assert_eq!(Verbosity::default().log_level_filter(), LevelFilter::Warn);
+    /// assert_eq!(Verbosity::new(2, 0).log_level_filter(), LevelFilter::Debug);
+    /// assert_eq!(Verbosity::new(0, 5).log_level_filter(), LevelFilter::Off);
+    /// ```
+    #[cfg(feature = "log")]
+    #[must_use]
+    pub fn log_level_filter(&self) -> log::LevelFilter {
+        match self.level() {
+            i16::MIN..=-2 => log::LevelFilter::Off,
+            -1 => log::LevelFilter::Error,
+            0 => log::LevelFilter::Warn,
+            1 => log::LevelFilter::Info,
+            2 => log::LevelFilter::Debug,
+            3..=i16::MAX => log::LevelFilter::Trace,
+        }
+    }
+
+    /// The `tracing` level filter for this verbosity, with the same levels as
+    /// [`log_level_filter()`](Self::log_level_filter).
+    ///
+    /// Requires the `tracing-subscriber` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::types::Verbosity;
+    /// use tracing_subscriber::filter::LevelFilter;
+    ///
+    /// assert_eq!(Verbosity::default().tracing_level_filter(), LevelFilter::WARN);
+    /// assert_eq!(Verbosity::new(0, 1).tracing_level_filter(), LevelFilter::ERROR);
+    /// ```
+    #[cfg(feature = "tracing-subscriber")]
+    #[must_use]
+    pub fn tracing_level_filter(&self) -> tracing_subscriber::filter::LevelFilter {
+        use tracing_subscriber::filter::LevelFilter;
+
+        match self.level() {
+            i16::MIN..=-2 => LevelFilter::OFF,
+            -1 => LevelFilter::ERROR,
+            0 => LevelFilter::WARN,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            3..=i16::MAX => LevelFilter::TRACE,
+        }
+    }
+
+    /// Count the flags in a group of repeated short flags, like `-vvv`. Returns zero if the
+    /// argument is anything else.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use onlyargs::types::Verbosity;
+    ///
+    /// assert_eq!(Verbosity::count_short("-vvv", 'v'), 3);
+    /// assert_eq!(Verbosity::count_short("-vq", 'v'), 0);
+    /// assert_eq!(Verbosity::count_short("--verbose", 'v'), 0);
+    /// ```
+    #[must_use]
+    pub fn count_short(arg: &str, short: char) -> u8 {
+        match arg.strip_prefix('-') {
+            Some(shorts) if !shorts.is_empty() && shorts.chars().all(|ch| ch == short) => {
+                u8::try_from(shorts.chars().count()).unwrap_or(u8::MAX)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// When to color output, from a `--color <WHEN>` option.
+///
+/// A field of this type adds an option that accepts `auto`, `always`, or `never`, and defaults to
+/// `auto`.
+///
+/// # Example
+///
+/// ```
+/// use onlyargs::help::Output;
+/// use onlyargs::types::ColorChoice;
+///
+/// let color = ColorChoice::from_name("never").unwrap();
+///
+/// assert_eq!(color, ColorChoice::Never);
+/// assert!(!color.should_colorize(Output::Stdout));
+/// assert_eq!(ColorChoice::from_name("sometimes"), None);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Color output when the stream is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+
+    /// Always color output.
+    Always,
+
+    /// Never color output.
+    Never,
+}
+
+impl ColorChoice {
+    /// The names accepted on the command line.
+    pub const NAMES: [&'static str; 3] = ["auto", "always", "never"];
+
+    /// Parse a name like `auto`. Returns `None` if the name is not one of [`NAMES`](Self::NAMES).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Self::Auto),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    /// The name of this choice on the command line.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Always => "always",
+            Self::Never => "never",
+        }
+    }
+
+    /// Check whether output written to `stream` should be colored.
+    ///
+    /// With `Auto`, this returns `false` if the [`NO_COLOR`] environment variable is set to a
+    /// non-empty value, or if `stream` is not a terminal. Terminals are only detected with the
+    /// `color` or `pager` feature, which have a higher MSRV than the rest of the crate. Without
+    /// them, `Auto` never colors output.
+    ///
+    /// [`NO_COLOR`]: https://no-color.org/
+    #[must_use]
+    pub fn should_colorize(self, stream: Output) -> bool {
+        match self {
+            Self::Auto => {
+                env::var_os("NO_COLOR").map_or(true, |value| value.is_empty())
+                    && is_terminal(stream)
+            }
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// `IsTerminal` is the reason for the higher MSRV of the `color` and `pager` features.
+#[cfg(any(feature = "color", feature = "pager"))]
+#[clippy::msrv = "1.70"]
+pub(crate) fn is_terminal(stream: Output) -> bool {
+    use std::io::IsTerminal as _;
+
+    match stream {
+        Output::Stdout => std::io::stdout().is_terminal(),
+        Output::Stderr => std::io::stderr().is_terminal(),
+    }
+}
+
+// Terminals are not detected without the `color` or `pager` features.
+#[cfg(not(any(feature = "color", feature = "pager")))]
+pub(crate) fn is_terminal(_stream: Output) -> bool {
+    false
+}